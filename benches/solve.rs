@@ -1,6 +1,11 @@
 use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rayon::prelude::*;
 
-use sudokugen::{board::BoardSize, Board, Puzzle};
+use sudokugen::solver::generator::GeneratorOptions;
+use sudokugen::solver::{verify, SudokuSolver};
+use sudokugen::{board::BoardSize, solver::Solver, Board, Puzzle};
 
 fn solve_benchmark(c: &mut Criterion) {
     let table: Board =
@@ -15,19 +20,207 @@ fn solve_benchmark(c: &mut Criterion) {
             BatchSize::SmallInput,
         )
     });
+
+    // checking a solution only scans the board, it should be far cheaper than solving
+    let solution = table.solved_copy().unwrap();
+    c.bench_function("verify", |b| {
+        b.iter(|| verify(black_box(&table), black_box(&solution)))
+    });
+
+    c.bench_function("solve_dlx", |b| {
+        b.iter_batched(
+            || table.clone(),
+            |mut table| table.solve_dlx(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let table = Board::from_csv(
+        "\
+,,15,,,6,,10,,,,,,,,
+,,,3,16,,,,,,,6,14,,,
+,,7,6,,,,,,,4,,9,12,,
+9,,2,,4,,15,,,7,11,,,1,,
+,4,1,,,,,,11,,,13,,,,5
+,3,,2,,,8,,5,,,14,,11,1,
+,12,11,,,,13,1,16,,,,,,14,3
+,,,,12,5,,15,,10,,,,,,
+10,7,4,,,2,,,,,6,16,,,15,
+3,,,14,9,,,,,2,7,,4,,,
+,2,5,,,12,14,,,,3,15,13,6,16,8
+,,,,,,,8,12,13,5,1,7,,,
+,,,,3,7,,5,,,,,,,11,
+,,,,,9,,4,,,,,,15,,12
+,,,,,14,,11,,,,,6,4,5,9
+,,,,,,,,,,15,,1,16,13,2
+",
+    )
+    .unwrap();
+
+    c.bench_function("solve_16x16", |b| {
+        b.iter_batched(
+            || table.clone(),
+            |mut table| table.solve(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("solve_16x16_generic", |b| {
+        b.iter_batched(
+            || table.clone(),
+            |mut table| SudokuSolver::new(&mut table).solve(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn solve_batch_benchmark(c: &mut Criterion) {
+    let puzzles: Vec<Board> = (0..1000)
+        .into_par_iter()
+        .map(|_| Puzzle::generate(BoardSize::NineByNine).board().clone())
+        .collect();
+
+    let mut group = c.benchmark_group("solve_batch");
+    group.sample_size(20);
+
+    group.bench_function("board_solve", |b| {
+        b.iter_batched(
+            || puzzles.clone(),
+            |mut puzzles| {
+                for puzzle in puzzles.iter_mut() {
+                    puzzle.solve().unwrap();
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("reused_solver", |b| {
+        let mut solver = Solver::with_capacity(BoardSize::NineByNine);
+        b.iter_batched(
+            || puzzles.clone(),
+            |mut puzzles| {
+                for puzzle in puzzles.iter_mut() {
+                    solver.solve(puzzle).unwrap();
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("board_solve_dlx", |b| {
+        b.iter_batched(
+            || puzzles.clone(),
+            |mut puzzles| {
+                for puzzle in puzzles.iter_mut() {
+                    puzzle.solve_dlx().unwrap();
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+/// Solves the whole top95 set, a standard benchmark of hard puzzles for sudoku solvers.
+fn solve_top95_benchmark(c: &mut Criterion) {
+    let puzzles: Vec<Board> = include_str!("../tests/data/top95.txt")
+        .lines()
+        .map(|line| line.parse().unwrap())
+        .collect();
+
+    let mut group = c.benchmark_group("top95");
+    group.sample_size(10);
+
+    group.bench_function("board_solve", |b| {
+        b.iter_batched(
+            || puzzles.clone(),
+            |mut puzzles| {
+                for puzzle in puzzles.iter_mut() {
+                    puzzle.solve().unwrap();
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("board_solve_dlx", |b| {
+        b.iter_batched(
+            || puzzles.clone(),
+            |mut puzzles| {
+                for puzzle in puzzles.iter_mut() {
+                    puzzle.solve_dlx().unwrap();
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
 }
 
 fn generate_benchmark(c: &mut Criterion) {
     c.bench_function("generate", |b| {
         b.iter(|| Puzzle::generate(black_box(BoardSize::NineByNine)))
     });
+
+    let parallel = GeneratorOptions {
+        parallel_clue_removal: true,
+        ..Default::default()
+    };
+    c.bench_function("generate_parallel_clue_removal", |b| {
+        b.iter(|| Puzzle::generate_with_options(black_box(BoardSize::NineByNine), parallel.clone()))
+    });
+
+    // only puzzles that need guessing to be solved have something to check
+    let puzzle = (0..)
+        .map(|seed| {
+            Puzzle::generate_with_rng(BoardSize::NineByNine, &mut StdRng::seed_from_u64(seed))
+        })
+        .find(|puzzle| puzzle.alternative_branch_count() > 0)
+        .unwrap();
+    c.bench_function("is_solution_unique", |b| {
+        b.iter(|| black_box(&puzzle).is_solution_unique())
+    });
+}
+
+fn generate_many_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_100");
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            (0..100)
+                .map(|_| Puzzle::generate(black_box(BoardSize::NineByNine)))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("par_generate", |b| {
+        b.iter(|| {
+            Puzzle::par_generate(black_box(BoardSize::NineByNine))
+                .take_any(100)
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
 }
 
-criterion_group!(solve_bench, solve_benchmark);
+criterion_group!(
+    solve_bench,
+    solve_benchmark,
+    solve_batch_benchmark,
+    solve_top95_benchmark
+);
 criterion_group!(
     name = gen_bench;
     config = Criterion::default().sample_size(40);
     targets = generate_benchmark
 );
+criterion_group!(
+    name = gen_many_bench;
+    config = Criterion::default().sample_size(10);
+    targets = generate_many_benchmark
+);
 
-criterion_main!(solve_bench, gen_bench);
+criterion_main!(solve_bench, gen_bench, gen_many_bench);
@@ -0,0 +1,100 @@
+//! Generates a booklet of puzzles as plain text, with the solutions at the end.
+//!
+//! ```text
+//! cargo run --release --example print_book -- [count] [4x4|9x9|16x16] [seed] > book.txt
+//! ```
+//!
+//! Prints 10 9x9 puzzles by default, the same seed always prints the same booklet.
+
+use std::cmp::Reverse;
+use std::env;
+use std::process;
+use sudokugen::solver::generator::Difficulty;
+use sudokugen::{Board, BoardSize, Puzzle};
+
+const USAGE: &str = "Usage: print_book [count] [4x4|9x9|16x16] [seed]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let count: usize = parse_arg(args.first(), 10);
+    let board_size: BoardSize = parse_arg(args.get(1), BoardSize::NineByNine);
+    let generator = match args.get(2) {
+        Some(_) => Puzzle::generator_with_seed(board_size, parse_arg(args.get(2), 0)),
+        None => Puzzle::generator(board_size),
+    };
+
+    let mut puzzles: Vec<(Difficulty, Puzzle)> = generator
+        .take(count)
+        .map(|puzzle| (puzzle.difficulty(), puzzle))
+        .collect();
+    // easier puzzles first, the most clues first for the same difficulty
+    puzzles.sort_by_key(|(difficulty, puzzle)| (*difficulty, Reverse(puzzle.board().clue_count())));
+
+    println!("SUDOKU");
+    println!("{} puzzles of size {}", puzzles.len(), board_size);
+
+    for (idx, (difficulty, puzzle)) in puzzles.iter().enumerate() {
+        println!();
+        println!(
+            "Puzzle {} - {}, {} clues",
+            idx + 1,
+            difficulty_name(*difficulty),
+            puzzle.board().clue_count()
+        );
+        println!();
+        print_board(puzzle.board());
+    }
+
+    println!();
+    println!("SOLUTIONS");
+    for (idx, (_, puzzle)) in puzzles.iter().enumerate() {
+        println!();
+        println!("Solution {}", idx + 1);
+        println!();
+        print_board(puzzle.solution());
+    }
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<&String>, default: T) -> T {
+    match arg {
+        Some(arg) => arg.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid argument {}\n{}", arg, USAGE);
+            process::exit(2);
+        }),
+        None => default,
+    }
+}
+
+fn difficulty_name(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "easy",
+        Difficulty::Medium => "medium",
+        Difficulty::Hard => "hard",
+    }
+}
+
+/// Prints the board with lines between the squares, using letters for values above 9.
+fn print_board(board: &Board) {
+    let base_size = board.board_size().get_base_size();
+    let line = board.to_line_string();
+    let width = base_size * base_size;
+    let separator = vec!["-".repeat(base_size * 2 - 1); base_size].join("-+-");
+
+    for (row, values) in line.as_bytes().chunks(width).enumerate() {
+        if row > 0 && row % base_size == 0 {
+            println!("{}", separator);
+        }
+
+        let squares: Vec<String> = values
+            .chunks(base_size)
+            .map(|square| {
+                square
+                    .iter()
+                    .map(|value| (*value as char).to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect();
+        println!("{}", squares.join(" | "));
+    }
+}
@@ -0,0 +1,85 @@
+//! Solves every puzzle in a file, one per line, in parallel and prints how long it took.
+//!
+//! ```text
+//! cargo run --release --example solve_file -- tests/data/puzzles.txt
+//! ```
+//!
+//! Lines can use any of the formats accepted by [`Board`]'s `from_str`, empty lines are
+//! skipped. Lines that can't be parsed or solved are reported on stderr.
+
+use std::env;
+use std::fs;
+use std::process;
+use std::time::Instant;
+use sudokugen::solver::solve_all;
+use sudokugen::Board;
+
+fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: solve_file <path>");
+            process::exit(2);
+        }
+    };
+
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Could not read {}: {}", path, err);
+        process::exit(1);
+    });
+
+    let mut lines = Vec::new();
+    let mut boards = Vec::new();
+    let mut malformed = 0;
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match line.parse::<Board>() {
+            Ok(board) => {
+                lines.push(idx + 1);
+                boards.push(board);
+            }
+            Err(err) => {
+                malformed += 1;
+                eprintln!("Line {}: {}", idx + 1, err);
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let solutions = solve_all(boards.clone());
+    let elapsed = start.elapsed();
+
+    let mut solved = 0;
+    let mut unsolvable = 0;
+    for ((line, board), solution) in lines.iter().zip(&boards).zip(&solutions) {
+        match solution {
+            Ok(solution) if board.validate_solution(solution) => solved += 1,
+            Ok(_) => panic!("Line {}: the solver returned a wrong solution", line),
+            Err(err) => {
+                unsolvable += 1;
+                eprintln!("Line {}: {}", line, err);
+            }
+        }
+    }
+
+    let clues: usize = boards.iter().map(Board::clue_count).sum();
+    let seconds = elapsed.as_secs_f64();
+
+    println!("puzzles:    {}", boards.len() + malformed);
+    println!("solved:     {}", solved);
+    println!("unsolvable: {}", unsolvable);
+    println!("malformed:  {}", malformed);
+    if !boards.is_empty() {
+        println!(
+            "clues:      {:.1} on average",
+            clues as f64 / boards.len() as f64
+        );
+    }
+    println!("time:       {:.3}s", seconds);
+    if seconds > 0.0 {
+        println!("throughput: {:.0} puzzles/s", boards.len() as f64 / seconds);
+    }
+}
@@ -0,0 +1,80 @@
+#ifndef SUDOKUGEN_H
+#define SUDOKUGEN_H
+
+/* Generated with cbindgen, do not edit. Run `cbindgen --output include/sudokugen.h` to update. */
+
+#include <stddef.h>
+
+/**
+ * The call succeeded.
+ */
+#define SUDOKUGEN_OK 0
+
+/**
+ * The board has no solution.
+ */
+#define SUDOKUGEN_UNSOLVABLE 1
+
+/**
+ * The board couldn't be parsed.
+ */
+#define SUDOKUGEN_MALFORMED 2
+
+/**
+ * An output buffer is too small for the board and its NUL terminator.
+ */
+#define SUDOKUGEN_BUFFER_TOO_SMALL 3
+
+/**
+ * A pointer is null or an argument is out of range.
+ */
+#define SUDOKUGEN_INVALID_ARGUMENT 4
+
+/**
+ * The library panicked, this is a bug.
+ */
+#define SUDOKUGEN_PANIC 5
+
+/**
+ * Returns the message of the last error of a call made from this thread, or null if
+ * the last call succeeded.
+ *
+ * The message is owned by the library and is valid until the next call from this
+ * thread, copy it to keep it longer.
+ */
+const char *sudokugen_last_error_message(void);
+
+/**
+ * Solves the board in the NUL terminated string `board` and writes the solution to
+ * `out`, a buffer of `out_len` bytes. Separators are accepted in `board` same as
+ * parsing a [`Board`], the solution is written without them.
+ *
+ * Returns [`SUDOKUGEN_OK`] on success, [`SUDOKUGEN_MALFORMED`] if `board` can't be
+ * parsed, [`SUDOKUGEN_UNSOLVABLE`] if it has no solution and
+ * [`SUDOKUGEN_BUFFER_TOO_SMALL`] if the solution doesn't fit in `out`, in which case
+ * `out` is left untouched.
+ *
+ * # Safety
+ *
+ * `board` must be null or point to a NUL terminated string, `out` must be null or
+ * point to at least `out_len` writable bytes.
+ */
+int sudokugen_solve(const char *board, char *out, size_t out_len);
+
+/**
+ * Generates a new puzzle with a unique solution for a board `size` cells wide, 4, 9 or
+ * 16, writing the puzzle to `puzzle_out` and its solution to `solution_out`, two
+ * buffers of `len` bytes each.
+ *
+ * Returns [`SUDOKUGEN_OK`] on success, [`SUDOKUGEN_INVALID_ARGUMENT`] for any other
+ * `size` and [`SUDOKUGEN_BUFFER_TOO_SMALL`] if the boards don't fit in the buffers,
+ * in which case the buffers are left untouched.
+ *
+ * # Safety
+ *
+ * `puzzle_out` and `solution_out` must be null or each point to at least `len` writable
+ * bytes.
+ */
+int sudokugen_generate(int size, char *puzzle_out, char *solution_out, size_t len);
+
+#endif /* SUDOKUGEN_H */
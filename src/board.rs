@@ -8,43 +8,243 @@
 //! the [`cell_at`] method of the board instance is more convenient to address
 //! cells of a specific board.
 
-use std::collections::BTreeSet;
-use std::convert::TryInto;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::{Infallible, TryFrom, TryInto};
 use std::error;
 use std::fmt;
+use std::ops::Index;
 use std::str::FromStr;
 
+use crate::solver::Block;
 use error::Error;
 use fmt::Display;
 
 /// Represents the size of the board that sudukogen can work with.
-/// Currently only 4x4, 9x9, and 16x16 boards are allowed.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// 4x4, 9x9, and 16x16 boards with square boxes have their own variant, boards with
+/// rectangular boxes, like 6x6 boards with boxes of 2 lines by 3 columns, are
+/// [`Custom`](BoardSize::Custom) sizes created with [`BoardSize::custom`].
+///
+/// With the `serde` feature enabled board sizes are serialized as the
+/// name of the variant, e.g. `"NineByNine"`, and custom sizes as
+/// `{"Custom": {"box_rows": 2, "box_cols": 3}}`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum BoardSize {
     /// A board with 16 cells, in a 4 by 4 square
     FourByFour,
     /// A board with 81 cells, in a 9 by 9 square
     NineByNine,
-    /// A board with 337 cells, in a 16 by 16 square
+    /// A board with 256 cells, in a 16 by 16 square
     SixteenBySixteen,
+    /// A board whose boxes, also called squares, are `box_rows` lines by `box_cols`
+    /// columns, the board is `box_rows * box_cols` cells wide. Use [`BoardSize::custom`]
+    /// to create it, which checks the size is supported and returns the variants above
+    /// for square boxes.
+    Custom {
+        /// The number of lines in each box.
+        box_rows: usize,
+        /// The number of columns in each box.
+        box_cols: usize,
+    },
 }
 
 impl BoardSize {
+    /// Returns the size of a board with boxes of `box_rows` lines by `box_cols` columns,
+    /// one of the named variants if the boxes are 2x2, 3x3 or 4x4. Boards can be at most
+    /// 16 cells wide.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// let six = BoardSize::custom(2, 3).unwrap();
+    /// assert_eq!(six, BoardSize::Custom { box_rows: 2, box_cols: 3 });
+    /// assert_eq!(six.width(), 6);
+    ///
+    /// assert_eq!(BoardSize::custom(3, 3), Ok(BoardSize::NineByNine));
+    /// assert!(BoardSize::custom(4, 5).is_err());
+    /// ```
+    pub fn custom(box_rows: usize, box_cols: usize) -> Result<BoardSize, BoxSizeOutOfRangeError> {
+        match (box_rows, box_cols) {
+            (2, 2) => Ok(BoardSize::FourByFour),
+            (3, 3) => Ok(BoardSize::NineByNine),
+            (4, 4) => Ok(BoardSize::SixteenBySixteen),
+            _ if box_rows > 0
+                && box_cols > 0
+                && (2..=MAX_WIDTH).contains(&box_rows.saturating_mul(box_cols)) =>
+            {
+                Ok(BoardSize::Custom { box_rows, box_cols })
+            }
+            _ => Err(BoxSizeOutOfRangeError { box_rows, box_cols }),
+        }
+    }
+
     /// A sudoku board is a square of N by N squares, each of them composed of N by N cells
     /// the base size of a board is N. For instance in a 9 by 9 board, composed of 3 by 3 squares,
     /// each of them composed of 3 by 3 cells, the base size is 3.
+    ///
+    /// Boxes of [`Custom`](BoardSize::Custom) sizes are not squares, their base size is
+    /// the number of lines in each box, same as [`box_rows`](Self::box_rows).
     pub fn get_base_size(&self) -> usize {
+        self.box_rows()
+    }
+
+    /// Returns the number of lines in each box of the board.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(BoardSize::NineByNine.box_rows(), 3);
+    /// assert_eq!(BoardSize::custom(2, 3).unwrap().box_rows(), 2);
+    /// ```
+    pub fn box_rows(&self) -> usize {
         match self {
             Self::FourByFour => 2,
             Self::NineByNine => 3,
             Self::SixteenBySixteen => 4,
+            Self::Custom { box_rows, .. } => *box_rows,
+        }
+    }
+
+    /// Returns the number of columns in each box of the board.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(BoardSize::NineByNine.box_cols(), 3);
+    /// assert_eq!(BoardSize::custom(2, 3).unwrap().box_cols(), 3);
+    /// ```
+    pub fn box_cols(&self) -> usize {
+        match self {
+            Self::Custom { box_cols, .. } => *box_cols,
+            _ => self.box_rows(),
+        }
+    }
+
+    /// Returns the number of cells in each line, column and square of the board,
+    /// which is also the number of different values the board holds.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(BoardSize::NineByNine.width(), 9);
+    /// ```
+    pub fn width(&self) -> usize {
+        self.box_rows() * self.box_cols()
+    }
+
+    /// Returns the total number of cells in the board.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(BoardSize::NineByNine.cell_count(), 81);
+    /// ```
+    pub fn cell_count(&self) -> usize {
+        self.width().pow(2)
+    }
+
+    /// The size of the board turned by a quarter, where boxes have as many lines as
+    /// these have columns.
+    pub(crate) fn transposed(&self) -> BoardSize {
+        match *self {
+            Self::Custom { box_rows, box_cols } => Self::Custom {
+                box_rows: box_cols,
+                box_cols: box_rows,
+            },
+            board_size => board_size,
+        }
+    }
+
+    /// Returns the biggest value that can be placed in a cell of the board.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(BoardSize::SixteenBySixteen.max_value(), 16);
+    /// ```
+    pub fn max_value(&self) -> u8 {
+        self.width() as u8
+    }
+}
+
+impl fmt::Display for BoardSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width(), self.width())?;
+        if let Self::Custom { box_rows, box_cols } = self {
+            write!(f, " ({}x{} boxes)", box_rows, box_cols)?;
+        }
+        Ok(())
+    }
+}
+
+/// The widest board supported, values are stored in 16 bit candidate sets.
+pub(crate) const MAX_WIDTH: usize = 16;
+
+/// Error returned when a board size cannot be parsed from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBoardSizeError(String);
+
+impl Display for ParseBoardSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a valid board size, expected 4x4, 9x9, 16x16 or a width with \
+             its boxes like 6x6 (2x3 boxes)",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseBoardSizeError {}
+
+impl FromStr for BoardSize {
+    type Err = ParseBoardSizeError;
+
+    /// Parses a board size in the same format it's displayed, `4x4`, `9x9` or `16x16`, or
+    /// the width followed by the size of the boxes for [`Custom`](BoardSize::Custom) sizes.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    ///
+    /// let board_size: BoardSize = "9x9".parse().unwrap();
+    /// assert_eq!(board_size, BoardSize::NineByNine);
+    /// assert_eq!(board_size.to_string(), "9x9");
+    ///
+    /// let board_size: BoardSize = "6x6 (2x3 boxes)".parse().unwrap();
+    /// assert_eq!(board_size, BoardSize::custom(2, 3).unwrap());
+    /// assert_eq!(board_size.to_string(), "6x6 (2x3 boxes)");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseBoardSizeError(s.to_string());
+
+        match s.trim() {
+            "4x4" => return Ok(BoardSize::FourByFour),
+            "9x9" => return Ok(BoardSize::NineByNine),
+            "16x16" => return Ok(BoardSize::SixteenBySixteen),
+            _ => {}
+        }
+
+        let (width, boxes) = s
+            .trim()
+            .strip_suffix(" boxes)")
+            .and_then(|rest| rest.split_once(" ("))
+            .ok_or_else(err)?;
+        let (box_rows, box_cols) = boxes.split_once('x').ok_or_else(err)?;
+        let box_rows = box_rows.parse().map_err(|_| err())?;
+        let box_cols = box_cols.parse().map_err(|_| err())?;
+        let board_size = BoardSize::custom(box_rows, box_cols).map_err(|_| err())?;
+
+        // the width is redundant, but must match the boxes
+        if width != format!("{0}x{0}", board_size.width()) {
+            return Err(err());
         }
+
+        Ok(board_size)
     }
 }
 
 /// Error returned when a `base_size: usize` cannot be converted to a board size,
 /// currently only 2, 3, and 4 can be converted back to a board size.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BoardSizeOutOfRangeError(usize);
 impl Display for BoardSizeOutOfRangeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -56,6 +256,26 @@ impl Display for BoardSizeOutOfRangeError {
 }
 impl Error for BoardSizeOutOfRangeError {}
 
+/// Error returned by [`BoardSize::custom`] when boards with boxes of that size are not
+/// supported, boxes must have at least one line and column and boards can be between
+/// 2 and 16 cells wide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxSizeOutOfRangeError {
+    box_rows: usize,
+    box_cols: usize,
+}
+
+impl Display for BoxSizeOutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Boxes of {}x{} cells are not supported, boards must be between 2 and {} cells wide",
+            self.box_rows, self.box_cols, MAX_WIDTH
+        )
+    }
+}
+impl Error for BoxSizeOutOfRangeError {}
+
 impl TryInto<BoardSize> for usize {
     type Error = BoardSizeOutOfRangeError;
 
@@ -69,6 +289,66 @@ impl TryInto<BoardSize> for usize {
     }
 }
 
+/// The rules a board follows on top of every line, column and square holding each
+/// value once, see [`Board::set_variant`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// A classic sudoku, with no extra rules.
+    Standard,
+    /// Hyper sudoku, also known as Windoku, only for 9x9 boards. Four extra 3x3 regions,
+    /// with their top left cells at R2C2, R2C6, R6C2 and R6C6, must also hold every
+    /// value once.
+    Hyper,
+}
+
+impl Variant {
+    /// Returns true if boards of `board_size` can follow the rules of this variant.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::Variant;
+    ///
+    /// assert!(Variant::Hyper.supports(BoardSize::NineByNine));
+    /// assert!(!Variant::Hyper.supports(BoardSize::FourByFour));
+    /// ```
+    pub fn supports(&self, board_size: BoardSize) -> bool {
+        match self {
+            Self::Standard => true,
+            Self::Hyper => board_size == BoardSize::NineByNine,
+        }
+    }
+
+    /// Number of extra regions of the variant, numbered from 0 in [`Block::Extra`].
+    pub(crate) fn extra_block_count(&self) -> usize {
+        match self {
+            Self::Standard => 0,
+            Self::Hyper => 4,
+        }
+    }
+
+    /// Line and column of the top left cell of an extra region, which is as big as a
+    /// square of the board.
+    pub(crate) fn extra_block_corner(&self, block: usize) -> (usize, usize) {
+        debug_assert!(block < self.extra_block_count());
+        (1 + 4 * (block / 2), 1 + 4 * (block % 2))
+    }
+}
+
+impl Default for Variant {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => write!(f, "Standard sudoku"),
+            Self::Hyper => write!(f, "Hyper sudoku"),
+        }
+    }
+}
+
 /// Represents a sudoku board.
 ///
 /// This is usually the entry point to use any of the functionality in this library.
@@ -102,8 +382,9 @@ impl TryInto<BoardSize> for usize {
 /// ```
 #[derive(Debug, Clone)]
 pub struct Board {
-    base_size: usize,
+    board_size: BoardSize,
     cells: Vec<Option<u8>>,
+    variant: Variant,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -114,7 +395,7 @@ pub struct Board {
 /// helper functions to navigate the board and access each cell by a more intuitive
 /// line/column pair
 pub struct CellLoc {
-    base_size: usize,
+    board_size: BoardSize,
     idx: usize,
 }
 
@@ -144,10 +425,9 @@ impl CellLoc {
     /// ```
     ///
     pub fn at(l: usize, c: usize, board_size: BoardSize) -> Self {
-        let base_size = board_size.get_base_size();
         CellLoc {
-            idx: l * base_size.pow(2) + c,
-            base_size,
+            idx: l * board_size.width() + c,
+            board_size,
         }
     }
 
@@ -162,8 +442,7 @@ impl CellLoc {
     /// assert_eq!((cell.line(), cell.col()), (1, 0));
     /// ```
     pub fn new(idx: usize, board_size: BoardSize) -> Self {
-        let base_size = board_size.get_base_size();
-        CellLoc { idx, base_size }
+        CellLoc { idx, board_size }
     }
 
     /// Returns the 0 based flat index of this cell location
@@ -207,12 +486,10 @@ impl CellLoc {
     }
 
     fn calculate_possible_values(&self, board: &Board) -> BTreeSet<u8> {
-        let mut possible_values: BTreeSet<u8> = (1..=board.base_size.pow(2) as u8).collect();
+        let mut possible_values: BTreeSet<u8> = (1..=board.board_size.max_value()).collect();
 
         let values_iter = self
-            .iter_line()
-            .chain(self.iter_col())
-            .chain(self.iter_square())
+            .iter_peers(board.variant)
             .filter_map(|cell_loc| board.cells[cell_loc.idx]);
 
         for value in values_iter {
@@ -232,7 +509,7 @@ impl CellLoc {
     /// assert_eq!(cell.line(), 0);
     /// ```
     pub fn line(&self) -> usize {
-        self.idx / self.base_size.pow(2)
+        self.idx / self.board_size.width()
     }
 
     /// Returns the 0 based column number for this cell location
@@ -245,7 +522,7 @@ impl CellLoc {
     /// assert_eq!(cell.col(), 0);
     /// ```
     pub fn col(&self) -> usize {
-        self.idx % self.base_size.pow(2)
+        self.idx % self.board_size.width()
     }
 
     /// Returns the 0 based square number for this cell location.
@@ -262,7 +539,140 @@ impl CellLoc {
         let line_no = self.line();
         let col_no = self.col();
 
-        (line_no / self.base_size) * self.base_size + (col_no / self.base_size)
+        // a band of boxes is as many boxes wide as each box has lines
+        (line_no / self.board_size.box_rows()) * self.board_size.box_rows()
+            + (col_no / self.board_size.box_cols())
+    }
+
+    /// Returns the line this cell belongs to as a [`Block`].
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::solver::Block;
+    ///
+    /// let cell = CellLoc::at(4, 3, BoardSize::NineByNine);
+    /// assert_eq!(cell.line_block(), Block::Line(4));
+    /// ```
+    pub fn line_block(&self) -> Block {
+        Block::Line(self.line())
+    }
+
+    /// Returns the column this cell belongs to as a [`Block`].
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::solver::Block;
+    ///
+    /// let cell = CellLoc::at(4, 3, BoardSize::NineByNine);
+    /// assert_eq!(cell.col_block(), Block::Col(3));
+    /// ```
+    pub fn col_block(&self) -> Block {
+        Block::Col(self.col())
+    }
+
+    /// Returns the square this cell belongs to as a [`Block`].
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::solver::Block;
+    ///
+    /// let cell = CellLoc::at(4, 3, BoardSize::NineByNine);
+    /// assert_eq!(cell.square_block(), Block::Square(4));
+    /// ```
+    pub fn square_block(&self) -> Block {
+        Block::Square(self.square())
+    }
+
+    /// Returns the line, column and square this cell belongs to, in this order.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::solver::Block;
+    ///
+    /// let cell = CellLoc::at(4, 3, BoardSize::NineByNine);
+    /// assert_eq!(
+    ///     cell.blocks(),
+    ///     [Block::Line(4), Block::Col(3), Block::Square(4)]
+    /// );
+    /// ```
+    pub fn blocks(&self) -> [Block; 3] {
+        [self.line_block(), self.col_block(), self.square_block()]
+    }
+
+    /// Returns the extra region of `variant` this cell belongs to, if any.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::{CellLoc, Variant};
+    /// use sudokugen::solver::Block;
+    ///
+    /// let cell = CellLoc::at(2, 6, BoardSize::NineByNine);
+    /// assert_eq!(cell.extra_block(Variant::Hyper), Some(Block::Extra(1)));
+    /// assert_eq!(cell.extra_block(Variant::Standard), None);
+    /// ```
+    pub fn extra_block(&self, variant: Variant) -> Option<Block> {
+        self.extra_region(variant).map(Block::Extra)
+    }
+
+    /// Number of the extra region of `variant` this cell belongs to, if any.
+    fn extra_region(&self, variant: Variant) -> Option<usize> {
+        if !variant.supports(self.board_size) {
+            return None;
+        }
+
+        (0..variant.extra_block_count()).find(|block| {
+            let (line, col) = variant.extra_block_corner(*block);
+            (line..line + self.board_size.box_rows()).contains(&self.line())
+                && (col..col + self.board_size.box_cols()).contains(&self.col())
+        })
+    }
+
+    /// The blocks this cell belongs to in a board of `variant`, the same as
+    /// [`blocks`](Self::blocks) followed by the [`extra_block`](Self::extra_block).
+    pub(crate) fn iter_blocks(&self, variant: Variant) -> impl Iterator<Item = Block> {
+        self.blocks().into_iter().chain(self.extra_block(variant))
+    }
+
+    /// Iterates over all cells in the same extra region of `variant` as this one, if it
+    /// belongs to any.
+    pub(crate) fn iter_extra(&self, variant: Variant) -> impl Iterator<Item = CellLoc> {
+        let board_size = self.board_size;
+        let corner = self
+            .extra_region(variant)
+            .map(|block| variant.extra_block_corner(block));
+
+        corner.into_iter().flat_map(move |(line, col)| {
+            let (box_cols, width) = (board_size.box_cols(), board_size.width());
+            (0..width).map(move |position| CellLoc {
+                idx: (line + position / box_cols) * width + col + position % box_cols,
+                board_size,
+            })
+        })
+    }
+
+    /// Returns true if `other` is in the same line, column, square or extra region of
+    /// `variant` as this cell, or is this cell.
+    pub(crate) fn sees(&self, other: &CellLoc, variant: Variant) -> bool {
+        self.line() == other.line()
+            || self.col() == other.col()
+            || self.square() == other.square()
+            || self
+                .extra_region(variant)
+                .map_or(false, |block| other.extra_region(variant) == Some(block))
+    }
+
+    /// Iterates over every cell that can't hold the same value as this one in a board of
+    /// `variant`, the cells of its line, column, square and extra region. Some cells,
+    /// this one included, are returned more than once.
+    pub(crate) fn iter_peers(&self, variant: Variant) -> impl Iterator<Item = CellLoc> {
+        self.iter_line()
+            .chain(self.iter_col())
+            .chain(self.iter_square())
+            .chain(self.iter_extra(variant))
     }
 
     /// Iterates over all cells in the same line as this one.
@@ -282,12 +692,12 @@ impl CellLoc {
     ///     ]
     ///);
     pub fn iter_line(&self) -> impl Iterator<Item = CellLoc> {
-        let base_size = self.base_size;
+        let board_size = self.board_size;
 
-        let line_start = self.line() * self.base_size.pow(2);
-        let line_end = line_start + self.base_size.pow(2);
+        let line_start = self.line() * self.board_size.width();
+        let line_end = line_start + self.board_size.width();
 
-        (line_start..line_end).map(move |idx| CellLoc { idx, base_size })
+        (line_start..line_end).map(move |idx| CellLoc { idx, board_size })
     }
 
     /// Iterates over all cells in the same column as this one.
@@ -307,11 +717,12 @@ impl CellLoc {
     ///     ]
     ///);
     pub fn iter_col(&self) -> impl Iterator<Item = CellLoc> {
-        let base_size = self.base_size;
+        let board_size = self.board_size;
+        let width = board_size.width();
         let col_no = self.col();
-        (0..base_size.pow(2)).map(move |line_no| CellLoc {
-            idx: line_no * base_size.pow(2) + col_no,
-            base_size,
+        (0..width).map(move |line_no| CellLoc {
+            idx: line_no * width + col_no,
+            board_size,
         })
     }
 
@@ -332,451 +743,5210 @@ impl CellLoc {
     ///     ]
     ///);
     pub fn iter_square(&self) -> impl Iterator<Item = CellLoc> {
-        let base_size = self.base_size;
+        let board_size = self.board_size;
+        let (box_rows, box_cols) = (board_size.box_rows(), board_size.box_cols());
+        let width = board_size.width();
 
-        let line_no = self.idx / self.base_size.pow(2);
-        let col_no = self.idx % self.base_size.pow(2);
+        let line_no = self.idx / width;
+        let col_no = self.idx % width;
 
-        let sq_line = (line_no / base_size) * base_size;
-        let sq_col = (col_no / base_size) * base_size;
+        let sq_line = (line_no / box_rows) * box_rows;
+        let sq_col = (col_no / box_cols) * box_cols;
 
-        (sq_line..(sq_line + base_size)).flat_map(move |line| {
-            (sq_col..(sq_col + base_size)).map(move |col| CellLoc {
-                idx: line * base_size.pow(2) + col,
-                base_size,
+        (sq_line..(sq_line + box_rows)).flat_map(move |line| {
+            (sq_col..(sq_col + box_cols)).map(move |col| CellLoc {
+                idx: line * width + col,
+                board_size,
             })
         })
     }
-}
 
-impl Board {
-    /// Creates a new empty board of the specified size.
+    /// Iterates over the other cells in the same line as this one, same as
+    /// [`iter_line`](Self::iter_line) without this cell.
     ///
     /// ```
-    /// use sudokugen::{Board, BoardSize};
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::BoardSize;
     ///
-    /// let board: Board = Board::new(BoardSize::NineByNine);
+    /// let cell = CellLoc::at(0, 1, BoardSize::FourByFour);
+    /// assert_eq!(
+    ///     cell.row_peers().collect::<Vec<CellLoc>>(),
+    ///     vec![
+    ///         CellLoc::at(0, 0, BoardSize::FourByFour),
+    ///         CellLoc::at(0, 2, BoardSize::FourByFour),
+    ///         CellLoc::at(0, 3, BoardSize::FourByFour),
+    ///     ]
+    /// );
     /// ```
-    #[must_use]
-    pub fn new(board_size: BoardSize) -> Self {
-        let base_size = board_size.get_base_size();
-        Board {
-            base_size,
-            cells: vec![None; base_size.pow(4)],
-        }
+    pub fn row_peers(&self) -> impl Iterator<Item = CellLoc> {
+        let cell = *self;
+        self.iter_line().filter(move |other| *other != cell)
     }
 
-    /// Returns the board size of this board..
+    /// Iterates over the other cells in the same column as this one, same as
+    /// [`iter_col`](Self::iter_col) without this cell.
+    ///
     /// ```
-    /// use sudokugen::{Board, BoardSize};
-    /// let board: Board = Board::new(BoardSize::NineByNine);
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::BoardSize;
     ///
-    /// assert_eq!(board.board_size(), BoardSize::NineByNine);
+    /// let cell = CellLoc::at(1, 0, BoardSize::FourByFour);
+    /// assert_eq!(
+    ///     cell.col_peers().collect::<Vec<CellLoc>>(),
+    ///     vec![
+    ///         CellLoc::at(0, 0, BoardSize::FourByFour),
+    ///         CellLoc::at(2, 0, BoardSize::FourByFour),
+    ///         CellLoc::at(3, 0, BoardSize::FourByFour),
+    ///     ]
+    /// );
     /// ```
-    pub fn board_size(&self) -> BoardSize {
-        self.base_size.try_into().unwrap()
+    pub fn col_peers(&self) -> impl Iterator<Item = CellLoc> {
+        let cell = *self;
+        self.iter_col().filter(move |other| *other != cell)
     }
 
-    /// Sets the value of a cell in the board using the [`CellLoc`] structure
-    /// abstraction. Returns the previous value in this location.
-    ///
-    /// [`CellLoc`]: struct.CellLoc.html
+    /// Iterates over the other cells in the same square as this one, same as
+    /// [`iter_square`](Self::iter_square) without this cell.
     ///
     /// ```
-    /// use sudokugen::{Board, BoardSize};
     /// use sudokugen::board::CellLoc;
+    /// use sudokugen::BoardSize;
     ///
-    /// let mut board = Board::new(BoardSize::NineByNine);
-    /// let cell = CellLoc::at(0, 0, BoardSize::NineByNine);
-    /// board.set(&cell, 1);
-    ///
-    /// assert_eq!(board.get(&cell), Some(1));
+    /// let cell = CellLoc::at(1, 1, BoardSize::FourByFour);
+    /// assert_eq!(
+    ///     cell.square_peers().collect::<Vec<CellLoc>>(),
+    ///     vec![
+    ///         CellLoc::at(0, 0, BoardSize::FourByFour),
+    ///         CellLoc::at(0, 1, BoardSize::FourByFour),
+    ///         CellLoc::at(1, 0, BoardSize::FourByFour),
+    ///     ]
+    /// );
     /// ```
-    pub fn set(&mut self, loc: &CellLoc, value: u8) -> Option<u8> {
-        self.cells[loc.get_index()].replace(value)
+    pub fn square_peers(&self) -> impl Iterator<Item = CellLoc> {
+        let cell = *self;
+        self.iter_square().filter(move |other| *other != cell)
     }
 
-    /// Convenience method to set a value in the board using line and column indexing.
-    /// Returns the previous value in the board.
+    /// Returns the line, column or square shared by this cell and `other`, in this order
+    /// of preference when they share more than one, or `None` if they don't share any or
+    /// belong to boards of different sizes.
     ///
     /// ```
-    /// use sudokugen::{Board, BoardSize};
-    ///
-    /// let mut board = Board::new(BoardSize::NineByNine);
-    /// board.set_at(0, 0, 1);
+    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::solver::Block;
+    /// use sudokugen::BoardSize;
     ///
-    /// assert_eq!(board.get_at(0, 0), Some(1));
+    /// let cell = CellLoc::at(4, 3, BoardSize::NineByNine);
+    /// assert_eq!(
+    ///     cell.shared_constraint_group(&CellLoc::at(4, 8, BoardSize::NineByNine)),
+    ///     Some(Block::Line(4))
+    /// );
+    /// assert_eq!(
+    ///     cell.shared_constraint_group(&CellLoc::at(5, 5, BoardSize::NineByNine)),
+    ///     Some(Block::Square(4))
+    /// );
+    /// assert_eq!(
+    ///     cell.shared_constraint_group(&CellLoc::at(0, 0, BoardSize::NineByNine)),
+    ///     None
+    /// );
     /// ```
-    pub fn set_at(&mut self, l: usize, c: usize, value: u8) -> Option<u8> {
-        let board_size = self.board_size();
+    pub fn shared_constraint_group(&self, other: &CellLoc) -> Option<Block> {
+        if self.board_size != other.board_size {
+            return None;
+        }
 
-        self.cells[CellLoc::at(l, c, board_size).get_index()].replace(value)
+        self.blocks()
+            .into_iter()
+            .zip(other.blocks())
+            .find(|(block, other_block)| block == other_block)
+            .map(|(block, _)| block)
     }
+}
 
-    /// Remove a value from the board at this cell and return the previously saved value.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BoardSize {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStructVariant;
+
+        let (idx, name) = match self {
+            Self::FourByFour => (0, "FourByFour"),
+            Self::NineByNine => (1, "NineByNine"),
+            Self::SixteenBySixteen => (2, "SixteenBySixteen"),
+            Self::Custom { box_rows, box_cols } => {
+                let mut state = serializer.serialize_struct_variant("BoardSize", 3, "Custom", 2)?;
+                state.serialize_field("box_rows", box_rows)?;
+                state.serialize_field("box_cols", box_cols)?;
+                return state.end();
+            }
+        };
+        serializer.serialize_unit_variant("BoardSize", idx, name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BoardSize {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const VARIANTS: &[&str] = &["FourByFour", "NineByNine", "SixteenBySixteen", "Custom"];
+
+        struct BoardSizeVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for BoardSizeVisitor {
+            type Value = BoardSize;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a board size")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<BoardSize, E> {
+                match name {
+                    "FourByFour" => Ok(BoardSize::FourByFour),
+                    "NineByNine" => Ok(BoardSize::NineByNine),
+                    "SixteenBySixteen" => Ok(BoardSize::SixteenBySixteen),
+                    _ => Err(E::unknown_variant(name, VARIANTS)),
+                }
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<BoardSize, A::Error> {
+                use serde::de::Error;
+
+                let name: String = map
+                    .next_key()?
+                    .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                if name != "Custom" {
+                    return Err(A::Error::unknown_variant(&name, &["Custom"]));
+                }
+
+                let boxes: BTreeMap<String, usize> = map.next_value()?;
+                let field = |name: &'static str| {
+                    boxes
+                        .get(name)
+                        .copied()
+                        .ok_or_else(|| A::Error::missing_field(name))
+                };
+                BoardSize::custom(field("box_rows")?, field("box_cols")?).map_err(A::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(BoardSizeVisitor)
+    }
+}
+
+/// Serializes a board as a string in the format returned by [`Board::to_line_string`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_line_string())
+    }
+}
+
+/// Deserializes a board from a string in any of the formats accepted by
+/// [`from_str`](struct.Board.html#method.from_str).
+///
+/// ```
+/// use sudokugen::board::Board;
+///
+/// let board: Board = serde_json::from_str(r#""1....2....3....4""#).unwrap();
+///
+/// assert_eq!(serde_json::to_string(&board).unwrap(), r#""1....2....3....4""#);
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let board = String::deserialize(deserializer)?;
+        board.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes a cell location as its line and column, `{"line": 0, "col": 0}`.
+/// The size of the board is not serialized, use [`CellLocSeed`] to deserialize it.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CellLoc {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("CellLoc", 2)?;
+        state.serialize_field("line", &self.line())?;
+        state.serialize_field("col", &self.col())?;
+        state.end()
+    }
+}
+
+/// Deserializes a [`CellLoc`] serialized as `{"line": 0, "col": 0}`.
+///
+/// Cell locations don't serialize the size of the board they belong to, so it must
+/// be provided when deserializing them.
+///
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use sudokugen::BoardSize;
+/// use sudokugen::board::{CellLoc, CellLocSeed};
+///
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{"line": 1, "col": 2}"#);
+/// let cell = CellLocSeed(BoardSize::NineByNine)
+///     .deserialize(&mut deserializer)
+///     .unwrap();
+///
+/// assert_eq!(cell, CellLoc::at(1, 2, BoardSize::NineByNine));
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy)]
+pub struct CellLocSeed(pub BoardSize);
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::DeserializeSeed<'de> for CellLocSeed {
+    type Value = CellLoc;
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<CellLoc, D::Error> {
+        deserializer.deserialize_struct("CellLoc", &["line", "col"], self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for CellLocSeed {
+    type Value = CellLoc;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a cell location with a line and column in a {} board",
+            self.0
+        )
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<CellLoc, A::Error> {
+        use serde::de::Error;
+
+        let line = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let col = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+
+        self.cell_at(line, col)
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<CellLoc, A::Error> {
+        use serde::de::Error;
+
+        let mut line = None;
+        let mut col = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "line" if line.is_none() => line = Some(map.next_value()?),
+                "col" if col.is_none() => col = Some(map.next_value()?),
+                "line" | "col" => {
+                    return Err(A::Error::custom(format!("duplicate field `{}`", key)))
+                }
+                _ => return Err(A::Error::unknown_field(&key, &["line", "col"])),
+            }
+        }
+
+        let line = line.ok_or_else(|| A::Error::missing_field("line"))?;
+        let col = col.ok_or_else(|| A::Error::missing_field("col"))?;
+
+        self.cell_at(line, col)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl CellLocSeed {
+    fn cell_at<E: serde::de::Error>(self, line: usize, col: usize) -> Result<CellLoc, E> {
+        let width = self.0.width();
+        if line >= width || col >= width {
+            return Err(E::custom(format!(
+                "cell ({}, {}) is outside of a {} board",
+                line, col, self.0
+            )));
+        }
+
+        Ok(CellLoc::at(line, col, self.0))
+    }
+}
+
+/// Generates any of the supported board sizes.
+#[cfg(feature = "arbitrary")]
+impl proptest::arbitrary::Arbitrary for BoardSize {
+    type Parameters = ();
+    type Strategy = proptest::sample::Select<BoardSize>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        proptest::sample::select(vec![
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ])
+    }
+}
+
+/// Parameters for the boards generated by [`Board`]'s `Arbitrary` implementation.
+#[cfg(feature = "arbitrary")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardParams {
+    /// Size of the boards generated, any size when `None`.
+    pub board_size: Option<BoardSize>,
+    /// Probability of each cell being filled, from 0.0 for empty boards to 1.0 for
+    /// boards without empty cells.
+    pub fill_ratio: f64,
+}
+
+#[cfg(feature = "arbitrary")]
+impl Default for BoardParams {
+    fn default() -> Self {
+        BoardParams {
+            board_size: None,
+            fill_ratio: 0.5,
+        }
+    }
+}
+
+/// Generates boards with every value in range for the size of the board, the values
+/// are random so the boards are usually not valid sudoku puzzles. Use
+/// [`Puzzle`](crate::Puzzle)'s `Arbitrary` implementation for solvable boards.
+///
+/// Boards shrink by removing values from their cells.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use sudokugen::board::{Board, BoardParams};
+/// use sudokugen::BoardSize;
+///
+/// let params = BoardParams {
+///     board_size: Some(BoardSize::FourByFour),
+///     fill_ratio: 0.25,
+/// };
+///
+/// proptest!(|(board in any_with::<Board>(params))| {
+///     prop_assert_eq!(board.board_size(), BoardSize::FourByFour);
+/// });
+/// ```
+#[cfg(feature = "arbitrary")]
+impl proptest::arbitrary::Arbitrary for Board {
+    type Parameters = BoardParams;
+    type Strategy = proptest::strategy::BoxedStrategy<Board>;
+
+    fn arbitrary_with(params: BoardParams) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        let board_size = match params.board_size {
+            Some(board_size) => Just(board_size).boxed(),
+            None => any::<BoardSize>().boxed(),
+        };
+
+        board_size
+            .prop_flat_map(move |board_size| {
+                let values = 1..=board_size.max_value();
+                let cell = if params.fill_ratio <= 0.0 {
+                    Just(None).boxed()
+                } else if params.fill_ratio >= 1.0 {
+                    values.prop_map(Some).boxed()
+                } else {
+                    proptest::option::weighted(params.fill_ratio, values).boxed()
+                };
+                proptest::collection::vec(cell, board_size.cell_count()).prop_map(move |cells| {
+                    Board {
+                        board_size,
+                        cells,
+                        variant: Variant::Standard,
+                    }
+                })
+            })
+            .boxed()
+    }
+}
+
+/// Creates a [`Board`] from its rows, one array per row with a number for each value
+/// and `_` for each empty cell. The size of the board is taken from the number of rows.
+///
+/// ```
+/// use sudokugen::{board, Board, BoardSize};
+///
+/// let board = board![
+///     [1, _, _, 4],
+///     [_, 2, _, _],
+///     [_, _, 3, _],
+///     [4, _, _, _],
+/// ];
+///
+/// assert_eq!(board.board_size(), BoardSize::FourByFour);
+/// assert_eq!(board, "1..4 .2.. ..3. 4...".parse::<Board>().unwrap());
+/// ```
+///
+/// Rows of different lengths don't compile:
+///
+/// ```compile_fail
+/// use sudokugen::board;
+///
+/// let board = board![[1, _, _, 4], [_, 2, _], [_, _, 3, _], [4, _, _, _]];
+/// ```
+///
+/// # Panics
+///
+/// Panics if the number of rows is not 4, 9 or 16, if there are not as many cells in each
+/// row as rows, or if any value is 0 or larger than the width of the board.
+#[macro_export]
+macro_rules! board {
+    (@cell _) => {
+        None
+    };
+    (@cell $value:expr) => {
+        Some($value)
+    };
+    ($([$($cell:tt),* $(,)?]),+ $(,)?) => {
+        $crate::board::Board::from_macro_rows(&[$([$($crate::board!(@cell $cell)),*]),+])
+    };
+}
+
+impl Board {
+    /// Creates a new empty board of the specified size.
     ///
     /// ```
-    /// use sudokugen::{Board, BoardSize};    
-    /// use sudokugen::board::CellLoc;
+    /// use sudokugen::{Board, BoardSize};
     ///
-    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
-    /// let cell = CellLoc::at(0, 0, BoardSize::FourByFour);
+    /// let board: Board = Board::new(BoardSize::NineByNine);
+    /// ```
+    #[must_use]
+    pub fn new(board_size: BoardSize) -> Self {
+        Board {
+            board_size,
+            cells: vec![None; board_size.cell_count()],
+            variant: Variant::Standard,
+        }
+    }
+
+    /// Creates a new empty 4x4 board, same as `Board::new(BoardSize::FourByFour)`.
     ///
-    /// let old_value = board.unset(&cell);
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
     ///
-    /// assert_eq!(old_value, Some(1));
-    /// assert_eq!(board.get(&cell), None);
+    /// let board = Board::new_4x4();
+    /// assert_eq!(board, Board::new(BoardSize::FourByFour));
     /// ```
-    pub fn unset(&mut self, loc: &CellLoc) -> Option<u8> {
-        self.cells[loc.get_index()].take()
+    #[must_use]
+    pub fn new_4x4() -> Self {
+        Self::new(BoardSize::FourByFour)
     }
 
-    /// Returns the value at a cell if there is any or `None` otherwise.
+    /// Creates a new empty 9x9 board, same as `Board::new(BoardSize::NineByNine)`.
     ///
     /// ```
-    /// use sudokugen::board::Board;
-    ///
-    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// use sudokugen::{Board, BoardSize};
     ///
-    /// assert_eq!(board.get(&board.cell_at(0, 0)), Some(1));
-    /// assert_eq!(board.get(&board.cell_at(0, 1)), None);
+    /// let board = Board::new_9x9();
+    /// assert_eq!(board, Board::new(BoardSize::NineByNine));
     /// ```
     #[must_use]
-    pub fn get(&self, cell: &CellLoc) -> Option<u8> {
-        self.cells[cell.idx]
+    pub fn new_9x9() -> Self {
+        Self::new(BoardSize::NineByNine)
     }
 
-    /// Same as [`get`] but more ergonomic for manual usage. Returns the
-    /// value at that position or None if no value is set. See the method
-    /// [`CellLoc::at`] for an explanation on the arrangement of lines and columns.
-    ///
-    /// [`get`]: #method.get
-    /// [`CellLoc::at`]: struct.CellLoc.html#method.at
+    /// Creates a new empty 16x16 board, same as `Board::new(BoardSize::SixteenBySixteen)`.
     ///
     /// ```
-    /// use sudokugen::board::Board;
+    /// use sudokugen::{Board, BoardSize};
     ///
-    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// let board = Board::new_16x16();
+    /// assert_eq!(board, Board::new(BoardSize::SixteenBySixteen));
+    /// ```
+    #[must_use]
+    pub fn new_16x16() -> Self {
+        Self::new(BoardSize::SixteenBySixteen)
+    }
+
+    /// Builds the board for the [`board!`] macro, checking the rows it was given.
+    #[doc(hidden)]
+    pub fn from_macro_rows<const N: usize>(rows: &[[Option<u8>; N]]) -> Board {
+        let board_size = match rows.len() {
+            4 => BoardSize::FourByFour,
+            9 => BoardSize::NineByNine,
+            16 => BoardSize::SixteenBySixteen,
+            len => panic!("board! needs 4, 9 or 16 rows, found {}", len),
+        };
+        assert_eq!(
+            N,
+            rows.len(),
+            "board! rows must have as many cells as there are rows"
+        );
+
+        let mut board = Board::new(board_size);
+        for (l, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                if let Some(value) = *value {
+                    assert!(
+                        value >= 1 && value <= board_size.max_value(),
+                        "board! value {} at row {} column {} is not between 1 and {}",
+                        value,
+                        l + 1,
+                        c + 1,
+                        board_size.max_value()
+                    );
+                    board.set_at(l, c, value);
+                }
+            }
+        }
+
+        board
+    }
+
+    /// Returns the board size of this board..
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    /// let board: Board = Board::new(BoardSize::NineByNine);
     ///
-    /// assert_eq!(board.get_at(0, 0), Some(1));
-    /// assert_eq!(board.get_at(0, 1), None);
+    /// assert_eq!(board.board_size(), BoardSize::NineByNine);
     /// ```
-    pub fn get_at(&self, l: usize, c: usize) -> Option<u8> {
-        self.get(&CellLoc::at(l, c, self.board_size()))
+    pub fn board_size(&self) -> BoardSize {
+        self.board_size
     }
 
-    /// Return an iterator over all cells in the board.
+    /// Returns the variant of sudoku this board follows, new and parsed boards are
+    /// [`Variant::Standard`].
     ///
     /// ```
     /// use sudokugen::{Board, BoardSize};
-    /// use sudokugen::board::CellLoc;
-    /// use std::collections::BTreeSet;
+    /// use sudokugen::board::Variant;
     ///
-    /// let board = Board::new(BoardSize::FourByFour);
+    /// assert_eq!(Board::new(BoardSize::NineByNine).variant(), Variant::Standard);
+    /// ```
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Makes the board follow the rules of `variant` when it's solved, generated or
+    /// validated, the values already in the board are kept as they are.
+    ///
+    /// Returns [`UnsupportedVariantError`] without changing the board if the variant
+    /// can't be used with boards of this size.
     ///
-    /// assert_eq!(
-    ///     board.iter_cells().collect::<BTreeSet<CellLoc>>(),
-    ///     (0..4).flat_map(|line| (0..4).map(move |col| {
-    ///         CellLoc::at(line.clone(), col, BoardSize::FourByFour)
-    ///     }))
-    ///         .collect::<BTreeSet<CellLoc>>()
-    /// );
     /// ```
+    /// use sudokugen::{Board, BoardSize};
+    /// use sudokugen::board::Variant;
     ///
-    /// Keep in mind that this iterates only over the cell location
-    /// not the cell value, in order to access/modify the current value
-    /// you'll need to use the [`get`] and [`set`] methods of this board.
+    /// let mut board = Board::new(BoardSize::NineByNine);
+    /// board.set_variant(Variant::Hyper).unwrap();
+    /// assert_eq!(board.variant(), Variant::Hyper);
     ///
-    /// [`get`]: #method.get
-    /// [`set`]: #method.set
-    pub fn iter_cells(&self) -> impl Iterator<Item = CellLoc> {
-        let base_size = self.base_size;
+    /// assert!(Board::new(BoardSize::FourByFour).set_variant(Variant::Hyper).is_err());
+    /// ```
+    pub fn set_variant(&mut self, variant: Variant) -> Result<(), UnsupportedVariantError> {
+        if !variant.supports(self.board_size()) {
+            return Err(UnsupportedVariantError {
+                variant,
+                board_size: self.board_size(),
+            });
+        }
 
-        (0..self.base_size.pow(4)).map(move |idx| CellLoc { idx, base_size })
+        self.variant = variant;
+        Ok(())
     }
 
-    /// Convenience method to return a [`CellLoc`] at this position that is compatible
-    /// with this board (has the same `base_size`). See more about referencing cells by
-    /// line and column using the [`at`] method
+    /// Returns true if none of the cells in the board have a value.
     ///
     /// ```
     /// use sudokugen::{Board, BoardSize};
     ///
-    /// let board = Board::new(BoardSize::NineByNine);
-    /// let cell = board.cell_at(1, 1);
+    /// let mut board = Board::new(BoardSize::NineByNine);
+    /// assert!(board.is_empty());
     ///
-    /// assert_eq!((cell.line(), cell.col()), (1, 1));
+    /// board.set_at(0, 0, 1);
+    /// assert!(!board.is_empty());
     /// ```
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_none())
+    }
+
+    /// Returns the number of cells in the board that have a value.
     ///
-    /// [`CellLoc`]: struct.CellLoc.html
-    /// [`at`]: struct.CellLoc.html#at
-    #[must_use]
-    pub fn cell_at(&self, l: usize, c: usize) -> CellLoc {
-        CellLoc::at(l, c, self.board_size())
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .2.. .... ...4".parse().unwrap();
+    /// assert_eq!(board.clue_count(), 3);
+    /// ```
+    pub fn clue_count(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
     }
 
-    /// Returns a new sudoku [`Board`] rotated clockwise by 90deg.
+    /// Returns true if every cell in the board has a value, whether or not the values
+    /// repeat in a line, column or square.
     ///
-    /// Valid sudoku puzzles are also valid if rotated 90deg, 180deg and 270deg,
-    /// they are the same puzzle, however must people would have trouble realizing that
-    /// they are doing the same puzzle. This function provides a cheap way to turn 1 valid
-    /// puzzle into 4.
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1234 3412 2143 4321".parse().unwrap();
+    /// assert!(board.is_complete());
+    ///
+    /// let board: Board = "1234 3412 2143 432.".parse().unwrap();
+    /// assert!(!board.is_complete());
+    /// ```
+    pub fn is_complete(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_some())
+    }
+
+    /// Returns true if line `row` holds every value of the board once, for instance to
+    /// highlight it once the player filled it correctly. Lines outside of the board are
+    /// never complete.
     ///
     /// ```
     /// use sudokugen::board::Board;
     ///
-    /// let board: Board = "
-    /// 1 2 | . .
-    /// 3 4 | . .
-    /// ---------
-    /// . . | . .
-    /// . . | . .
-    /// ".parse().unwrap();
+    /// let board: Board = "1234 34.2 2143 4321".parse().unwrap();
+    /// assert!(board.row_is_complete(0));
+    /// assert!(!board.row_is_complete(1));
+    /// ```
+    pub fn row_is_complete(&self, row: usize) -> bool {
+        self.unit_is_complete(|cell| cell.line() == row)
+    }
+
+    /// Returns true if column `col` holds every value of the board once, see
+    /// [`Board::row_is_complete`].
     ///
-    /// let rotated_board: Board = "
-    /// . . | 3 1
-    /// . . | 4 2
-    /// ---------
-    /// . . | . .
-    /// . . | . .
-    /// ".parse().unwrap();
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1234 34.2 2143 4321".parse().unwrap();
+    /// assert!(board.col_is_complete(0));
+    /// assert!(!board.col_is_complete(2));
+    /// ```
+    pub fn col_is_complete(&self, col: usize) -> bool {
+        self.unit_is_complete(|cell| cell.col() == col)
+    }
+
+    /// Returns true if square `sq` holds every value of the board once, see
+    /// [`Board::row_is_complete`]. Squares are numbered line first and then columns, as
+    /// in [`CellLoc::square`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1234 34.2 2143 4321".parse().unwrap();
+    /// assert!(board.square_is_complete(0));
+    /// assert!(!board.square_is_complete(1));
+    /// ```
+    pub fn square_is_complete(&self, sq: usize) -> bool {
+        self.unit_is_complete(|cell| cell.square() == sq)
+    }
+
+    /// Returns true if the cells picked by `in_unit` hold every value of the board once.
+    fn unit_is_complete(&self, in_unit: impl Fn(&CellLoc) -> bool) -> bool {
+        let max_value = self.board_size.max_value();
+        let mut seen = BTreeSet::new();
+
+        for cell in self.iter_cells().filter(in_unit) {
+            match self.get(&cell) {
+                Some(value) if (1..=max_value).contains(&value) && seen.insert(value) => (),
+                _ => return false,
+            }
+        }
+
+        seen.len() == usize::from(max_value)
+    }
+
+    /// Returns true if every value in this board is in the same cell of `other`, empty
+    /// cells match anything. Boards of different sizes never match.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .4.. .... ....".parse().unwrap();
+    ///
+    /// assert!(board.is_subset_of(&"12.. .4.. .... ....".parse().unwrap()));
+    /// assert!(!board.is_subset_of(&"2... .4.. .... ....".parse().unwrap()));
+    /// assert!(!board.is_subset_of(&"1... .... .... ....".parse().unwrap()));
+    /// ```
+    pub fn is_subset_of(&self, other: &Board) -> bool {
+        self.board_size == other.board_size
+            && self
+                .cells
+                .iter()
+                .zip(&other.cells)
+                .all(|(value, expected)| value.is_none() || value == expected)
+    }
+
+    /// Returns true if every value in this board is in the same cell of `solution`, so the
+    /// board can still be completed to it. Boards of different sizes never match.
+    ///
+    /// The same as [`is_subset_of`](Board::is_subset_of), unlike
+    /// [`validate_solution`](Board::validate_solution) neither board needs to be complete,
+    /// nor is `solution` checked for repeated values.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let solution: Board = "1243 3412 2134 4321".parse().unwrap();
+    ///
+    /// assert!("1... .4.. .... ....".parse::<Board>().unwrap().could_complete_to(&solution));
+    /// assert!(!"2... .... .... ....".parse::<Board>().unwrap().could_complete_to(&solution));
+    /// ```
+    pub fn could_complete_to(&self, solution: &Board) -> bool {
+        self.is_subset_of(solution)
+    }
+
+    /// Returns true if `candidate_solution` is a solution to this puzzle: both boards
+    /// have the same size, every value in this board is in the same cell of the solution,
+    /// the solution is complete and no value repeats in any of its lines, columns or
+    /// squares.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let puzzle: Board = "1... ..1. .... ...1".parse().unwrap();
+    ///
+    /// assert!(puzzle.validate_solution(&"1243 3412 2134 4321".parse().unwrap()));
+    ///
+    /// // the first cell doesn't match the puzzle
+    /// assert!(!puzzle.validate_solution(&"2134 3412 1243 4321".parse().unwrap()));
+    /// // 1 and 2 repeat in the last line
+    /// assert!(!puzzle.validate_solution(&"1243 3412 2134 4312".parse().unwrap()));
+    /// ```
+    pub fn validate_solution(&self, candidate_solution: &Board) -> bool {
+        if !candidate_solution.is_complete() || !self.is_subset_of(candidate_solution) {
+            return false;
+        }
+
+        candidate_solution.iter_cells().all(|cell| {
+            let value = candidate_solution.get(&cell);
+            cell.iter_peers(self.variant)
+                .all(|other| other == cell || candidate_solution.get(&other) != value)
+        })
+    }
+
+    /// Returns true if this board is a solution to `puzzle`, the same as
+    /// `puzzle.validate_solution(self)`: this board is complete, has no repeated values
+    /// and agrees with every value of `puzzle`. Boards of different sizes never match.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let puzzle: Board = "1... ..1. .... ...1".parse().unwrap();
+    /// let solution: Board = "1243 3412 2134 4321".parse().unwrap();
+    ///
+    /// assert!(solution.is_completion_of(&puzzle));
+    /// assert!(!puzzle.is_completion_of(&solution));
+    /// ```
+    pub fn is_completion_of(&self, puzzle: &Board) -> bool {
+        puzzle.validate_solution(self)
+    }
+
+    /// Sets the value of a cell in the board using the [`CellLoc`] structure
+    /// abstraction. Returns the previous value in this location.
+    ///
+    /// [`CellLoc`]: struct.CellLoc.html
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    /// use sudokugen::board::CellLoc;
+    ///
+    /// let mut board = Board::new(BoardSize::NineByNine);
+    /// let cell = CellLoc::at(0, 0, BoardSize::NineByNine);
+    /// board.set(&cell, 1);
+    ///
+    /// assert_eq!(board.get(&cell), Some(1));
+    /// ```
+    pub fn set<V>(&mut self, loc: &CellLoc, value: V) -> Option<u8>
+    where
+        V: TryInto<CellValue>,
+        V::Error: Into<ValueOutOfRangeError>,
+    {
+        debug_assert_eq!(
+            loc.board_size, self.board_size,
+            "cell and board sizes differ"
+        );
+        let value = self
+            .checked_value(value)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.cells[loc.get_index()].replace(value)
+    }
+
+    /// Same as [`set`] but returns [`SizeMismatchError`] instead of panicking or
+    /// writing to the wrong cell when the cell location was created for a board of
+    /// a different size.
+    ///
+    /// Panics like [`set`] if the value is out of range for the board. Deprecated in
+    /// favour of [`try_set_value`], which returns an error in both cases.
+    ///
+    /// [`set`]: #method.set
+    /// [`try_set_value`]: #method.try_set_value
+    /// [`SizeMismatchError`]: struct.SizeMismatchError.html
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    /// use sudokugen::board::CellLoc;
+    ///
+    /// let mut board = Board::new(BoardSize::FourByFour);
+    ///
+    /// assert_eq!(board.try_set(&CellLoc::at(0, 0, BoardSize::FourByFour), 1), Ok(None));
+    /// assert!(board.try_set(&CellLoc::at(8, 8, BoardSize::NineByNine), 1).is_err());
+    /// ```
+    #[deprecated(note = "use `try_set_value`, which doesn't panic on values out of range")]
+    pub fn try_set<V>(&mut self, loc: &CellLoc, value: V) -> Result<Option<u8>, SizeMismatchError>
+    where
+        V: TryInto<CellValue>,
+        V::Error: Into<ValueOutOfRangeError>,
+    {
+        let idx = self.checked_index(loc)?;
+        let value = self
+            .checked_value(value)
+            .unwrap_or_else(|err| panic!("{}", err));
+        Ok(self.cells[idx].replace(value))
+    }
+
+    /// Same as [`set`] but returns [`SetValueError`] instead of panicking or writing to
+    /// the wrong cell when the cell location was created for a board of a different size
+    /// or the value is out of range for the board.
+    ///
+    /// [`set`]: #method.set
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    /// use sudokugen::board::CellLoc;
+    ///
+    /// let mut board = Board::new(BoardSize::FourByFour);
+    ///
+    /// assert_eq!(board.try_set_value(&CellLoc::at(0, 0, BoardSize::FourByFour), 1), Ok(None));
+    /// assert!(board.try_set_value(&CellLoc::at(8, 8, BoardSize::NineByNine), 1).is_err());
+    /// assert!(board.try_set_value(&CellLoc::at(0, 0, BoardSize::FourByFour), 5).is_err());
+    /// ```
+    pub fn try_set_value<V>(&mut self, loc: &CellLoc, value: V) -> Result<Option<u8>, SetValueError>
+    where
+        V: TryInto<CellValue>,
+        V::Error: Into<ValueOutOfRangeError>,
+    {
+        let idx = self
+            .checked_index(loc)
+            .map_err(SetValueError::SizeMismatch)?;
+        let value = self
+            .checked_value(value)
+            .map_err(SetValueError::ValueOutOfRange)?;
+        Ok(self.cells[idx].replace(value))
+    }
+
+    /// Convenience method to set a value in the board using line and column indexing.
+    /// Returns the previous value in the board.
+    ///
+    /// Panics like [`set`](Self::set) if the value is out of range for the board.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let mut board = Board::new(BoardSize::NineByNine);
+    /// board.set_at(0, 0, 1);
+    ///
+    /// assert_eq!(board.get_at(0, 0), Some(1));
+    /// ```
+    pub fn set_at<V>(&mut self, l: usize, c: usize, value: V) -> Option<u8>
+    where
+        V: TryInto<CellValue>,
+        V::Error: Into<ValueOutOfRangeError>,
+    {
+        let board_size = self.board_size();
+        let value = self
+            .checked_value(value)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        self.cells[CellLoc::at(l, c, board_size).get_index()].replace(value)
+    }
+
+    /// Returns `value` as a [`CellValue`] if it's between 1 and the largest value of
+    /// this board.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board = Board::new(BoardSize::FourByFour);
+    ///
+    /// assert_eq!(board.value(4).unwrap().get(), 4);
+    /// assert!(board.value(0).is_err());
+    /// assert!(board.value(5).is_err());
+    /// ```
+    pub fn value(&self, value: u8) -> Result<CellValue, ValueOutOfRangeError> {
+        CellValue::new(value, self.board_size())
+    }
+
+    fn checked_value<V>(&self, value: V) -> Result<u8, ValueOutOfRangeError>
+    where
+        V: TryInto<CellValue>,
+        V::Error: Into<ValueOutOfRangeError>,
+    {
+        // the conversion only knows the largest board, report the range of this one instead
+        let value = value.try_into().map_err(|err| ValueOutOfRangeError {
+            max_value: self.board_size().max_value(),
+            ..err.into()
+        })?;
+        self.value(value.get()).map(|value| value.get())
+    }
+
+    /// Remove a value from the board at this cell and return the previously saved value.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};    
+    /// use sudokugen::board::CellLoc;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// let cell = CellLoc::at(0, 0, BoardSize::FourByFour);
+    ///
+    /// let old_value = board.unset(&cell);
+    ///
+    /// assert_eq!(old_value, Some(1));
+    /// assert_eq!(board.get(&cell), None);
+    /// ```
+    pub fn unset(&mut self, loc: &CellLoc) -> Option<u8> {
+        debug_assert_eq!(
+            loc.board_size, self.board_size,
+            "cell and board sizes differ"
+        );
+        self.cells[loc.get_index()].take()
+    }
+
+    /// Removes every value from the board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    /// board.clear();
+    ///
+    /// assert!(board.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = None);
+    }
+
+    /// Removes the value of every cell not in `keep`, for instance to go back to the
+    /// givens of a puzzle. Cells created for a board of a different size are ignored.
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "12.. .2.. ..3. ...4".parse().unwrap();
+    /// let keep: BTreeSet<_> = vec![board.cell_at(0, 0), board.cell_at(3, 3)].into_iter().collect();
+    /// board.retain_cells(&keep);
+    ///
+    /// assert_eq!(board, "1... .... .... ...4".parse().unwrap());
+    /// ```
+    pub fn retain_cells(&mut self, keep: &BTreeSet<CellLoc>) {
+        for (idx, cell) in self.cells.iter_mut().enumerate() {
+            let loc = CellLoc {
+                board_size: self.board_size,
+                idx,
+            };
+            if !keep.contains(&loc) {
+                *cell = None;
+            }
+        }
+    }
+
+    /// Copies every filled cell of `other` onto this board, replacing the values already
+    /// there, and keeps the cells empty in `other` as they are.
+    ///
+    /// Returns [`BoardSizeMismatchError`] without changing the board if the boards have
+    /// different sizes.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::BoardSize;
+    ///
+    /// let mut board: Board = "1... .2.. .... ....".parse().unwrap();
+    /// let moves: Board = "2... .... ..3. ....".parse().unwrap();
+    /// board.overlay(&moves).unwrap();
+    ///
+    /// assert_eq!(board, "2... .2.. ..3. ....".parse().unwrap());
+    /// assert!(board.overlay(&Board::new(BoardSize::NineByNine)).is_err());
+    /// ```
+    pub fn overlay(&mut self, other: &Board) -> Result<(), BoardSizeMismatchError> {
+        if other.board_size != self.board_size {
+            return Err(BoardSizeMismatchError {
+                expected: self.board_size(),
+                found: other.board_size(),
+            });
+        }
+
+        for (cell, value) in self.cells.iter_mut().zip(&other.cells) {
+            if value.is_some() {
+                *cell = *value;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the value at a cell if there is any or `None` otherwise.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(board.get(&board.cell_at(0, 0)), Some(1));
+    /// assert_eq!(board.get(&board.cell_at(0, 1)), None);
+    /// ```
+    #[must_use]
+    pub fn get(&self, cell: &CellLoc) -> Option<u8> {
+        debug_assert_eq!(
+            cell.board_size, self.board_size,
+            "cell and board sizes differ"
+        );
+        self.cells[cell.idx]
+    }
+
+    /// Same as [`get`] but returns [`SizeMismatchError`] instead of panicking or
+    /// reading the wrong cell when the cell location was created for a board of
+    /// a different size.
+    ///
+    /// [`get`]: #method.get
+    /// [`SizeMismatchError`]: struct.SizeMismatchError.html
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::board::{Board, CellLoc};
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(board.try_get(&CellLoc::at(0, 0, BoardSize::FourByFour)), Ok(Some(1)));
+    /// assert!(board.try_get(&CellLoc::at(0, 0, BoardSize::NineByNine)).is_err());
+    /// ```
+    pub fn try_get(&self, cell: &CellLoc) -> Result<Option<u8>, SizeMismatchError> {
+        let idx = self.checked_index(cell)?;
+        Ok(self.cells[idx])
+    }
+
+    fn checked_index(&self, cell: &CellLoc) -> Result<usize, SizeMismatchError> {
+        if cell.board_size == self.board_size && cell.idx < self.cells.len() {
+            Ok(cell.idx)
+        } else {
+            Err(SizeMismatchError {
+                cell: *cell,
+                board_size: self.board_size(),
+            })
+        }
+    }
+
+    /// Same as [`get`] but more ergonomic for manual usage. Returns the
+    /// value at that position or None if no value is set. See the method
+    /// [`CellLoc::at`] for an explanation on the arrangement of lines and columns.
+    ///
+    /// [`get`]: #method.get
+    /// [`CellLoc::at`]: struct.CellLoc.html#method.at
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(board.get_at(0, 0), Some(1));
+    /// assert_eq!(board.get_at(0, 1), None);
+    /// ```
+    pub fn get_at(&self, l: usize, c: usize) -> Option<u8> {
+        self.get(&CellLoc::at(l, c, self.board_size()))
+    }
+
+    /// Return an iterator over all cells in the board.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    /// use sudokugen::board::CellLoc;
+    /// use std::collections::BTreeSet;
+    ///
+    /// let board = Board::new(BoardSize::FourByFour);
+    ///
+    /// assert_eq!(
+    ///     board.iter_cells().collect::<BTreeSet<CellLoc>>(),
+    ///     (0..4).flat_map(|line| (0..4).map(move |col| {
+    ///         CellLoc::at(line.clone(), col, BoardSize::FourByFour)
+    ///     }))
+    ///         .collect::<BTreeSet<CellLoc>>()
+    /// );
+    /// ```
+    ///
+    /// Keep in mind that this iterates only over the cell location
+    /// not the cell value, in order to access/modify the current value
+    /// you'll need to use the [`get`] and [`set`] methods of this board.
+    ///
+    /// [`get`]: #method.get
+    /// [`set`]: #method.set
+    pub fn iter_cells(&self) -> impl Iterator<Item = CellLoc> {
+        let board_size = self.board_size;
+
+        (0..self.board_size.cell_count()).map(move |idx| CellLoc { idx, board_size })
+    }
+
+    /// Convenience method to return a [`CellLoc`] at this position that is compatible
+    /// with this board (has the same board size). See more about referencing cells by
+    /// line and column using the [`at`] method
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board = Board::new(BoardSize::NineByNine);
+    /// let cell = board.cell_at(1, 1);
+    ///
+    /// assert_eq!((cell.line(), cell.col()), (1, 1));
+    /// ```
+    ///
+    /// [`CellLoc`]: struct.CellLoc.html
+    /// [`at`]: struct.CellLoc.html#at
+    #[must_use]
+    pub fn cell_at(&self, l: usize, c: usize) -> CellLoc {
+        CellLoc::at(l, c, self.board_size())
+    }
+
+    /// Index in `cells` of the cell at line `l` and column `c`, panics if either is
+    /// outside of the board instead of wrapping to another line.
+    /// Writes `value` to the cell at `(l, c)` without checking that it's in range. Boards
+    /// can't hold such values through the public API, but the solver still guards
+    /// against them.
+    #[cfg(test)]
+    pub(crate) fn set_unchecked(&mut self, l: usize, c: usize, value: Option<u8>) {
+        let idx = self.index_at(l, c);
+        self.cells[idx] = value;
+    }
+
+    fn index_at(&self, l: usize, c: usize) -> usize {
+        let width = self.board_size.width();
+        assert!(
+            l < width && c < width,
+            "cell ({}, {}) is outside of a {} board",
+            l,
+            c,
+            self.board_size
+        );
+        self.cell_at(l, c).get_index()
+    }
+
+    /// Returns a new sudoku [`Board`] rotated clockwise by 90deg.
+    ///
+    /// Valid sudoku puzzles are also valid if rotated 90deg, 180deg and 270deg,
+    /// they are the same puzzle, however must people would have trouble realizing that
+    /// they are doing the same puzzle. This function provides a cheap way to turn 1 valid
+    /// puzzle into 4.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "
+    /// 1 2 | . .
+    /// 3 4 | . .
+    /// ---------
+    /// . . | . .
+    /// . . | . .
+    /// ".parse().unwrap();
+    ///
+    /// let rotated_board: Board = "
+    /// . . | 3 1
+    /// . . | 4 2
+    /// ---------
+    /// . . | . .
+    /// . . | . .
+    /// ".parse().unwrap();
+    ///
+    /// assert_eq!(board.rotated(), rotated_board);
+    /// ```
+    pub fn rotated(&self) -> Self {
+        // the boxes of custom sizes are turned as well
+        let mut board = Board::new(self.board_size.transposed());
+        board.variant = self.variant;
+        let width = self.board_size.width();
+
+        for cell in self.iter_cells() {
+            let l = cell.col();
+            let c = width - 1 - cell.line();
+
+            if let Some(value) = self.get(&cell) {
+                board.set_at(l, c, value);
+            }
+        }
+
+        board
+    }
+
+    /// Rotates this board clockwise by 90deg, same as [`Board::rotated`] but without
+    /// allocating a new board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "12.. 34.. .... ....".parse().unwrap();
+    /// board.rotate_in_place();
+    ///
+    /// assert_eq!(board, "..31 ..42 .... ....".parse().unwrap());
+    /// ```
+    pub fn rotate_in_place(&mut self) {
+        let width = self.board_size.width();
+
+        // a clockwise rotation is a transposition followed by mirroring each line
+        for l in 0..width {
+            for c in (l + 1)..width {
+                self.cells.swap(l * width + c, c * width + l);
+            }
+        }
+        self.board_size = self.board_size.transposed();
+
+        self.mirror_horizontal_in_place();
+    }
+
+    /// Returns a new sudoku [`Board`] rotated clockwise by 90deg `quarter_turns` times.
+    /// Turns are counted modulo 4, so 4 quarter turns return the same board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "12.. 34.. .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(board.rotated_n(0), board);
+    /// assert_eq!(board.rotated_n(1), board.rotated());
+    /// assert_eq!(board.rotated_n(2), ".... .... ..43 ..21".parse().unwrap());
+    /// assert_eq!(board.rotated_n(3), ".... .... 24.. 13..".parse().unwrap());
+    /// ```
+    pub fn rotated_n(&self, quarter_turns: u8) -> Self {
+        let width = self.board_size.width();
+        let mut board = self.clone();
+
+        match quarter_turns % 4 {
+            0 => (),
+            1 => board.rotate_in_place(),
+            2 => board.cells.reverse(),
+            _ => {
+                for (idx, cell) in board.cells.iter_mut().enumerate() {
+                    let (l, c) = (idx / width, idx % width);
+                    *cell = self.cells[c * width + (width - 1 - l)];
+                }
+                board.board_size = self.board_size.transposed();
+            }
+        }
+
+        board
+    }
+
+    /// Mirrors this board horizontally, the first column becomes the last and vice-versa.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "12.. 34.. .... ....".parse().unwrap();
+    /// board.mirror_horizontal_in_place();
+    ///
+    /// assert_eq!(board, "..21 ..43 .... ....".parse().unwrap());
+    /// ```
+    pub fn mirror_horizontal_in_place(&mut self) {
+        let width = self.board_size.width();
+
+        for line in self.cells.chunks_mut(width) {
+            line.reverse();
+        }
+    }
+
+    /// Mirrors this board vertically, the first line becomes the last and vice-versa.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "12.. 34.. .... ....".parse().unwrap();
+    /// board.mirror_vertical_in_place();
+    ///
+    /// assert_eq!(board, ".... .... 34.. 12..".parse().unwrap());
+    /// ```
+    pub fn mirror_vertical_in_place(&mut self) {
+        let width = self.board_size.width();
+
+        for l in 0..(width / 2) {
+            for c in 0..width {
+                self.cells.swap(l * width + c, (width - 1 - l) * width + c);
+            }
+        }
+    }
+
+    /// Returns a copy of the board with its values relabeled in order of first appearance,
+    /// reading each line from left to right and the lines from top to bottom: the first
+    /// value found becomes 1, the next different value 2 and so on.
+    ///
+    /// Boards that only differ in which value stands for which, like a puzzle where every
+    /// 1 is swapped with a 2, have the same normalized board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = ".3.. 4... ..3. 1..2".parse().unwrap();
+    /// assert_eq!(board.normalize(), ".1.. 2... ..1. 3..4".parse().unwrap());
+    ///
+    /// let swapped: Board = ".4.. 3... ..4. 1..2".parse().unwrap();
+    /// assert_eq!(swapped.normalize(), board.normalize());
+    /// ```
+    pub fn normalize(&self) -> Board {
+        let mut labels = BTreeMap::new();
+        let cells = self
+            .cells
+            .iter()
+            .map(|cell| {
+                cell.map(|value| {
+                    let next = labels.len() as u8 + 1;
+                    *labels.entry(value).or_insert(next)
+                })
+            })
+            .collect();
+
+        Board {
+            board_size: self.board_size,
+            cells,
+            variant: self.variant,
+        }
+    }
+
+    /// Returns a copy of the board with its lines, columns and values permuted, line `i`
+    /// of the result is line `row_perm[i]` of this board, column `j` is column
+    /// `col_perm[j]`, and every value `v` is replaced by `digit_perm[v - 1]`.
+    ///
+    /// Any combination of the transformations that turn a valid board into another valid
+    /// board can be written this way, so `row_perm` and `col_perm` must not only be
+    /// permutations of `0..width` but also keep the lines, or columns, of each band
+    /// together and `digit_perm` must be a permutation of the values. On boards with
+    /// extra regions, like [`Variant::Hyper`], the lines and columns of each extra region
+    /// must also end up in an extra region. Otherwise [`PermutationError`] says which of
+    /// them is wrong.
+    ///
+    /// ```
+    /// use sudokugen::board::{Board, PermutationError};
+    ///
+    /// let board: Board = "12.. 3... ..4. ...1".parse().unwrap();
+    ///
+    /// // swap the two bands, the two columns of the first stack and the values 1 and 2
+    /// let permuted = board
+    ///     .apply_permutation(&[2, 3, 0, 1], &[1, 0, 2, 3], &[2, 1, 3, 4])
+    ///     .unwrap();
+    /// assert_eq!(permuted, "..4. ...2 12.. .3..".parse().unwrap());
+    ///
+    /// // line 2 can't move to the band of lines 0 and 1 on its own
+    /// assert_eq!(
+    ///     board.apply_permutation(&[2, 1, 0, 3], &[0, 1, 2, 3], &[1, 2, 3, 4]),
+    ///     Err(PermutationError::Rows)
+    /// );
+    /// ```
+    pub fn apply_permutation(
+        &self,
+        row_perm: &[usize],
+        col_perm: &[usize],
+        digit_perm: &[u8],
+    ) -> Result<Board, PermutationError> {
+        let width = self.board_size.width();
+        if !self.is_band_permutation(row_perm, self.board_size.box_rows())
+            || !self.keeps_extra_regions(row_perm, |(line, _)| line, self.board_size.box_rows())
+        {
+            return Err(PermutationError::Rows);
+        }
+        if !self.is_band_permutation(col_perm, self.board_size.box_cols())
+            || !self.keeps_extra_regions(col_perm, |(_, col)| col, self.board_size.box_cols())
+        {
+            return Err(PermutationError::Columns);
+        }
+
+        let mut values: Vec<u8> = digit_perm.to_vec();
+        values.sort_unstable();
+        if !values.iter().copied().eq(1..=width as u8) {
+            return Err(PermutationError::Digits);
+        }
+
+        let mut board = Board::new(self.board_size());
+        board.variant = self.variant;
+        for (l, source_l) in row_perm.iter().enumerate() {
+            for (c, source_c) in col_perm.iter().enumerate() {
+                board.cells[l * width + c] = self.cells[source_l * width + source_c]
+                    .map(|value| digit_perm[value as usize - 1]);
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Returns a copy of the board where every `a` is replaced by `b` and every `b` by `a`,
+    /// a single swap of the values of [`apply_permutation`](Self::apply_permutation).
+    ///
+    /// Returns [`ValueOutOfRangeError`] if either value is not between 1 and the largest
+    /// value of this board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "12.. 3... ..4. ...1".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.swap_values(1, 4).unwrap(),
+    ///     "42.. 3... ..1. ...4".parse().unwrap()
+    /// );
+    /// assert!(board.swap_values(1, 5).is_err());
+    /// ```
+    pub fn swap_values(&self, a: u8, b: u8) -> Result<Board, ValueOutOfRangeError> {
+        let a = self.checked_value(a)?;
+        let b = self.checked_value(b)?;
+
+        let mut board = self.clone();
+        for value in board.cells.iter_mut().flatten() {
+            if *value == a {
+                *value = b;
+            } else if *value == b {
+                *value = a;
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Checks that `perm` is a permutation of `0..width` that moves whole bands, or
+    /// stacks, of `band_width` lines, or columns, and only shuffles them inside the bands.
+    fn is_band_permutation(&self, perm: &[usize], band_width: usize) -> bool {
+        let width = self.board_size.width();
+        let mut sorted = perm.to_vec();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(0..width) {
+            return false;
+        }
+
+        perm.chunks(band_width).all(|band| {
+            band.iter()
+                .all(|idx| idx / band_width == band[0] / band_width)
+        })
+    }
+
+    /// Checks that `perm`, already known to be a permutation, moves the lines, or
+    /// columns, of each extra region of the variant onto the ones of an extra region.
+    /// `start` picks the line, or column, where a region starts from its corner.
+    fn keeps_extra_regions(
+        &self,
+        perm: &[usize],
+        start: fn((usize, usize)) -> usize,
+        region_width: usize,
+    ) -> bool {
+        let regions: Vec<BTreeSet<usize>> = (0..self.variant.extra_block_count())
+            .map(|block| {
+                let first = start(self.variant.extra_block_corner(block));
+                (first..first + region_width).collect()
+            })
+            .collect();
+
+        regions.iter().all(|region| {
+            let moved: BTreeSet<usize> = region.iter().map(|idx| perm[*idx]).collect();
+            regions.contains(&moved)
+        })
+    }
+
+    /// Returns which cells of the board are filled, in the same order as
+    /// [`iter_cells`](Self::iter_cells).
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .... .... ...2".parse().unwrap();
+    /// let mask = board.givens_mask();
+    ///
+    /// assert_eq!(mask.iter().filter(|given| **given).count(), 2);
+    /// assert!(mask[0] && mask[15]);
+    /// ```
+    pub fn givens_mask(&self) -> Vec<bool> {
+        self.cells.iter().map(|cell| cell.is_some()).collect()
+    }
+
+    /// Returns true if the pattern of filled cells is the same after rotating the board
+    /// 180 degrees, the values in the cells are ignored.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    /// assert!(board.has_rotational_symmetry());
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// assert!(!board.has_rotational_symmetry());
+    /// ```
+    pub fn has_rotational_symmetry(&self) -> bool {
+        let last = self.board_size.width() - 1;
+        self.is_symmetric_by(|l, c| (last - l, last - c))
+    }
+
+    /// Returns whether the pattern of filled cells is the same after mirroring the board
+    /// horizontally and vertically, in the same sense as [`mirror_horizontal_in_place`]
+    /// and [`mirror_vertical_in_place`]. The values in the cells are ignored.
+    ///
+    /// [`mirror_horizontal_in_place`]: Self::mirror_horizontal_in_place
+    /// [`mirror_vertical_in_place`]: Self::mirror_vertical_in_place
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1..2 .... .... ....".parse().unwrap();
+    /// assert_eq!(board.has_mirror_symmetry(), (true, false));
+    ///
+    /// let board: Board = "1... .... .... 2...".parse().unwrap();
+    /// assert_eq!(board.has_mirror_symmetry(), (false, true));
+    /// ```
+    pub fn has_mirror_symmetry(&self) -> (bool, bool) {
+        let last = self.board_size.width() - 1;
+        let horizontal = self.is_symmetric_by(|l, c| (l, last - c));
+        let vertical = self.is_symmetric_by(|l, c| (last - l, c));
+
+        (horizontal, vertical)
+    }
+
+    /// Returns true if the pattern of filled cells is symmetric about the main diagonal,
+    /// from the top left to the bottom right corner, cell `(l, c)` is filled exactly when
+    /// cell `(c, l)` is filled.
+    ///
+    /// ```
+    /// use sudokugen::board;
+    ///
+    /// let board = board![
+    ///     [1, 2, _, _],
+    ///     [3, _, _, _],
+    ///     [_, _, 4, _],
+    ///     [_, _, _, _],
+    /// ];
+    /// assert!(board.has_diagonal_symmetry());
+    /// assert!(!board.has_rotational_symmetry());
+    /// ```
+    pub fn has_diagonal_symmetry(&self) -> bool {
+        self.is_symmetric_by(|l, c| (c, l))
+    }
+
+    /// Checks that every filled cell maps to a filled cell, `mapping` must be its own
+    /// inverse so the empty cells are covered too.
+    fn is_symmetric_by(&self, mapping: impl Fn(usize, usize) -> (usize, usize)) -> bool {
+        self.iter_cells().all(|cell| {
+            let (l, c) = mapping(cell.line(), cell.col());
+            self.get(&cell).is_some() == self.get_at(l, c).is_some()
+        })
+    }
+
+    /// Returns the number of filled cells in each band, the lines of squares from top
+    /// to bottom, and in each stack, the columns of squares from left to right.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "12.. 3... .... ...4".parse().unwrap();
+    ///
+    /// assert_eq!(board.givens_per_band_and_stack(), (vec![3, 1], vec![3, 1]));
+    /// ```
+    pub fn givens_per_band_and_stack(&self) -> (Vec<usize>, Vec<usize>) {
+        let (box_rows, box_cols) = (self.board_size.box_rows(), self.board_size.box_cols());
+        // there are as many bands as columns in a box, and as many stacks as lines
+        let mut bands = vec![0; box_cols];
+        let mut stacks = vec![0; box_rows];
+
+        for cell in self.iter_cells().filter(|cell| self.get(cell).is_some()) {
+            bands[cell.line() / box_rows] += 1;
+            stacks[cell.col() / box_cols] += 1;
+        }
+
+        (bands, stacks)
+    }
+
+    /// Returns a multi-line representation of the board where each cell is rendered
+    /// as a small grid of its candidates the size of a box, also known as
+    /// pencil marks. Empty cells show every value that is still possible in that cell
+    /// and a `.` for the ones that aren't, filled cells show only their value in the
+    /// middle of the grid.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "
+    /// 1 . | . .
+    /// . . | . .
+    /// ---------
+    /// . 2 | . .
+    /// . . | . .
+    /// ".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.to_pencilmark_string(),
+    ///     "\
+    /// 1  .. | .2 .2
+    ///    34 | 34 34
+    ///
+    /// .2 .. | 12 12
+    /// 34 34 | 34 34
+    /// -------------
+    /// .. 2  | 1. 1.
+    /// 34    | 34 34
+    ///
+    /// .. 1. | 12 12
+    /// 34 34 | 34 34
+    /// "
+    /// );
+    /// ```
+    pub fn to_pencilmark_string(&self) -> String {
+        let (box_rows, box_cols) = (self.board_size.box_rows(), self.board_size.box_cols());
+        let width = self.board_size.width();
+        let symbol_width = width.to_string().len();
+        let center = ((box_rows - 1) / 2, (box_cols - 1) / 2);
+
+        let mut lines: Vec<String> = Vec::new();
+
+        for l in 0..width {
+            for sub_line in 0..box_rows {
+                let mut line = String::new();
+
+                for c in 0..width {
+                    if c > 0 && c % box_cols == 0 {
+                        line.push_str(" | ");
+                    } else if c > 0 {
+                        line.push(' ');
+                    }
+
+                    let cell = self.cell_at(l, c);
+                    let possible_values = cell.get_possible_values(self);
+
+                    for sub_col in 0..box_cols {
+                        let symbol = match (self.get(&cell), &possible_values) {
+                            (Some(value), _) if (sub_line, sub_col) == center => value.to_string(),
+                            (Some(_), _) => String::from(" "),
+                            (None, Some(possible_values)) => {
+                                let value = (sub_line * box_cols + sub_col + 1) as u8;
+                                if possible_values.contains(&value) {
+                                    value.to_string()
+                                } else {
+                                    String::from(".")
+                                }
+                            }
+                            (None, None) => unreachable!("empty cells always have possible values"),
+                        };
+
+                        line.push_str(&format!("{:<width$}", symbol, width = symbol_width));
+                    }
+                }
+
+                lines.push(line.trim_end().to_string());
+            }
+
+            if l + 1 < width {
+                if (l + 1) % box_rows == 0 {
+                    let line_len = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+                    lines.push("-".repeat(line_len));
+                } else {
+                    lines.push(String::new());
+                }
+            }
+        }
+
+        let mut result = lines.join("\n");
+        result.push('\n');
+        result
+    }
+
+    /// Returns the board with every cell written as its value, or as the list of its
+    /// possible values if it's empty, one line of the board per line of text. Cells are
+    /// padded to the widest cell of their column and squares are separated by `|` and
+    /// lines of `-`, similar to the pencil mark grids exchanged by programs like HoDoKu.
+    ///
+    /// An empty cell with a single possible value is written in brackets, like `[4]`, so
+    /// [`Board::parse_with_candidates`] doesn't read it back as a filled cell. Empty cells
+    /// without possible values are written as `.`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... ..3. .... .2..".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.to_candidates_string(),
+    ///     "\
+    /// 1  34  | 24  24
+    /// 24 [4] | 3   124
+    /// -------+---------
+    /// 34 134 | 124 1234
+    /// 34 2   | 14  134
+    /// "
+    /// );
+    /// ```
+    pub fn to_candidates_string(&self) -> String {
+        let width = self.board_size.width();
+        let box_cols = self.board_size.box_cols();
+        let symbol = |value: u8| std::char::from_digit(u32::from(value), 17).unwrap_or('?');
+
+        let cells: Vec<String> = self
+            .iter_cells()
+            .map(
+                |cell| match (self.get(&cell), cell.get_possible_values(self)) {
+                    (Some(value), _) => symbol(value).to_string(),
+                    (None, Some(values)) if values.is_empty() => String::from("."),
+                    (None, Some(values)) if values.len() == 1 => {
+                        format!("[{}]", values.into_iter().map(symbol).collect::<String>())
+                    }
+                    (None, Some(values)) => values.into_iter().map(symbol).collect(),
+                    (None, None) => unreachable!("empty cells always have possible values"),
+                },
+            )
+            .collect();
+
+        let col_widths: Vec<usize> = (0..width)
+            .map(|c| {
+                (0..width)
+                    .map(|l| cells[l * width + c].len())
+                    .max()
+                    .unwrap_or(1)
+            })
+            .collect();
+
+        let mut separator = String::new();
+        for (c, col_width) in col_widths.iter().enumerate() {
+            if c > 0 && c % box_cols == 0 {
+                separator.push_str("-+-");
+            } else if c > 0 {
+                separator.push('-');
+            }
+            separator.push_str(&"-".repeat(*col_width));
+        }
+
+        let mut result = String::new();
+        for l in 0..width {
+            if l > 0 && l % self.board_size.box_rows() == 0 {
+                result.push_str(&separator);
+                result.push('\n');
+            }
+
+            let mut line = String::new();
+            for (c, col_width) in col_widths.iter().enumerate() {
+                if c > 0 && c % box_cols == 0 {
+                    line.push_str(" | ");
+                } else if c > 0 {
+                    line.push(' ');
+                }
+                line.push_str(&format!(
+                    "{:<width$}",
+                    cells[l * width + c],
+                    width = col_width
+                ));
+            }
+
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Parses a board written as in [`Board::to_candidates_string`], returning the board
+    /// and the candidates of each of its empty cells. Cells are separated by whitespace,
+    /// a cell with a single value is a filled cell, a cell with more values, or with any
+    /// values in brackets like `[4]`, is an empty cell with those candidates and `.` is an
+    /// empty cell without candidates. Words made only of `|`, `-` and `+` are ignored.
+    ///
+    /// The size of the board is told from the number of cells, so only 4x4, 9x9 and
+    /// 16x16 boards can be parsed, boards of [`BoardSize::Custom`] sizes written by
+    /// [`Board::to_candidates_string`] can't be read back.
+    ///
+    /// The candidates don't need to match the ones possible on the board, they can be a
+    /// state where some of them were already ruled out, see
+    /// [`Board::solve_with_candidates`].
+    ///
+    /// Returns [`MalformedBoardError`] if the number of cells is not the one of a board,
+    /// or if a cell has any other character, a value out of range or the same value twice.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let (board, candidates) = Board::parse_with_candidates("
+    /// 1   4  | 24  24
+    /// 24  24 | 3   14
+    /// -------+-------
+    /// 234 13 | 124 134
+    /// 34  2  | 14  134
+    /// ").unwrap();
+    ///
+    /// assert_eq!(board, "14.. ..3. .... .2..".parse().unwrap());
+    /// assert_eq!(candidates[&board.cell_at(1, 0)], vec![2, 4].into_iter().collect());
+    /// ```
+    pub fn parse_with_candidates(
+        s: &str,
+    ) -> Result<(Board, HashMap<CellLoc, BTreeSet<u8>>), MalformedBoardError> {
+        let cells: Vec<&str> = s
+            .split_whitespace()
+            .filter(|word| !word.chars().all(|c| matches!(c, '|' | '-' | '+')))
+            .collect();
+
+        let base_size = (cells.len() as f64).sqrt().sqrt();
+        if base_size.fract() != 0.0 {
+            return Err(MalformedBoardError);
+        }
+        let board_size: BoardSize = (base_size as usize)
+            .try_into()
+            .map_err(|_| MalformedBoardError)?;
+
+        let mut board = Board::new(board_size);
+        let mut candidates = HashMap::new();
+
+        for (idx, word) in cells.into_iter().enumerate() {
+            let cell = CellLoc::new(idx, board_size);
+            let (word, bracketed) = match word.strip_prefix('[').and_then(|w| w.strip_suffix(']')) {
+                Some("") => return Err(MalformedBoardError),
+                Some(inner) => (inner, true),
+                None => (word, false),
+            };
+            let values = if word == "." {
+                BTreeSet::new()
+            } else {
+                word.chars()
+                    .map(|c| {
+                        c.to_digit(17)
+                            .filter(|value| (1..=u32::from(board_size.max_value())).contains(value))
+                            .map(|value| value as u8)
+                            .ok_or(MalformedBoardError)
+                    })
+                    .collect::<Result<BTreeSet<u8>, _>>()?
+            };
+            if word != "." && values.len() != word.len() {
+                return Err(MalformedBoardError);
+            }
+
+            match (word.len(), values.iter().next()) {
+                (1, Some(value)) if !bracketed => {
+                    board.set(&cell, *value);
+                }
+                _ => {
+                    candidates.insert(cell, values);
+                }
+            }
+        }
+
+        Ok((board, candidates))
+    }
+}
+
+/// The default board is an empty 9x9 board.
+///
+/// ```
+/// use sudokugen::{Board, BoardSize};
+///
+/// let board = Board::default();
+/// assert_eq!(board, Board::new(BoardSize::NineByNine));
+/// ```
+impl Default for Board {
+    fn default() -> Self {
+        Board::new(BoardSize::NineByNine)
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        if self.board_size != other.board_size || self.variant != other.variant {
+            return false;
+        }
+
+        for idx in 0..self.board_size.cell_count() {
+            if self.cells[idx] != other.cells[idx] {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl Eq for Board {}
+
+/// Reads the value of the cell at `(line, column)`, same as [`Board::get_at`].
+///
+/// ```
+/// use sudokugen::Board;
+///
+/// let board: Board = "1... .... .... ...4".parse().unwrap();
+///
+/// assert_eq!(board[(0, 0)], Some(1));
+/// assert_eq!(board[(0, 1)], None);
+/// assert_eq!(board[(3, 3)], Some(4));
+/// ```
+///
+/// Panics if the line or the column is outside of the board.
+impl Index<(usize, usize)> for Board {
+    type Output = Option<u8>;
+
+    fn index(&self, (l, c): (usize, usize)) -> &Option<u8> {
+        &self.cells[self.index_at(l, c)]
+    }
+}
+
+/// Reads the value of `cell`, same as [`Board::get`].
+///
+/// ```
+/// use sudokugen::Board;
+///
+/// let board: Board = "1... .... .... ....".parse().unwrap();
+///
+/// assert_eq!(board[board.cell_at(0, 0)], Some(1));
+/// ```
+impl Index<CellLoc> for Board {
+    type Output = Option<u8>;
+
+    fn index(&self, cell: CellLoc) -> &Option<u8> {
+        debug_assert_eq!(
+            cell.board_size, self.board_size,
+            "cell and board sizes differ"
+        );
+        &self.cells[cell.idx]
+    }
+}
+
+impl fmt::Display for Board {
+    /// Writes the board one line per line of text, with each value followed by a space
+    /// and a dot for each empty cell. Values above 9, only found in boards wider than 9
+    /// cells, are written as the letters `a` to `g` like in
+    /// [`to_line_string`](Board::to_line_string), so every value takes a single column.
+    ///
+    /// The output of 4x4, 9x9 and 16x16 boards can always be parsed back with
+    /// [`parse`](str::parse). The size of [`Custom`](BoardSize::Custom) boards can't be
+    /// told from the number of cells, their output is parsed back with
+    /// [`Board::parse_with_size`] instead.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    /// assert_eq!(board.to_string(), "1 . . . \n. 2 . . \n. . 3 . \n. . . 4 \n");
+    ///
+    /// let mut board = Board::new(BoardSize::SixteenBySixteen);
+    /// board.set_at(0, 0, 10);
+    /// board.set_at(0, 1, 16);
+    /// assert!(board.to_string().starts_with("a g . "));
+    /// assert_eq!(board.to_string().parse::<Board>(), Ok(board));
+    ///
+    /// let six = BoardSize::custom(2, 3).unwrap();
+    /// let mut board = Board::new(six);
+    /// board.set_at(0, 0, 6);
+    /// assert!(board.to_string().parse::<Board>().is_err());
+    /// assert_eq!(Board::parse_with_size(&board.to_string(), six), Ok(board));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for l in 0..self.board_size.width() {
+            for c in 0..self.board_size.width() {
+                if let Some(value) = self.cells[l * self.board_size.width() + c] {
+                    let value = std::char::from_digit(u32::from(value), 17).unwrap_or('?');
+                    write!(f, "{} ", value)?;
+                } else {
+                    write!(f, ". ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when the representation of the board cannot be parsed successfully.
+///
+/// Boards have constraints that cannot be represented in easy to transfer formats (such as strings),
+/// A 9x9 board for instance must have exactly 81 cells with values ranging between 1 and 9.
+/// This error is returned when those constraints are not met.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedBoardError;
+
+impl fmt::Display for MalformedBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "This board is not correctly formed")
+    }
+}
+
+// This is important for other errors to wrap this one.
+impl error::Error for MalformedBoardError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+/// Error returned when a [`CellLoc`] is used to access a board it does not belong to,
+/// either because it was created for a board of a different size or because its
+/// index is past the end of the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeMismatchError {
+    cell: CellLoc,
+    board_size: BoardSize,
+}
+
+impl fmt::Display for SizeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Cell {} is not a valid location in a {} board",
+            self.cell, self.board_size
+        )
+    }
+}
+
+impl error::Error for SizeMismatchError {}
+
+/// Error returned when a board is combined with a board of a different size, see
+/// [`Board::overlay`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardSizeMismatchError {
+    expected: BoardSize,
+    found: BoardSize,
+}
+
+impl fmt::Display for BoardSizeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Expected a {} board, found a {} board",
+            self.expected, self.found
+        )
+    }
+}
+
+impl error::Error for BoardSizeMismatchError {}
+
+/// Error returned by [`Board::set_variant`] when the variant can't be used with boards
+/// of that size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedVariantError {
+    variant: Variant,
+    board_size: BoardSize,
+}
+
+impl fmt::Display for UnsupportedVariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is not supported on {} boards",
+            self.variant, self.board_size
+        )
+    }
+}
+
+impl error::Error for UnsupportedVariantError {}
+
+/// Error returned by [`Board::apply_permutation`] when one of the permutations doesn't
+/// turn valid boards into valid boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermutationError {
+    /// The line permutation is not a permutation of the lines, splits a band or moves
+    /// the lines of an extra region apart.
+    Rows,
+    /// The column permutation is not a permutation of the columns, splits a stack or
+    /// moves the columns of an extra region apart.
+    Columns,
+    /// The value permutation is not a permutation of the values of the board.
+    Digits,
+}
+
+impl fmt::Display for PermutationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Rows => write!(
+                f,
+                "The line permutation must keep the lines of each band and extra region together"
+            ),
+            Self::Columns => write!(
+                f,
+                "The column permutation must keep the columns of each stack and extra region together"
+            ),
+            Self::Digits => write!(f, "The value permutation must use every value once"),
+        }
+    }
+}
+
+impl error::Error for PermutationError {}
+
+/// A value that can be placed in a cell, from 1 up to 16, the largest value of any board.
+///
+/// The setters of [`Board`] accept anything that converts into a `CellValue`, so plain
+/// `u8` values keep working and values out of range are rejected when they are set
+/// instead of leaving the board in a state no solver can handle.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use sudokugen::board::CellValue;
+/// use sudokugen::BoardSize;
+///
+/// assert_eq!(CellValue::try_from(16).unwrap().get(), 16);
+/// assert!(CellValue::try_from(0).is_err());
+///
+/// assert!(CellValue::new(9, BoardSize::NineByNine).is_ok());
+/// assert!(CellValue::new(10, BoardSize::NineByNine).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellValue(u8);
+
+impl CellValue {
+    /// Returns the value if it's between 1 and the largest value of a board of
+    /// `board_size`, or [`ValueOutOfRangeError`] otherwise.
+    ///
+    /// ```
+    /// use sudokugen::board::CellValue;
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(CellValue::new(4, BoardSize::FourByFour).unwrap().get(), 4);
+    /// assert!(CellValue::new(5, BoardSize::FourByFour).is_err());
+    /// ```
+    pub fn new(value: u8, board_size: BoardSize) -> Result<Self, ValueOutOfRangeError> {
+        if value == 0 || value > board_size.max_value() {
+            return Err(ValueOutOfRangeError {
+                value,
+                max_value: board_size.max_value(),
+            });
+        }
+
+        Ok(CellValue(value))
+    }
+
+    /// Returns the value as a number.
+    ///
+    /// ```
+    /// use sudokugen::board::CellValue;
+    /// use sudokugen::BoardSize;
+    ///
+    /// assert_eq!(CellValue::new(3, BoardSize::NineByNine).unwrap().get(), 3);
+    /// ```
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for CellValue {
+    type Error = ValueOutOfRangeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        CellValue::new(value, BoardSize::SixteenBySixteen)
+    }
+}
+
+impl From<CellValue> for u8 {
+    fn from(value: CellValue) -> Self {
+        value.0
+    }
+}
+
+/// Error returned when a value is 0 or larger than the largest value of the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueOutOfRangeError {
+    value: u8,
+    max_value: u8,
+}
+
+impl fmt::Display for ValueOutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Value {} is out of range, values go from 1 to {}",
+            self.value, self.max_value
+        )
+    }
+}
+
+impl error::Error for ValueOutOfRangeError {}
+
+/// Converting a [`CellValue`] into itself never fails.
+impl From<Infallible> for ValueOutOfRangeError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// Error returned by [`Board::try_set_value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetValueError {
+    /// See [`SizeMismatchError`].
+    SizeMismatch(SizeMismatchError),
+    /// See [`ValueOutOfRangeError`].
+    ValueOutOfRange(ValueOutOfRangeError),
+}
+
+impl fmt::Display for SetValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SizeMismatch(err) => err.fmt(f),
+            Self::ValueOutOfRange(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for SetValueError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::SizeMismatch(err) => Some(err),
+            Self::ValueOutOfRange(err) => Some(err),
+        }
+    }
+}
+
+impl FromStr for Board {
+    type Err = MalformedBoardError;
+
+    /// Parses a board from a string. A board will be parsed from a string with each digit
+    /// representing a value in the board. Separator characters like space ('` `'), newline ('`\n`'),
+    /// underscore ('`_`'), dash ('`-`'), and pipe ('`|`') are ignored to allow a more friendly formatting.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// let board: Board = "
+    /// 1 . . | . . . | . . .
+    /// . 2 . | . . . | . . .
+    /// . . 3 | . . . | . . .
+    /// ---------------------
+    /// . . . | 4 . . | . . .
+    /// . . . | . 5 . | . . .
+    /// . . . | . . 6 | . . .
+    /// ---------------------
+    /// . . . | . . . | 7 . .
+    /// . . . | . . . | . 8 .
+    /// . . . | . . . | . . 9
+    /// ".parse().unwrap();
+    /// ```
+    ///
+    /// Alternatively a more streamlined format can be used, which is the same but without any formatting characters.
+    /// ```
+    /// use sudokugen::board::Board;
+    /// let board: Board = "123456789........................................................................".parse().unwrap();
+    /// ```
+    ///
+    fn from_str(board_as_string: &str) -> Result<Self, Self::Err> {
+        let board_as_string = strip_separators(board_as_string);
+
+        let base_size = (board_as_string.len() as f64).sqrt().sqrt();
+
+        if base_size.fract() != 0.0 {
+            return Err(MalformedBoardError);
+        }
+
+        let board_size: BoardSize = (base_size as usize)
+            .try_into()
+            .map_err(|_| MalformedBoardError)?;
+
+        parse_cells(&board_as_string, board_size)
+    }
+}
+
+/// Removes the separator characters accepted between the cells of a board.
+fn strip_separators(board_as_string: &str) -> String {
+    board_as_string
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '\n' | '_' | '-' | '|'))
+        .collect()
+}
+
+/// Parses a board of `board_size` from a string of one character per cell.
+fn parse_cells(board_as_string: &str, board_size: BoardSize) -> Result<Board, MalformedBoardError> {
+    if board_as_string.len() != board_size.cell_count() {
+        return Err(MalformedBoardError);
+    }
+
+    let mut table = Board::new(board_size);
+
+    for (idx, c) in board_as_string.char_indices() {
+        match c {
+            // values above 9 use the same letters as `to_line_string`
+            '1'..='9' | 'a'..='g' => {
+                table
+                    .try_set_value(
+                        &CellLoc::new(idx, board_size),
+                        c.to_digit(17).unwrap() as u8,
+                    )
+                    .map_err(|_| MalformedBoardError)?;
+            }
+            '.' => continue,
+            _ => return Err(MalformedBoardError), // _ => panic!("All characters in the board representation should be digits or a spacing character '.', '-', '|' or '\\n'")
+        }
+    }
+
+    Ok(table)
+}
+
+/// Error returned by [`Board::parse_grid`] naming the row of the grid that could not be
+/// parsed. Rows and columns are numbered from 1, separator lines are not counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MalformedGridError {
+    /// The first row has a number of cells that is not the width of any board size.
+    Width {
+        /// The number of cells in the first row
+        len: usize,
+    },
+    /// A row has a different number of cells than the first one.
+    RowLength {
+        /// The 1 based number of the row
+        row: usize,
+        /// The number of cells in the row
+        len: usize,
+        /// The number of cells in the first row
+        expected: usize,
+    },
+    /// A cell is neither a dot nor a value of the board.
+    InvalidCell {
+        /// The 1 based number of the row
+        row: usize,
+        /// The 1 based number of the column
+        col: usize,
+        /// The character found in the cell
+        character: char,
+    },
+    /// The grid has a different number of rows than cells in each row.
+    RowCount {
+        /// The number of rows in the grid
+        rows: usize,
+        /// The number of cells in each row
+        expected: usize,
+    },
+}
+
+impl fmt::Display for MalformedGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Width { len } => write!(
+                f,
+                "Row 1 has {} cells, boards are 4, 9 or 16 cells wide",
+                len
+            ),
+            Self::RowLength { row, len, expected } => {
+                write!(f, "Row {} has {} cells instead of {}", row, len, expected)
+            }
+            Self::InvalidCell {
+                row,
+                col,
+                character,
+            } => write!(
+                f,
+                "Row {} column {} has '{}' which is not a value of the board",
+                row, col, character
+            ),
+            Self::RowCount { rows, expected } => {
+                write!(f, "The grid has {} rows instead of {}", rows, expected)
+            }
+        }
+    }
+}
+
+impl error::Error for MalformedGridError {}
+
+/// Error returned by [`Board::from_csv`] naming the line of the text that could not be
+/// parsed. Rows and columns are numbered from 1, empty lines are not counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MalformedCsvError {
+    /// The number of rows is not the width of any board size.
+    RowCount {
+        /// The number of rows in the text
+        rows: usize,
+    },
+    /// A row doesn't have one field for each column of the board.
+    RowLength {
+        /// The 1 based number of the row
+        row: usize,
+        /// The number of fields in the row
+        len: usize,
+        /// The number of columns of the board
+        expected: usize,
+    },
+    /// A field is neither empty, a dot nor a value of the board.
+    InvalidValue {
+        /// The 1 based number of the row
+        row: usize,
+        /// The 1 based number of the column
+        col: usize,
+        /// The text of the field
+        field: String,
+    },
+}
+
+impl fmt::Display for MalformedCsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RowCount { rows } => {
+                write!(f, "The CSV has {} rows, boards have 4, 9 or 16 rows", rows)
+            }
+            Self::RowLength { row, len, expected } => {
+                write!(f, "Row {} has {} fields instead of {}", row, len, expected)
+            }
+            Self::InvalidValue { row, col, field } => write!(
+                f,
+                "Row {} column {} has '{}' which is not a value of the board",
+                row, col, field
+            ),
+        }
+    }
+}
+
+impl error::Error for MalformedCsvError {}
+
+/// Forgets where the CSV was malformed, for callers that handle every board that can't
+/// be parsed the same way.
+impl From<MalformedCsvError> for MalformedBoardError {
+    fn from(_: MalformedCsvError) -> Self {
+        MalformedBoardError
+    }
+}
+
+impl Board {
+    /// Parses a board of `board_size` in the same format as [`from_str`](#method.from_str),
+    /// for sizes that can't be told apart by the number of cells, like the 6x6 and 12x12
+    /// [`Custom`](BoardSize::Custom) sizes.
+    ///
+    /// Returns [`MalformedBoardError`] if the string doesn't have one value or `.` for each
+    /// cell of the board or has a value larger than the width of the board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::BoardSize;
+    ///
+    /// let six = BoardSize::custom(2, 3).unwrap();
+    /// let board = Board::parse_with_size("
+    /// 1 2 3 | . . .
+    /// . . . | 1 2 3
+    /// -------------
+    /// . . . | . . .
+    /// . . . | . . .
+    /// -------------
+    /// . . . | . . .
+    /// . . . | . . 6
+    /// ", six).unwrap();
+    ///
+    /// assert_eq!(board.board_size(), six);
+    /// assert_eq!(board.get_at(5, 5), Some(6));
+    ///
+    /// assert!(Board::parse_with_size("1... .2.. ..3. ...4", six).is_err());
+    /// assert!(Board::parse_with_size("7........................ ...........", six).is_err());
+    /// ```
+    pub fn parse_with_size(s: &str, board_size: BoardSize) -> Result<Board, MalformedBoardError> {
+        parse_cells(&strip_separators(s), board_size)
+    }
+
+    /// Parses a board written as a grid, one row per line, checking that every row has the
+    /// same number of cells.
+    ///
+    /// Unlike [`from_str`](#method.from_str), which joins all lines together, a row with a
+    /// missing or extra cell is reported with its row number instead of shifting every cell
+    /// after it. Cells are written as in `from_str`, spaces and pipes (`|`) inside a row are
+    /// ignored, as are empty lines and lines made only of `-`, `+`, `_`, `=` and `|`.
+    ///
+    /// ```
+    /// use sudokugen::board::{Board, MalformedGridError};
+    ///
+    /// let board = Board::parse_grid("
+    /// 1 . | . .
+    /// . 2 | . .
+    /// ----+----
+    /// . . | 3 .
+    /// . . | . 4
+    /// ").unwrap();
+    /// assert_eq!(board, "1... .2.. ..3. ...4".parse().unwrap());
+    ///
+    /// assert_eq!(
+    ///     Board::parse_grid("1...\n.2.\n..3.\n...4"),
+    ///     Err(MalformedGridError::RowLength { row: 2, len: 3, expected: 4 })
+    /// );
+    /// ```
+    pub fn parse_grid(grid: &str) -> Result<Board, MalformedGridError> {
+        let rows: Vec<Vec<char>> = grid
+            .lines()
+            .filter(|line| {
+                !line
+                    .chars()
+                    .all(|c| c.is_whitespace() || "-+_=|".contains(c))
+            })
+            .map(|line| {
+                line.chars()
+                    .filter(|c| !c.is_whitespace() && *c != '|')
+                    .collect()
+            })
+            .collect();
+
+        let width = rows.first().map_or(0, Vec::len);
+        let board_size = match width {
+            4 => BoardSize::FourByFour,
+            9 => BoardSize::NineByNine,
+            16 => BoardSize::SixteenBySixteen,
+            len => return Err(MalformedGridError::Width { len }),
+        };
+
+        let mut board = Board::new(board_size);
+        for (line, row) in rows.iter().enumerate() {
+            if line == width {
+                return Err(MalformedGridError::RowCount {
+                    rows: rows.len(),
+                    expected: width,
+                });
+            }
+            if row.len() != width {
+                return Err(MalformedGridError::RowLength {
+                    row: line + 1,
+                    len: row.len(),
+                    expected: width,
+                });
+            }
+
+            for (col, character) in row.iter().enumerate() {
+                if *character == '.' {
+                    continue;
+                }
+
+                match character.to_digit(17) {
+                    Some(value) if value >= 1 && value as usize <= width => {
+                        board.set_at(line, col, value as u8);
+                    }
+                    _ => {
+                        return Err(MalformedGridError::InvalidCell {
+                            row: line + 1,
+                            col: col + 1,
+                            character: *character,
+                        })
+                    }
+                }
+            }
+        }
+
+        if rows.len() != width {
+            return Err(MalformedGridError::RowCount {
+                rows: rows.len(),
+                expected: width,
+            });
+        }
+
+        Ok(board)
+    }
+}
+
+impl Board {
+    /// Number of bits needed to store a single cell, enough to represent
+    /// an empty cell (0) or any value in the board.
+    fn bits_per_cell(board_size: BoardSize) -> usize {
+        let width = board_size.width();
+        (usize::BITS - width.leading_zeros()) as usize
+    }
+
+    /// Serializes the board into a compact binary representation.
+    ///
+    /// The first byte holds the base size of the board, followed by the value of each cell
+    /// packed using as few bits as the size of the board allows, 0 representing an empty cell.
+    /// A 9x9 board uses 4 bits per cell and fits in 42 bytes. [`Custom`](BoardSize::Custom)
+    /// sizes and boards with a [`Variant`] other than [`Standard`](Variant::Standard) start
+    /// with a 0 byte followed by the lines and columns of each box and the variant instead,
+    /// 0 for standard boards and 1 for [`Hyper`](Variant::Hyper).
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board =
+    ///     "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+    ///         .parse()
+    ///         .unwrap();
+    ///
+    /// let data = board.to_binary();
+    /// assert_eq!(data.len(), 42);
+    /// assert_eq!(Board::from_binary(&data).unwrap(), board);
+    /// ```
+    pub fn to_binary(&self) -> Vec<u8> {
+        let bits_per_cell = Self::bits_per_cell(self.board_size);
+        let is_custom = matches!(self.board_size, BoardSize::Custom { .. });
+        let mut data = if is_custom || self.variant != Variant::Standard {
+            let variant = match self.variant {
+                Variant::Standard => 0,
+                Variant::Hyper => 1,
+            };
+            vec![
+                0,
+                self.board_size.box_rows() as u8,
+                self.board_size.box_cols() as u8,
+                variant,
+            ]
+        } else {
+            vec![self.board_size.get_base_size() as u8]
+        };
+        let header_len = data.len();
+        data.resize(header_len + (self.cells.len() * bits_per_cell + 7) / 8, 0);
+
+        for (idx, cell) in self.cells.iter().enumerate() {
+            let value = cell.unwrap_or(0);
+            for bit in 0..bits_per_cell {
+                if value & (1 << (bits_per_cell - 1 - bit)) != 0 {
+                    let pos = idx * bits_per_cell + bit;
+                    data[header_len + pos / 8] |= 0x80 >> (pos % 8);
+                }
+            }
+        }
+
+        data
+    }
+
+    /// Deserializes a board from the binary representation created by [`Board::to_binary`].
+    ///
+    /// Returns [`MalformedBoardError`] if the data has an unexpected length, the base size
+    /// or the variant are not supported or any of the cells holds a value that is too big
+    /// for the board.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let mut board = Board::new(BoardSize::FourByFour);
+    /// board.set_at(1, 2, 3);
+    ///
+    /// assert_eq!(Board::from_binary(&board.to_binary()).unwrap(), board);
+    /// assert!(Board::from_binary(&[3, 0, 0]).is_err());
+    /// ```
+    pub fn from_binary(data: &[u8]) -> Result<Board, MalformedBoardError> {
+        let (board_size, variant, packed) = match data {
+            [0, box_rows, box_cols, variant, packed @ ..] => (
+                BoardSize::custom(usize::from(*box_rows), usize::from(*box_cols))
+                    .map_err(|_| MalformedBoardError)?,
+                match variant {
+                    0 => Variant::Standard,
+                    1 => Variant::Hyper,
+                    _ => return Err(MalformedBoardError),
+                },
+                packed,
+            ),
+            [0, ..] | [] => return Err(MalformedBoardError),
+            [base_size, packed @ ..] => (
+                usize::from(*base_size)
+                    .try_into()
+                    .map_err(|_| MalformedBoardError)?,
+                Variant::Standard,
+                packed,
+            ),
+        };
+        let mut board = Board::new(board_size);
+        board
+            .set_variant(variant)
+            .map_err(|_| MalformedBoardError)?;
+
+        let bits_per_cell = Self::bits_per_cell(board_size);
+        if packed.len() != (board.cells.len() * bits_per_cell + 7) / 8 {
+            return Err(MalformedBoardError);
+        }
+
+        for idx in 0..board.cells.len() {
+            let mut value = 0;
+            for bit in 0..bits_per_cell {
+                let pos = idx * bits_per_cell + bit;
+                value <<= 1;
+                if packed[pos / 8] & (0x80 >> (pos % 8)) != 0 {
+                    value |= 1;
+                }
+            }
+
+            if value as usize > board.board_size.width() {
+                return Err(MalformedBoardError);
+            }
+
+            if value != 0 {
+                board.cells[idx] = Some(value);
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Serializes the board into a URL-safe base64 string, using the binary
+    /// representation from [`Board::to_binary`]. The string uses only letters, digits,
+    /// `-` and `_` so it can be used in URLs without escaping.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board =
+    ///     "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+    ///         .parse()
+    ///         .unwrap();
+    ///
+    /// let encoded = board.to_base64();
+    /// assert_eq!(encoded.len(), 56);
+    /// assert_eq!(Board::from_base64(&encoded).unwrap(), board);
+    /// ```
+    pub fn to_base64(&self) -> String {
+        let data = self.to_binary();
+        let mut encoded = String::with_capacity((data.len() * 4 + 2) / 3);
+
+        for chunk in data.chunks(3) {
+            let bytes = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+            let group = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+
+            for i in 0..=chunk.len() {
+                let sextet = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            }
+        }
+
+        encoded
+    }
+
+    /// Deserializes a board from the string created by [`Board::to_base64`].
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board = Board::new(BoardSize::FourByFour);
+    ///
+    /// assert_eq!(Board::from_base64(&board.to_base64()).unwrap(), board);
+    /// assert!(Board::from_base64("not base64!").is_err());
+    /// ```
+    pub fn from_base64(encoded: &str) -> Result<Board, MalformedBoardError> {
+        let sextets = encoded
+            .bytes()
+            .map(|byte| {
+                BASE64_ALPHABET
+                    .iter()
+                    .position(|&symbol| symbol == byte)
+                    .map(|sextet| sextet as u32)
+                    .ok_or(MalformedBoardError)
+            })
+            .collect::<Result<Vec<u32>, MalformedBoardError>>()?;
+
+        let mut data = Vec::with_capacity(sextets.len() * 3 / 4);
+        for chunk in sextets.chunks(4) {
+            if chunk.len() == 1 {
+                return Err(MalformedBoardError);
+            }
+
+            let group = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |group, (i, sextet)| group | sextet << (18 - 6 * i));
+
+            for i in 0..chunk.len() - 1 {
+                data.push((group >> (16 - 8 * i)) as u8);
+            }
+        }
+
+        Board::from_binary(&data)
+    }
+}
+
+impl Board {
+    /// Returns the board in a single line, the streamlined format accepted by
+    /// [`from_str`](#method.from_str), with a digit for each value and a dot for each
+    /// empty cell.
+    ///
+    /// Values above 9, only found in 16x16 boards, are written as the letters `a` to `g`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    ///
+    /// assert_eq!(board.to_line_string(), "1....2....3....4");
+    /// assert_eq!(board.to_line_string().parse::<Board>().unwrap(), board);
+    /// ```
+    pub fn to_line_string(&self) -> String {
+        self.cells
+            .iter()
+            .map(|cell| match cell {
+                Some(value) => std::char::from_digit(u32::from(*value), 17).unwrap_or('?'),
+                None => '.',
+            })
+            .collect()
+    }
+
+    /// Returns the board as comma separated values, one line of the board per line of text.
+    /// Empty cells are left as empty fields.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    ///
+    /// assert_eq!(board.to_csv(), "1,,,\n,2,,\n,,3,\n,,,4\n");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let width = self.board_size.width();
+        let mut csv = String::new();
+
+        for line in self.cells.chunks(width) {
+            let fields: Vec<String> = line
+                .iter()
+                .map(|cell| cell.map(|value| value.to_string()).unwrap_or_default())
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Parses a board from comma separated values, in the format returned by [`Board::to_csv`].
+    /// Empty cells can be empty fields or a dot (`.`), empty lines are ignored and spaces
+    /// around the values are allowed.
+    ///
+    /// Returns [`MalformedCsvError`] if the number of lines is not a valid board size, if any
+    /// line doesn't have one field for each column of the board or if any value is not a number
+    /// between 1 and the width of the board. Unlike the other parsers the error names the row
+    /// and column at fault, since a ragged row is hard to find in a spreadsheet otherwise. It
+    /// converts into the [`MalformedBoardError`] they return.
+    ///
+    /// ```
+    /// use sudokugen::board::{Board, MalformedBoardError, MalformedCsvError};
+    ///
+    /// let board = Board::from_csv("1,,,\n,2,,\n,,3,\n,,,4").unwrap();
+    /// assert_eq!(board, "1... .2.. ..3. ...4".parse().unwrap());
+    ///
+    /// assert_eq!(
+    ///     Board::from_csv("1, ., ., .\n., 2, ., .\n., ., 3, .\n., ., ., 4").unwrap(),
+    ///     board
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Board::from_csv("1,,,\n,2,\n,,3,\n,,,4"),
+    ///     Err(MalformedCsvError::RowLength { row: 2, len: 3, expected: 4 })
+    /// );
+    ///
+    /// fn parse(csv: &str) -> Result<Board, MalformedBoardError> {
+    ///     Ok(Board::from_csv(csv)?)
+    /// }
+    /// assert_eq!(parse("1,.,.,.\n.,..,.,.\n.,.,3,.\n.,.,.,4"), Err(MalformedBoardError));
+    /// ```
+    pub fn from_csv(csv: &str) -> Result<Board, MalformedCsvError> {
+        let lines: Vec<&str> = csv
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let row_count = MalformedCsvError::RowCount { rows: lines.len() };
+
+        let base_size = (lines.len() as f64).sqrt();
+        if base_size.fract() != 0.0 {
+            return Err(row_count);
+        }
+
+        let board_size: BoardSize = (base_size as usize).try_into().map_err(|_| row_count)?;
+        let mut board = Board::new(board_size);
+
+        for (l, line) in lines.iter().enumerate() {
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            if fields.len() != board_size.width() {
+                return Err(MalformedCsvError::RowLength {
+                    row: l + 1,
+                    len: fields.len(),
+                    expected: board_size.width(),
+                });
+            }
+
+            for (c, field) in fields.iter().enumerate() {
+                if field.is_empty() || *field == "." {
+                    continue;
+                }
+
+                let value = field
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|value| (1..=board_size.max_value()).contains(value))
+                    .ok_or_else(|| MalformedCsvError::InvalidValue {
+                        row: l + 1,
+                        col: c + 1,
+                        field: field.to_string(),
+                    })?;
+
+                board.set_at(l, c, value);
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Parses every grid in the format of the Project Euler problem 96 puzzle file, a
+    /// `Grid NN` header followed by one line per row of the board, with a digit for each
+    /// value and `0` for each empty cell. Empty lines are ignored.
+    ///
+    /// Returns [`MalformedBoardError`] if the text doesn't start with a header, if the
+    /// number of lines of a grid is not a valid board size or if any line doesn't have one
+    /// value for each column of the board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let boards = Board::from_euler_format("
+    /// Grid 01
+    /// 1000
+    /// 0200
+    /// 0030
+    /// 0004
+    /// Grid 02
+    /// 0000
+    /// 0000
+    /// 0000
+    /// 0000
+    /// ").unwrap();
+    ///
+    /// assert_eq!(boards.len(), 2);
+    /// assert_eq!(boards[0], "1... .2.. ..3. ...4".parse().unwrap());
+    ///
+    /// assert!(Board::from_euler_format("1000\n0200\n0030\n0004").is_err());
+    /// ```
+    pub fn from_euler_format(s: &str) -> Result<Vec<Board>, MalformedBoardError> {
+        let mut grids: Vec<Vec<&str>> = Vec::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if line.starts_with("Grid") {
+                grids.push(Vec::new());
+            } else {
+                grids.last_mut().ok_or(MalformedBoardError)?.push(line);
+            }
+        }
+
+        grids
+            .into_iter()
+            .map(|lines| {
+                let base_size = (lines.len() as f64).sqrt();
+                if base_size.fract() != 0.0 {
+                    return Err(MalformedBoardError);
+                }
+
+                let board_size: BoardSize = (base_size as usize)
+                    .try_into()
+                    .map_err(|_| MalformedBoardError)?;
+                let mut board = Board::new(board_size);
+
+                for (l, line) in lines.iter().enumerate() {
+                    if line.chars().count() != board_size.width() {
+                        return Err(MalformedBoardError);
+                    }
+
+                    for (c, digit) in line.chars().enumerate() {
+                        let value = digit
+                            .to_digit(17)
+                            .filter(|value| *value <= u32::from(board_size.max_value()))
+                            .ok_or(MalformedBoardError)?;
+                        if value != 0 {
+                            board.set_at(l, c, value as u8);
+                        }
+                    }
+                }
+
+                Ok(board)
+            })
+            .collect()
+    }
+
+    /// Writes `boards` in the format read by [`Board::from_euler_format`], numbering the
+    /// grids from `start_idx`. Values above 9, only found in 16x16 boards, are written as
+    /// the letters `a` to `g`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let boards: Vec<Board> = vec![
+    ///     "1... .2.. ..3. ...4".parse().unwrap(),
+    ///     "...4 .... .... ....".parse().unwrap(),
+    /// ];
+    /// let text = Board::to_euler_format(&boards, 1);
+    ///
+    /// assert_eq!(text, "Grid 01\n1000\n0200\n0030\n0004\nGrid 02\n0004\n0000\n0000\n0000\n");
+    /// assert_eq!(Board::from_euler_format(&text).unwrap(), boards);
+    /// ```
+    pub fn to_euler_format(boards: &[Board], start_idx: usize) -> String {
+        let mut text = String::new();
+
+        for (idx, board) in boards.iter().enumerate() {
+            text.push_str(&format!("Grid {:02}\n", start_idx + idx));
+            let line = board.to_line_string().replace('.', "0");
+            for row in line.as_bytes().chunks(board.board_size().width()) {
+                text.push_str(std::str::from_utf8(row).expect("The line string is ascii"));
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+
+    /// Parses a board in the format of the SadMan Software sudoku program, one line per
+    /// row of the board with `#` or `.` for each empty cell.
+    ///
+    /// Files of that program may start with metadata lines, a `#` followed by an
+    /// uppercase letter and a space such as `#A Anonymous` for the author, and may put
+    /// the board in a `[Puzzle]` section, these are skipped. Values above 9 can be
+    /// written with uppercase letters too. Any section after the board, like the
+    /// `[State]` saved by the program, is ignored. Spaces and the separators accepted by
+    /// [`Board::from_str`] can be used between cells.
+    ///
+    /// Returns [`MalformedBoardError`] if the number of cells is not the one of a board,
+    /// or if there is any other character.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board = Board::from_sadman("
+    /// #A Anonymous
+    /// #D A small puzzle
+    /// [Puzzle]
+    /// 1#34
+    /// #4#1
+    /// 2###
+    /// #1#3
+    /// ").unwrap();
+    ///
+    /// assert_eq!(board, "1.34 .4.1 2... .1.3".parse().unwrap());
+    /// ```
+    pub fn from_sadman(s: &str) -> Result<Board, MalformedBoardError> {
+        let mut cells = String::new();
+        let mut sections = 0;
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if line.starts_with('[') {
+                sections += 1;
+                // the board is in the first section, or before any section at all
+                if sections > 1 || !cells.is_empty() {
+                    break;
+                }
+                continue;
+            }
+
+            // a 16x16 row can also start with `#` and a letter, metadata is only read
+            // before the first row of the board
+            let mut chars = line.chars();
+            let is_metadata = chars.next() == Some('#')
+                && chars.next().map_or(false, |c| c.is_ascii_uppercase())
+                && chars.next().map_or(true, |c| c == ' ');
+            if is_metadata && cells.is_empty() {
+                continue;
+            }
+
+            cells.push_str(&line.replace('#', ".").to_ascii_lowercase());
+        }
+
+        cells.parse()
+    }
+
+    /// Returns a LaTeX `tabular` environment with the board, drawing thicker lines
+    /// around each square. Empty cells are left blank.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.to_latex(),
+    ///     "\\begin{tabular}{!{\\vrule width 1.5pt}c|c!{\\vrule width 1.5pt}c|c!{\\vrule width 1.5pt}}
+    /// \\noalign{\\hrule height 1.5pt}
+    /// 1 &   &   &   \\\\ \\hline
+    ///   &   &   &   \\\\ \\noalign{\\hrule height 1.5pt}
+    ///   &   &   &   \\\\ \\hline
+    ///   &   &   &   \\\\ \\noalign{\\hrule height 1.5pt}
+    /// \\end{tabular}
+    /// "
+    /// );
+    /// ```
+    pub fn to_latex(&self) -> String {
+        const THICK_VERTICAL: &str = "!{\\vrule width 1.5pt}";
+        const THICK_HORIZONTAL: &str = "\\noalign{\\hrule height 1.5pt}";
+
+        let width = self.board_size.width();
+        let value_width = width.to_string().len();
+
+        let columns: Vec<String> = (0..self.board_size.box_rows())
+            .map(|_| vec!["c"; self.board_size.box_cols()].join("|"))
+            .collect();
+        let mut latex = format!(
+            "\\begin{{tabular}}{{{}{}{}}}\n{}\n",
+            THICK_VERTICAL,
+            columns.join(THICK_VERTICAL),
+            THICK_VERTICAL,
+            THICK_HORIZONTAL
+        );
+
+        for (l, line) in self.cells.chunks(width).enumerate() {
+            let values: Vec<String> = line
+                .iter()
+                .map(|cell| {
+                    let value = cell.map(|value| value.to_string()).unwrap_or_default();
+                    format!("{:>width$}", value, width = value_width)
+                })
+                .collect();
+
+            let separator = if (l + 1) % self.board_size.box_rows() == 0 {
+                THICK_HORIZONTAL
+            } else {
+                "\\hline"
+            };
+
+            latex.push_str(&format!("{} \\\\ {}\n", values.join(" & "), separator));
+        }
+
+        latex.push_str("\\end{tabular}\n");
+        latex
+    }
+
+    /// Returns an HTML `<table>` with the board, one row of the table per line of the board,
+    /// to be styled with CSS. The table has the class `sudoku` and each cell the class
+    /// `given` if it has a value or `empty` otherwise. Cells on the right and bottom edges
+    /// of a square, except on the edges of the board, also have the classes
+    /// `box-border-right` and `box-border-bottom` to draw thicker lines between squares.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     board.to_html_table(),
+    ///     r#"<table class="sudoku">
+    /// <tr><td class="given">1</td><td class="empty box-border-right"></td><td class="empty"></td><td class="empty"></td></tr>
+    /// <tr><td class="empty box-border-bottom"></td><td class="empty box-border-right box-border-bottom"></td><td class="empty box-border-bottom"></td><td class="empty box-border-bottom"></td></tr>
+    /// <tr><td class="empty"></td><td class="empty box-border-right"></td><td class="empty"></td><td class="empty"></td></tr>
+    /// <tr><td class="empty"></td><td class="empty box-border-right"></td><td class="empty"></td><td class="empty"></td></tr>
+    /// </table>
+    /// "#
+    /// );
+    /// ```
+    pub fn to_html_table(&self) -> String {
+        let width = self.board_size.width();
+        let mut html = String::from("<table class=\"sudoku\">\n");
+
+        for (l, line) in self.cells.chunks(width).enumerate() {
+            html.push_str("<tr>");
+            for (c, cell) in line.iter().enumerate() {
+                let mut classes = vec![if cell.is_some() { "given" } else { "empty" }];
+                if (c + 1) % self.board_size.box_cols() == 0 && c + 1 < width {
+                    classes.push("box-border-right");
+                }
+                if (l + 1) % self.board_size.box_rows() == 0 && l + 1 < width {
+                    classes.push("box-border-bottom");
+                }
+
+                let value = cell.map(|value| value.to_string()).unwrap_or_default();
+                html.push_str(&format!(
+                    "<td class=\"{}\">{}</td>",
+                    classes.join(" "),
+                    value
+                ));
+            }
+            html.push_str("</tr>\n");
+        }
+
+        html.push_str("</table>\n");
+        html
+    }
+
+    /// Returns an SVG image of the board, each cell a square of `cell_size` pixels. Lines
+    /// between squares and around the board are thicker than lines between cells, values
+    /// are centered in their cells. Line widths and font size scale with `cell_size`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// let svg = board.to_svg(50);
+    ///
+    /// assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200" viewBox="0 0 200 200">"#));
+    /// assert!(svg.contains(r#">1</text>"#));
+    /// assert!(svg.trim_end().ends_with("</svg>"));
+    /// ```
+    pub fn to_svg(&self, cell_size: u32) -> String {
+        let width = self.board_size.width();
+        let size = cell_size * width as u32;
+        let thin = f64::from(cell_size) / 40.0;
+        let thick = f64::from(cell_size) / 12.0;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">\n",
+            size = size
+        );
+        svg.push_str(&format!(
+            "<rect x=\"0\" y=\"0\" width=\"{size}\" height=\"{size}\" fill=\"white\"/>\n",
+            size = size
+        ));
+
+        // thin lines first so the thick ones are drawn over them where they cross
+        for thick_line in [false, true] {
+            for idx in 0..=width {
+                let position = cell_size * idx as u32;
+                let stroke_width = if thick_line { thick } else { thin };
+                if (idx % self.board_size.box_cols() == 0) == thick_line {
+                    svg.push_str(&format!(
+                        "<line x1=\"{p}\" y1=\"0\" x2=\"{p}\" y2=\"{size}\" stroke=\"black\" stroke-width=\"{w}\" stroke-linecap=\"square\"/>\n",
+                        p = position,
+                        size = size,
+                        w = stroke_width
+                    ));
+                }
+                if (idx % self.board_size.box_rows() != 0) == thick_line {
+                    continue;
+                }
+                svg.push_str(&format!(
+                    "<line x1=\"0\" y1=\"{p}\" x2=\"{size}\" y2=\"{p}\" stroke=\"black\" stroke-width=\"{w}\" stroke-linecap=\"square\"/>\n",
+                    p = position,
+                    size = size,
+                    w = stroke_width
+                ));
+            }
+        }
+
+        for (idx, cell) in self.cells.iter().enumerate() {
+            if let Some(value) = cell {
+                let x = f64::from(cell_size) * (idx % width) as f64 + f64::from(cell_size) / 2.0;
+                let y = f64::from(cell_size) * (idx / width) as f64 + f64::from(cell_size) / 2.0;
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                    x,
+                    y,
+                    f64::from(cell_size) * 0.6,
+                    value
+                ));
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(feature = "colored")]
+impl Board {
+    /// Returns the board drawn with ANSI escape codes and box drawing characters for a
+    /// terminal. Values are bold, and red if they repeat in a line, column or square of
+    /// the board, empty cells are a grey `·`. Requires the `colored` feature.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    /// println!("{}", board.to_colored_string());
+    ///
+    /// assert!(board.to_colored_string().contains("\x1b[1m1\x1b[0m"));
+    /// ```
+    pub fn to_colored_string(&self) -> String {
+        const VALUE: &str = "\x1b[1m";
+        const CONFLICT: &str = "\x1b[1;31m";
+        const EMPTY: &str = "\x1b[90m·";
+        const RESET: &str = "\x1b[0m";
+
+        let width = self.board_size.width();
+        let (box_rows, box_cols) = (self.board_size.box_rows(), self.board_size.box_cols());
+        let separator = vec!["─".repeat(box_cols * 2 + 1); box_rows].join("┼");
+        let mut colored = String::new();
+
+        for (l, line) in self.cells.chunks(width).enumerate() {
+            if l > 0 && l % box_rows == 0 {
+                colored.push_str(&separator);
+                colored.push('\n');
+            }
+
+            for (c, cell) in line.iter().enumerate() {
+                if c > 0 && c % box_cols == 0 {
+                    colored.push_str(" │");
+                }
+                colored.push(' ');
+
+                let loc = self.cell_at(l, c);
+                match cell {
+                    Some(value) => {
+                        let repeated = loc
+                            .iter_peers(self.variant)
+                            .any(|other| other != loc && self.get(&other) == Some(*value));
+                        let digit = std::char::from_digit(u32::from(*value), 17).unwrap_or('?');
+
+                        colored.push_str(if repeated { CONFLICT } else { VALUE });
+                        colored.push(digit);
+                    }
+                    None => colored.push_str(EMPTY),
+                }
+                colored.push_str(RESET);
+            }
+            colored.push('\n');
+        }
+
+        colored
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[cfg(test)]
+mod test {
+    use super::CellLoc;
+    use super::{
+        Block, Board, BoardSize, BoardSizeMismatchError, BoardSizeOutOfRangeError, CellValue,
+        MalformedBoardError, MalformedCsvError, MalformedGridError, PermutationError,
+        SetValueError, SizeMismatchError, ValueOutOfRangeError, Variant,
+    };
+    use std::collections::BTreeSet;
+    use std::convert::TryInto;
+
+    #[test]
+    fn mismatched_cell_sizes() {
+        let mut board = Board::new(BoardSize::FourByFour);
+
+        // index past the end of a 4x4 board
+        let too_large = CellLoc::at(8, 8, BoardSize::NineByNine);
+        assert_eq!(
+            board.try_get(&too_large),
+            Err(SizeMismatchError {
+                cell: too_large,
+                board_size: BoardSize::FourByFour
+            })
+        );
+        assert_eq!(
+            board.try_set_value(&too_large, 1),
+            Err(SetValueError::SizeMismatch(SizeMismatchError {
+                cell: too_large,
+                board_size: BoardSize::FourByFour
+            }))
+        );
+
+        // index fits in a 4x4 board but refers to a different cell
+        let fitting = CellLoc::at(1, 0, BoardSize::NineByNine);
+        assert!(fitting.get_index() < 16);
+        assert!(board.try_get(&fitting).is_err());
+        assert!(board.try_set_value(&fitting, 1).is_err());
+
+        // right size but out of range index
+        let past_end = CellLoc::new(16, BoardSize::FourByFour);
+        assert!(board.try_get(&past_end).is_err());
+
+        assert!(board.is_empty());
+        assert_eq!(
+            board.try_set_value(&CellLoc::at(1, 0, BoardSize::FourByFour), 1),
+            Ok(None)
+        );
+        assert_eq!(board.get_at(1, 0), Some(1));
+    }
+
+    #[test]
+    fn out_of_range_values() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let mut board = Board::new(board_size);
+            let cell = board.cell_at(0, 0);
+            let max_value = board_size.max_value();
+
+            for value in [0, max_value + 1] {
+                assert_eq!(
+                    board.try_set_value(&cell, value),
+                    Err(SetValueError::ValueOutOfRange(ValueOutOfRangeError {
+                        value,
+                        max_value
+                    }))
+                );
+                assert!(board.value(value).is_err());
+            }
+            assert!(board.is_empty());
+
+            let err = board.try_set_value(&cell, 0).unwrap_err();
+            assert_eq!(
+                std::error::Error::source(&err).map(ToString::to_string),
+                Some(board.value(0).unwrap_err().to_string())
+            );
+
+            assert_eq!(board.try_set_value(&cell, max_value), Ok(None));
+            let value = board.value(1).unwrap();
+            assert_eq!(board.try_set_value(&cell, value), Ok(Some(max_value)));
+            assert_eq!(board.set(&cell, value), Some(1));
+        }
+
+        assert!(CellValue::try_from(17).is_err());
+        assert_eq!(
+            ValueOutOfRangeError {
+                value: 5,
+                max_value: 4
+            }
+            .to_string(),
+            "Value 5 is out of range, values go from 1 to 4"
+        );
+
+        // parsing rejects values the board can't hold instead of panicking
+        assert_eq!(
+            "5... .... .... ....".parse::<Board>(),
+            Err(MalformedBoardError)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Value 0 is out of range, values go from 1 to 9")]
+    fn set_zero() {
+        let mut board = Board::new(BoardSize::NineByNine);
+        board.set_at(0, 0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Value 10 is out of range, values go from 1 to 9")]
+    fn set_past_width() {
+        let mut board = Board::new(BoardSize::NineByNine);
+        board.set(&board.cell_at(0, 0), 10);
+    }
+
+    #[test]
+    fn basics() {
+        let table = Board::new(BoardSize::FourByFour);
+
+        assert!(table.iter_cells().all(|cell| table.get(&cell).is_none()));
+    }
+
+    #[test]
+    fn set_value() {
+        let mut table = Board::new(BoardSize::NineByNine);
+        assert_eq!(table.get_at(0, 0), None);
+        table.set(&CellLoc::new(0, BoardSize::NineByNine), 3);
+        assert_eq!(table.get_at(0, 0), Some(3));
+    }
+
+    #[test]
+    fn square() {
+        assert_eq!(CellLoc::at(0, 0, BoardSize::NineByNine).square(), 0);
+        assert_eq!(CellLoc::at(0, 3, BoardSize::NineByNine).square(), 1);
+        assert_eq!(CellLoc::at(3, 0, BoardSize::NineByNine).square(), 3);
+    }
+
+    #[test]
+    fn rectangular_squares() {
+        for (box_rows, box_cols) in [(2, 3), (3, 2), (3, 4), (4, 3)] {
+            let board_size = BoardSize::custom(box_rows, box_cols).unwrap();
+            let board = Board::new(board_size);
+            let width = board_size.width();
+
+            for cell in board.iter_cells() {
+                let square = cell.square();
+                assert_eq!(
+                    square,
+                    (cell.line() / box_rows) * box_rows + cell.col() / box_cols
+                );
+
+                let cells = cell.iter_square().collect::<Vec<_>>();
+                assert_eq!(cells.len(), width);
+                assert!(cells.contains(&cell));
+                assert!(cells.iter().all(|other| other.square() == square));
+                assert!(cells.windows(2).all(|pair| pair[0] < pair[1]));
+                assert_eq!(cells[0].line() % box_rows, 0);
+                assert_eq!(cells[0].col() % box_cols, 0);
+            }
+
+            let mut count = vec![0; width];
+            for cell in board.iter_cells() {
+                count[cell.square()] += 1;
+            }
+            assert_eq!(count, vec![width; width]);
+        }
+
+        let board_size = BoardSize::custom(2, 3).unwrap();
+        assert_eq!(CellLoc::at(0, 3, board_size).square(), 1);
+        assert_eq!(CellLoc::at(2, 0, board_size).square(), 2);
+        assert_eq!(CellLoc::at(5, 5, board_size).square(), 5);
+        assert_eq!(
+            CellLoc::at(3, 4, board_size)
+                .iter_square()
+                .map(|cell| cell.idx)
+                .collect::<Vec<usize>>(),
+            &[15, 16, 17, 21, 22, 23]
+        );
+    }
+
+    #[test]
+    fn index() {
+        let board: Board = "1... ..2. .... ...4".parse().unwrap();
+
+        assert_eq!(board[(0, 0)], Some(1));
+        assert_eq!(board[(1, 2)], Some(2));
+        assert_eq!(board[(2, 2)], None);
+
+        let cell = board.cell_at(3, 3);
+        assert_eq!(board[cell], Some(4));
+        assert_eq!(board[board.cell_at(0, 1)], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell (0, 4) is outside of a 4x4 board")]
+    fn index_out_of_range() {
+        let board = Board::new(BoardSize::FourByFour);
+        let _ = board[(0, 4)];
+    }
+
+    #[test]
+    fn could_complete_to() {
+        let solution = board![[1, 2, 4, 3], [3, 4, 1, 2], [2, 1, 3, 4], [4, 3, 2, 1]];
+        let mut board = Board::new(BoardSize::FourByFour);
+        assert!(board.could_complete_to(&solution));
+        assert!(board.could_complete_to(&Board::new(BoardSize::FourByFour)));
+        assert!(!board.could_complete_to(&Board::new(BoardSize::NineByNine)));
+
+        board.set_at(1, 2, 1);
+        assert!(board.could_complete_to(&solution));
+
+        board.set_at(3, 0, 3);
+        assert!(!board.could_complete_to(&solution));
+
+        // a complete board only completes to itself
+        assert!(solution.could_complete_to(&solution));
+        assert!(solution.validate_solution(&solution));
+        let other = board![[2, 1, 3, 4], [3, 4, 1, 2], [1, 2, 4, 3], [4, 3, 2, 1]];
+        assert!(!solution.could_complete_to(&other));
+        assert!(!solution.validate_solution(&other));
+    }
+
+    #[test]
+    fn board_macro() {
+        let board = board![
+            [_, _, _, 4, _, _, 8, 7, _],
+            [4, _, 3, _, _, _, _, _, _],
+            [2, _, _, _, _, 3, _, _, 9],
+            [_, _, 6, 2, _, _, _, _, 7],
+            [_, _, _, 9, _, 6, _, _, _],
+            [3, _, 9, _, 8, _, _, _, _],
+            [_, _, _, _, _, _, _, 4, _],
+            [8, 7, 2, 5, _, _, _, _, _],
+            [_, _, _, 7, 2, _, 6, _, _],
+        ];
+        assert_eq!(
+            board,
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap()
+        );
+
+        let empty = board![[_, _, _, _], [_, _, _, _], [_, _, _, _], [_, _, _, _,],];
+        assert_eq!(empty, Board::new(BoardSize::FourByFour));
+    }
+
+    #[test]
+    #[should_panic(expected = "board! needs 4, 9 or 16 rows, found 3")]
+    fn board_macro_wrong_row_count() {
+        board![[1, _, _], [_, 2, _], [_, _, 3]];
+    }
+
+    #[test]
+    #[should_panic(expected = "board! rows must have as many cells as there are rows")]
+    fn board_macro_wrong_row_length() {
+        board![[1, _, _], [_, 2, _], [_, _, 3], [_, _, _]];
+    }
+
+    #[test]
+    #[should_panic(expected = "board! value 5 at row 2 column 3 is not between 1 and 4")]
+    fn board_macro_value_out_of_range() {
+        board![[1, _, _, _], [_, 2, 5, _], [_, _, 3, _], [_, _, _, 4]];
+    }
+
+    #[test]
+    fn subset_and_completion() {
+        let puzzle = board![[1, _, _, _], [_, _, 1, _], [_, _, _, _], [_, _, _, 1]];
+        let solution = board![[1, 2, 4, 3], [3, 4, 1, 2], [2, 1, 3, 4], [4, 3, 2, 1]];
+        assert!(puzzle.is_subset_of(&solution));
+        assert!(puzzle.is_subset_of(&puzzle));
+        assert!(solution.is_completion_of(&puzzle));
+        assert!(solution.is_completion_of(&solution));
+        assert!(!solution.is_subset_of(&puzzle));
+        assert!(!puzzle.is_completion_of(&puzzle));
+
+        // a single cell disagrees with the puzzle
+        let mut other = puzzle.clone();
+        other.set_at(3, 3, 2);
+        assert!(!other.is_subset_of(&solution));
+        assert!(!solution.is_completion_of(&other));
+
+        // a complete board that repeats values agrees with the puzzle but doesn't solve it
+        let repeated = board![[1, 2, 4, 3], [3, 4, 1, 2], [2, 1, 3, 4], [4, 3, 1, 1]];
+        assert!(puzzle.is_subset_of(&repeated));
+        assert!(!repeated.is_completion_of(&puzzle));
+
+        let nine = Board::new(BoardSize::NineByNine);
+        let nine_solution = nine.clone().into_solved().unwrap();
+        assert!(!puzzle.is_subset_of(&nine));
+        assert!(!nine.is_subset_of(&puzzle));
+        assert!(!nine_solution.is_completion_of(&puzzle));
+        assert!(!solution.is_completion_of(&nine));
+        assert!(nine_solution.is_completion_of(&nine));
+    }
+
+    #[test]
+    fn validate_solution() {
+        let puzzle = board![[1, _, _, _], [_, _, 1, _], [_, _, _, _], [_, _, _, 1]];
+        let solution = board![[1, 2, 4, 3], [3, 4, 1, 2], [2, 1, 3, 4], [4, 3, 2, 1]];
+        assert!(puzzle.validate_solution(&solution));
+        assert!(solution.validate_solution(&solution));
+        assert!(Board::new(BoardSize::FourByFour).validate_solution(&solution));
+
+        // wrong size
+        let nine: Board =
+            "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+                .parse()
+                .unwrap();
+        assert!(!puzzle.validate_solution(&nine));
+        assert!(Board::new(BoardSize::NineByNine).validate_solution(&nine));
+
+        // a given that doesn't match
+        let other_solution: Board = "2134 3412 1243 4321".parse().unwrap();
+        assert!(Board::new(BoardSize::FourByFour).validate_solution(&other_solution));
+        assert!(!puzzle.validate_solution(&other_solution));
+
+        // incomplete
+        let incomplete: Board = "1243 3412 2134 432.".parse().unwrap();
+        assert!(!puzzle.validate_solution(&incomplete));
+
+        // complete, but values repeat
+        let repeated: Board = "1243 3412 2134 4312".parse().unwrap();
+        assert!(repeated.is_complete());
+        assert!(!puzzle.validate_solution(&repeated));
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(
+            CellLoc::at(0, 0, BoardSize::NineByNine).blocks(),
+            [Block::Line(0), Block::Col(0), Block::Square(0)]
+        );
+        assert_eq!(
+            CellLoc::at(8, 8, BoardSize::NineByNine).blocks(),
+            [Block::Line(8), Block::Col(8), Block::Square(8)]
+        );
+        assert_eq!(
+            CellLoc::at(5, 7, BoardSize::SixteenBySixteen).blocks(),
+            [Block::Line(5), Block::Col(7), Block::Square(5)]
+        );
+        assert_eq!(
+            CellLoc::at(2, 1, BoardSize::FourByFour).square_block(),
+            Block::Square(2)
+        );
+    }
+
+    #[test]
+    fn peers() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let board = Board::new(board_size);
+            for cell in board.iter_cells() {
+                for (peers, all) in [
+                    (
+                        cell.row_peers().collect::<Vec<_>>(),
+                        cell.iter_line().collect::<Vec<_>>(),
+                    ),
+                    (cell.col_peers().collect(), cell.iter_col().collect()),
+                    (cell.square_peers().collect(), cell.iter_square().collect()),
+                ] {
+                    assert_eq!(peers.len(), board_size.width() - 1);
+                    assert!(!peers.contains(&cell));
+                    assert_eq!(
+                        peers,
+                        all.into_iter()
+                            .filter(|other| *other != cell)
+                            .collect::<Vec<_>>()
+                    );
+                }
+
+                assert_eq!(cell.shared_constraint_group(&cell), Some(cell.line_block()));
+                for other in board.iter_cells() {
+                    let expected = if other.line() == cell.line() {
+                        Some(cell.line_block())
+                    } else if other.col() == cell.col() {
+                        Some(cell.col_block())
+                    } else if other.square() == cell.square() {
+                        Some(cell.square_block())
+                    } else {
+                        None
+                    };
+                    assert_eq!(cell.shared_constraint_group(&other), expected);
+                    assert_eq!(
+                        expected.is_some(),
+                        cell.row_peers()
+                            .chain(cell.col_peers())
+                            .chain(cell.square_peers())
+                            .chain(std::iter::once(cell))
+                            .any(|peer| peer == other)
+                    );
+                }
+            }
+        }
+
+        assert_eq!(
+            CellLoc::at(0, 0, BoardSize::FourByFour).shared_constraint_group(&CellLoc::at(
+                0,
+                0,
+                BoardSize::NineByNine
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn iter_cells() {
+        let table = Board::new(BoardSize::NineByNine);
+        assert_eq!(
+            table
+                .iter_cells()
+                .map(|cell| cell.idx)
+                .collect::<Vec<usize>>(),
+            (0..81).collect::<Vec<usize>>()
+        )
+    }
+
+    #[test]
+    fn iter_square() {
+        let cell0 = CellLoc {
+            idx: 0,
+            board_size: BoardSize::NineByNine,
+        };
+
+        assert_eq!(
+            cell0
+                .iter_square()
+                .map(|cell| cell.idx)
+                .collect::<Vec<usize>>(),
+            &[0, 1, 2, 9, 10, 11, 18, 19, 20]
+        )
+    }
+
+    #[test]
+    fn possible_values_is_zero() {
+        let mut table = Board::new(BoardSize::NineByNine);
+        table.set_at(0, 0, 1);
+
+        let mut iter = table.iter_cells();
+        let cell = iter.next().expect("table should have 81 cells");
+
+        assert_eq!(cell.idx, 0);
+        assert!(cell.get_possible_values(&table).is_none())
+    }
+
+    #[test]
+    fn possible_values() {
+        let mut table = Board::new(BoardSize::NineByNine);
+        table.set_at(0, 1, 2);
+        table.set_at(0, 2, 3);
+        table.set_at(1, 0, 4);
+        table.set_at(2, 2, 5);
+
+        let mut iter = table.iter_cells();
+        let cell = iter.next().expect("table should have 81 cells");
+
+        assert_eq!(
+            cell.get_possible_values(&table),
+            Some(
+                [1u8, 6, 7, 8, 9]
+                    .iter()
+                    .map(|value| value.to_owned())
+                    .collect::<BTreeSet<u8>>()
+            )
+        )
+    }
+
+    #[test]
+    fn from() {
+        let table: Board = "................".parse().unwrap();
+        print!("{}", table);
+        assert_eq!(table, Board::new(BoardSize::FourByFour));
+    }
+
+    #[test]
+    fn pencilmark_string() {
+        let table: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+
+        let pencilmarks = table.to_pencilmark_string();
+        let mut lines = pencilmarks.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("1.. 1.. 1.. |     1.. 12. |         123")
+        );
+        assert_eq!(
+            lines.next(),
+            Some(".56 .56 .5. |  4  .56 .5. |  8   7  .56")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("..9 ..9 ... |     ..9 ..9 |         ...")
+        );
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(pencilmarks.lines().count(), 9 * 3 + 8);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+            BoardSize::custom(2, 3).unwrap(),
+            BoardSize::custom(4, 3).unwrap(),
+        ] {
+            for _ in 0..100 {
+                let board = random_board(board_size);
+                let data = board.to_binary();
+                assert_eq!(Board::from_binary(&data).unwrap(), board);
+            }
+        }
+
+        let mut board = random_board(BoardSize::NineByNine);
+        board.set_variant(Variant::Hyper).unwrap();
+        let data = board.to_binary();
+        assert_eq!(&data[..4], &[0, 3, 3, 1]);
+        assert_eq!(data.len(), 45);
+        assert_eq!(Board::from_binary(&data).unwrap(), board);
+        assert_eq!(Board::from_base64(&board.to_base64()).unwrap(), board);
+    }
+
+    #[test]
+    fn binary_size() {
+        assert_eq!(Board::new(BoardSize::FourByFour).to_binary().len(), 7);
+        assert_eq!(Board::new(BoardSize::NineByNine).to_binary().len(), 42);
+        assert_eq!(
+            Board::new(BoardSize::SixteenBySixteen).to_binary().len(),
+            161
+        );
+    }
+
+    #[test]
+    fn malformed_binary() {
+        assert!(Board::from_binary(&[]).is_err());
+        assert!(Board::from_binary(&[5, 0]).is_err());
+        assert!(Board::from_binary(&[0, 2, 3]).is_err());
+
+        // unknown variant, and a variant the board size doesn't support
+        let mut data = Board::new(BoardSize::custom(2, 3).unwrap()).to_binary();
+        data[3] = 2;
+        assert!(Board::from_binary(&data).is_err());
+        data[3] = 1;
+        assert!(Board::from_binary(&data).is_err());
+
+        let mut data = Board::new(BoardSize::FourByFour).to_binary();
+        data.pop();
+        assert!(Board::from_binary(&data).is_err());
+
+        // 4x4 boards use 3 bits per cell, 0b111 is bigger than 4
+        let mut data = Board::new(BoardSize::FourByFour).to_binary();
+        data[1] = 0b1110_0000;
+        assert!(Board::from_binary(&data).is_err());
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let table: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+
+        let encoded = table.to_base64();
+
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(Board::from_base64(&encoded).unwrap(), table);
+
+        for board_size in [BoardSize::FourByFour, BoardSize::SixteenBySixteen] {
+            let mut table = Board::new(board_size);
+            table.set_at(0, 1, 2);
+            assert_eq!(Board::from_base64(&table.to_base64()).unwrap(), table);
+        }
+    }
+
+    #[test]
+    fn malformed_base64() {
+        assert!(Board::from_base64("").is_err());
+        assert!(Board::from_base64("A").is_err());
+        assert!(Board::from_base64("AAA=").is_err());
+    }
+
+    #[test]
+    fn parse_grid() {
+        let grid = "
+            . . . | 4 . . | 8 7 .
+            4 . 3 | . . . | . . .
+            2 . . | . . 3 | . . 9
+            ------+-------+------
+            . . 6 | 2 . . | . . 7
+            . . . | 9 . 6 | . .
+            3 . 9 | . 8 . | . . .
+            ------+-------+------
+            . . . | . . . | . 4 .
+            8 7 2 | 5 . . | . . .
+            . . . | 7 2 . | 6 . .
+        ";
+        let err = Board::parse_grid(grid).unwrap_err();
+        assert_eq!(
+            err,
+            MalformedGridError::RowLength {
+                row: 5,
+                len: 8,
+                expected: 9
+            }
+        );
+        assert_eq!(err.to_string(), "Row 5 has 8 cells instead of 9");
+
+        let fixed = grid.replace("9 . 6 | . .\n", "9 . 6 | . . .\n");
+        assert_eq!(
+            Board::parse_grid(&fixed).unwrap(),
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap()
+        );
+
+        let board = Board::new(BoardSize::SixteenBySixteen);
+        let mut grid = board.to_string().replacen('.', "g", 1);
+        assert_eq!(Board::parse_grid(&grid).unwrap().get_at(0, 0), Some(16));
+        grid.push_str(". . . . . . . . . . . . . . . .\n");
+        assert_eq!(
+            Board::parse_grid(&grid),
+            Err(MalformedGridError::RowCount {
+                rows: 17,
+                expected: 16
+            })
+        );
+
+        assert_eq!(
+            Board::parse_grid("1...\n.2..\n..3."),
+            Err(MalformedGridError::RowCount {
+                rows: 3,
+                expected: 4
+            })
+        );
+        assert_eq!(
+            Board::parse_grid("1...\n.5..\n..3.\n...4"),
+            Err(MalformedGridError::InvalidCell {
+                row: 2,
+                col: 2,
+                character: '5'
+            })
+        );
+        assert_eq!(
+            Board::parse_grid("\n---\n"),
+            Err(MalformedGridError::Width { len: 0 })
+        );
+        assert_eq!(
+            Board::parse_grid("1....\n"),
+            Err(MalformedGridError::Width { len: 5 })
+        );
+    }
+
+    #[test]
+    fn euler_format() {
+        // the first three grids of the Project Euler problem 96 puzzle file
+        let text = "Grid 01
+003020600
+900305001
+001806400
+008102900
+700000008
+006708200
+002609500
+800203009
+005010300
+Grid 02
+200080300
+060070084
+030500209
+000105408
+000000000
+402706000
+301007040
+720040060
+004010003
+Grid 03
+000000907
+000420180
+000705026
+100904000
+050000040
+000507009
+920108000
+034059000
+507000000
+";
+        let boards = Board::from_euler_format(text).unwrap();
+        assert_eq!(boards.len(), 3);
+        assert_eq!(boards[0].get_at(0, 2), Some(3));
+        assert_eq!(boards[0].get_at(0, 0), None);
+        assert_eq!(Board::to_euler_format(&boards, 1), text);
+
+        let solutions: Vec<Board> = boards
+            .iter()
+            .map(|board| board.clone().into_solved().unwrap())
+            .collect();
+        // the number formed by the first three cells of the first solution
+        assert_eq!(
+            solutions[0].to_line_string()[..3].parse::<u32>().unwrap(),
+            483
+        );
+        for (board, solution) in boards.iter().zip(&solutions) {
+            assert!(board.validate_solution(solution));
+        }
+
+        let mut board = Board::new(BoardSize::SixteenBySixteen);
+        board.set_at(15, 15, 16);
+        let text = Board::to_euler_format(&[board.clone()], 96);
+        assert!(text.starts_with("Grid 96\n"));
+        assert!(text.ends_with("000g\n"));
+        assert_eq!(Board::from_euler_format(&text).unwrap(), vec![board]);
+
+        assert_eq!(Board::from_euler_format(""), Ok(vec![]));
+        assert!(Board::from_euler_format("Grid 01\n1000\n0200\n0030").is_err());
+        assert!(Board::from_euler_format("Grid 01\n1000\n0200\n0030\n005").is_err());
+        assert!(Board::from_euler_format("Grid 01\n1000\n0200\n0030\n0005").is_err());
+        assert!(Board::from_euler_format("Grid 01\n1000\n0200\n0030\n000x").is_err());
+    }
+
+    #[test]
+    fn sadman_format() {
+        let expected: Board =
+            "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3.."
+                .parse()
+                .unwrap();
+
+        let board = Board::from_sadman(
+            "
+            #A SadMan Software
+            #D Grid 01 of Project Euler problem 96
+            #B 2005-06-01
+            [Puzzle]
+            ##3#2#6##
+            9##3#5##1
+            ##18#64##
+            ##81#29##
+            7#######8
+            ##67#82##
+            ##26#95##
+            8##2#3##9
+            ##5#1#3##
+            [State]
+            483921657
+            967345821
+            ",
+        )
+        .unwrap();
+        assert_eq!(board, expected);
+
+        // without metadata, with dots and separators
+        let board = Board::from_sadman(
+            "
+            . . 3 | . 2 . | 6 . .
+            9 . . | 3 . 5 | . . 1
+            . . 1 | 8 . 6 | 4 . .
+            ---------------------
+            . . 8 | 1 . 2 | 9 . .
+            7 . . | . . . | . . 8
+            . . 6 | 7 . 8 | 2 . .
+            ---------------------
+            . . 2 | 6 . 9 | 5 . .
+            8 . . | 2 . 3 | . . 9
+            . . 5 | . 1 . | 3 . .
+            ",
+        )
+        .unwrap();
+        assert_eq!(board, expected);
+
+        assert_eq!(
+            Board::from_sadman(
+                "1###
+#2##
+##3#
+###4"
+            ),
+            "1... .2.. ..3. ...4".parse()
+        );
+        assert!(Board::from_sadman("").is_err());
+        assert!(Board::from_sadman(
+            "[Puzzle]
+1###
+#2##
+##3#"
+        )
+        .is_err());
+        assert!(Board::from_sadman(
+            "1###
+#2##
+##3#
+###x"
+        )
+        .is_err());
+        assert!(Board::from_sadman(
+            "#X
+1###
+#2##
+##3#
+###4"
+        )
+        .is_ok());
+        // only a letter followed by a space starts a metadata line
+        assert!(Board::from_sadman(
+            "#Xyz
+1###
+#2##
+##3#
+###4"
+        )
+        .is_err());
+
+        // 16x16 rows starting with an empty cell and a letter are not metadata
+        let mut rows = vec!["################"; 16];
+        rows[0] = "1###############";
+        rows[1] = "#A##############";
+        rows[15] = "#G#############c";
+        let board = Board::from_sadman(&format!("#A Anonymous\n{}", rows.join("\n"))).unwrap();
+        assert_eq!(board.board_size(), BoardSize::SixteenBySixteen);
+        assert_eq!(board.get_at(0, 0), Some(1));
+        assert_eq!(board.get_at(1, 1), Some(10));
+        assert_eq!(board.get_at(15, 1), Some(16));
+        assert_eq!(board.get_at(15, 15), Some(12));
+        assert_eq!(board.clue_count(), 4);
+    }
+
+    #[test]
+    fn candidates_format() {
+        use crate::solver::SolveError;
+
+        // a state in the middle of a solve, where the solver had to stop
+        let mut board: Board =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......"
+                .parse()
+                .unwrap();
+        let state = match board.solve_logical() {
+            Err(SolveError::RequiresGuessing { board_state }) => board_state,
+            other => panic!("expected the solver to get stuck, got {:?}", other),
+        };
+
+        let text = state.to_candidates_string();
+        let (parsed, candidates) = Board::parse_with_candidates(&text).unwrap();
+        assert_eq!(parsed, state);
+        for cell in state.iter_cells() {
+            assert_eq!(
+                candidates.get(&cell).cloned(),
+                cell.get_possible_values(&state)
+            );
+        }
+        assert_eq!(parsed.to_candidates_string(), text);
+
+        // candidates that were ruled out are kept out
+        let (board, candidates) =
+            Board::parse_with_candidates("1 34 24 24 24 4 3 124 34 134 124 1234 34 2 14 134")
+                .unwrap();
+        assert_eq!(board, "1... .43. .... .2..".parse().unwrap());
+        assert_eq!(
+            candidates[&board.cell_at(0, 1)],
+            vec![3, 4].into_iter().collect::<BTreeSet<u8>>()
+        );
+        assert_eq!(
+            candidates[&board.cell_at(3, 3)],
+            vec![1, 3, 4].into_iter().collect::<BTreeSet<u8>>()
+        );
+        assert_eq!(candidates.len(), 12);
+
+        // an empty cell with a single candidate is not read back as a given
+        let board: Board = "1... ..3. .... .2..".parse().unwrap();
+        let text = board.to_candidates_string();
+        assert!(text.contains("[4]"));
+        let (parsed, candidates) = Board::parse_with_candidates(&text).unwrap();
+        assert_eq!(parsed, board);
+        assert_eq!(
+            candidates[&board.cell_at(1, 1)],
+            vec![4].into_iter().collect::<BTreeSet<u8>>()
+        );
+        assert_eq!(candidates.len(), 13);
+        assert_eq!(parsed.to_candidates_string(), text);
+
+        let (board, candidates) =
+            Board::parse_with_candidates(". 234 234 234 23 23 . . . . . . . . . .").unwrap();
+        assert!(board.iter_cells().all(|cell| board.get(&cell).is_none()));
+        assert!(candidates[&board.cell_at(0, 0)].is_empty());
+
+        assert!(Board::parse_with_candidates("").is_err());
+        assert!(Board::parse_with_candidates("1 2 3").is_err());
+        assert!(
+            Board::parse_with_candidates("1 234 24 24 24 24 3 14 234 13 124 134 34 2 14 15")
+                .is_err()
+        );
+        assert!(
+            Board::parse_with_candidates("1 224 24 24 24 24 3 14 234 13 124 134 34 2 14 14")
+                .is_err()
+        );
+        assert!(
+            Board::parse_with_candidates("1 2x 24 24 24 24 3 14 234 13 124 134 34 2 14 14")
+                .is_err()
+        );
+        assert!(
+            Board::parse_with_candidates("1 [] 24 24 24 24 3 14 234 13 124 134 34 2 14 14")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn board_size_helpers() {
+        assert_eq!(BoardSize::FourByFour.width(), 4);
+        assert_eq!(BoardSize::NineByNine.width(), 9);
+        assert_eq!(BoardSize::SixteenBySixteen.width(), 16);
+
+        assert_eq!(BoardSize::FourByFour.cell_count(), 16);
+        assert_eq!(BoardSize::NineByNine.cell_count(), 81);
+        assert_eq!(BoardSize::SixteenBySixteen.cell_count(), 256);
+
+        assert_eq!(BoardSize::FourByFour.max_value(), 4);
+        assert_eq!(BoardSize::NineByNine.max_value(), 9);
+        assert_eq!(BoardSize::SixteenBySixteen.max_value(), 16);
+    }
+
+    #[test]
+    fn unit_is_complete() {
+        let solution: Board =
+            "483921657967345821251876493548132976729564138136798245372689514814253769695417382"
+                .parse()
+                .unwrap();
+        for n in 0..9 {
+            assert!(solution.row_is_complete(n));
+            assert!(solution.col_is_complete(n));
+            assert!(solution.square_is_complete(n));
+        }
+        assert!(!solution.row_is_complete(9));
+        assert!(!solution.col_is_complete(9));
+        assert!(!solution.square_is_complete(9));
+
+        // partially filled units
+        let mut board = solution.clone();
+        board.unset(&board.cell_at(4, 4));
+        assert!(!board.row_is_complete(4));
+        assert!(!board.col_is_complete(4));
+        assert!(!board.square_is_complete(4));
+        assert!(board.row_is_complete(3));
+        assert!(board.col_is_complete(3));
+        assert!(board.square_is_complete(3));
+
+        // a duplicate in the line, which is still full
+        let mut board = solution.clone();
+        board.set_at(0, 0, 8);
+        assert!(!board.row_is_complete(0));
+        assert!(!board.col_is_complete(0));
+        assert!(!board.square_is_complete(0));
+        assert!(board.row_is_complete(1));
+
+        // a value out of range
+        let mut board = solution;
+        board.set_unchecked(0, 0, Some(10));
+        assert!(!board.row_is_complete(0));
+
+        // rectangular boxes
+        let board = Board::parse_with_size(
+            "156243243165465312312456524631631524",
+            BoardSize::custom(2, 3).unwrap(),
+        )
+        .unwrap();
+        assert!((0..6).all(|sq| board.square_is_complete(sq)));
+    }
+
+    #[test]
+    fn new_sizes() {
+        for (board, board_size) in [
+            (Board::new_4x4(), BoardSize::FourByFour),
+            (Board::new_9x9(), BoardSize::NineByNine),
+            (Board::new_16x16(), BoardSize::SixteenBySixteen),
+        ] {
+            assert_eq!(board.board_size(), board_size);
+            assert_eq!(board, Board::new(board_size));
+            assert!(board.iter_cells().all(|cell| board.get(&cell).is_none()));
+        }
+    }
+
+    #[test]
+    fn custom_board_size() {
+        assert_eq!(BoardSize::custom(2, 2), Ok(BoardSize::FourByFour));
+        assert_eq!(BoardSize::custom(3, 3), Ok(BoardSize::NineByNine));
+        assert_eq!(BoardSize::custom(4, 4), Ok(BoardSize::SixteenBySixteen));
+
+        let board_size = BoardSize::custom(2, 3).unwrap();
+        assert_eq!(board_size.box_rows(), 2);
+        assert_eq!(board_size.box_cols(), 3);
+        assert_eq!(board_size.width(), 6);
+        assert_eq!(board_size.cell_count(), 36);
+        assert_eq!(board_size.max_value(), 6);
+        assert_eq!(board_size.to_string(), "6x6 (2x3 boxes)");
+        assert_ne!(board_size, BoardSize::custom(3, 2).unwrap());
+
+        assert!(BoardSize::custom(0, 3).is_err());
+        assert!(BoardSize::custom(1, 1).is_err());
+        assert!(BoardSize::custom(4, 5).is_err());
+        assert_eq!(
+            BoardSize::custom(4, 5).unwrap_err().to_string(),
+            "Boxes of 4x5 cells are not supported, boards must be between 2 and 16 cells wide"
+        );
+    }
+
+    #[test]
+    fn parse_with_size() {
+        let board_size = BoardSize::custom(2, 3).unwrap();
+        let board = Board::parse_with_size(
+            "
+            .5.2..
+            .4....
+            ...31.
+            ..2.5.
+            5.....
+            .315..
+            ",
+            board_size,
+        )
+        .unwrap();
+
+        assert_eq!(board.board_size(), board_size);
+        assert_eq!(board.get_at(0, 1), Some(5));
+        assert_eq!(board.get_at(5, 3), Some(5));
+        assert_eq!(
+            Board::parse_with_size(&board.to_string(), board_size),
+            Ok(board)
+        );
+
+        // 36 cells is not a square number of a named size
+        assert!(".5.2...4.......31...2.5.5......315.."
+            .parse::<Board>()
+            .is_err());
+        assert!(Board::parse_with_size(".5.2..", board_size).is_err());
+        assert!(
+            Board::parse_with_size("7...................................", board_size).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_board_size() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            assert_eq!(board_size.to_string().parse(), Ok(board_size));
+        }
+
+        for (box_rows, box_cols) in [(2, 3), (3, 2), (3, 4), (2, 1), (2, 8)] {
+            let board_size = BoardSize::custom(box_rows, box_cols).unwrap();
+            assert_eq!(board_size.to_string().parse(), Ok(board_size));
+        }
+
+        assert_eq!("16x16".parse(), Ok(BoardSize::SixteenBySixteen));
+        assert_eq!("9x9 (3x3 boxes)".parse(), Ok(BoardSize::NineByNine));
+        assert!("".parse::<BoardSize>().is_err());
+        assert!("3x3".parse::<BoardSize>().is_err());
+        assert!("9".parse::<BoardSize>().is_err());
+        assert!("NineByNine".parse::<BoardSize>().is_err());
+        assert!("6x6".parse::<BoardSize>().is_err());
+        assert!("8x8 (2x3 boxes)".parse::<BoardSize>().is_err());
+        assert!("6x6 (2x3)".parse::<BoardSize>().is_err());
+        assert!("20x20 (4x5 boxes)".parse::<BoardSize>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_cell_loc() {
+        use super::CellLocSeed;
+        use serde::de::DeserializeSeed;
+
+        let cell = CellLoc::at(4, 7, BoardSize::NineByNine);
+        let json = serde_json::to_string(&cell).unwrap();
+        assert_eq!(json, r#"{"line":4,"col":7}"#);
+
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert_eq!(
+            CellLocSeed(BoardSize::NineByNine)
+                .deserialize(&mut deserializer)
+                .unwrap(),
+            cell
+        );
+
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert!(CellLocSeed(BoardSize::FourByFour)
+            .deserialize(&mut deserializer)
+            .is_err());
+    }
 
-    ///
-    /// assert_eq!(board.rotated(), rotated_board);
-    /// ```
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_board() {
+        let board = random_board(BoardSize::NineByNine);
+        let json = serde_json::to_string(&board).unwrap();
 
-    pub fn rotated(&self) -> Self {
-        let mut board = Board::new(self.board_size());
-        let width = self.base_size.pow(2);
+        assert_eq!(json, format!("\"{}\"", board.to_line_string()));
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+        assert!(serde_json::from_str::<Board>(r#""123""#).is_err());
+    }
 
-        for cell in self.iter_cells() {
-            let l = cell.col();
-            let c = width - 1 - cell.line();
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_board_size() {
+        let json = serde_json::to_string(&BoardSize::NineByNine).unwrap();
+        assert_eq!(json, r#""NineByNine""#);
+        assert_eq!(
+            serde_json::from_str::<BoardSize>(&json).unwrap(),
+            BoardSize::NineByNine
+        );
+        assert!(serde_json::from_str::<BoardSize>(r#""9x9""#).is_err());
 
-            if let Some(value) = self.get(&cell) {
-                board.set_at(l, c, value);
+        let board_size = BoardSize::custom(2, 3).unwrap();
+        let json = serde_json::to_string(&board_size).unwrap();
+        assert_eq!(json, r#"{"Custom":{"box_rows":2,"box_cols":3}}"#);
+        assert_eq!(
+            serde_json::from_str::<BoardSize>(&json).unwrap(),
+            board_size
+        );
+        assert!(
+            serde_json::from_str::<BoardSize>(r#"{"Custom":{"box_rows":5,"box_cols":5}}"#).is_err()
+        );
+    }
+
+    fn random_board(board_size: BoardSize) -> Board {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let mut board = Board::new(board_size);
+        for cell in board.iter_cells() {
+            if rng.gen_bool(0.5) {
+                board.set(&cell, rng.gen_range(1..=board_size.max_value()));
             }
         }
 
         board
     }
-}
 
-impl PartialEq for Board {
-    fn eq(&self, other: &Self) -> bool {
-        if self.base_size != other.base_size {
-            return false;
-        }
+    #[test]
+    fn normalize() {
+        use rand::seq::SliceRandom;
 
-        for idx in 0..self.base_size.pow(4) {
-            if self.cells[idx] != other.cells[idx] {
-                return false;
+        let mut rng = rand::thread_rng();
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let board = random_board(board_size);
+            let normalized = board.normalize();
+            assert_eq!(normalized.normalize(), normalized);
+            assert_eq!(normalized.givens_mask(), board.givens_mask());
+
+            let mut labels: Vec<u8> = (1..=board_size.max_value()).collect();
+            labels.shuffle(&mut rng);
+            let mut relabeled = board.clone();
+            for cell in board.iter_cells() {
+                if let Some(value) = board.get(&cell) {
+                    relabeled.set(&cell, labels[value as usize - 1]);
+                }
+            }
+            assert_eq!(relabeled.normalize(), normalized);
+
+            // values appear in increasing order the first time they are found
+            let mut highest = 0;
+            for value in normalized.cells.iter().flatten() {
+                assert!(*value <= highest + 1);
+                highest = highest.max(*value);
             }
         }
 
-        true
+        let board = Board::new(BoardSize::NineByNine);
+        assert_eq!(board.normalize(), board);
     }
-}
 
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for l in 0..self.base_size.pow(2) {
-            for c in 0..self.base_size.pow(2) {
-                if let Some(value) = self.cells[l * self.base_size.pow(2) + c] {
-                    write!(f, "{} ", value)?;
-                } else {
-                    write!(f, ". ")?;
+    #[test]
+    fn rotations() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+            BoardSize::custom(2, 3).unwrap(),
+        ] {
+            for _ in 0..10 {
+                let board = random_board(board_size);
+
+                let mut in_place = board.clone();
+                in_place.rotate_in_place();
+                assert_eq!(in_place, board.rotated());
+
+                assert_eq!(board.rotated_n(0), board);
+                assert_eq!(board.rotated_n(1), board.rotated());
+                assert_eq!(board.rotated_n(2), board.rotated().rotated());
+                assert_eq!(board.rotated_n(3), board.rotated().rotated().rotated());
+                assert_eq!(board.rotated_n(4), board);
+                assert_eq!(board.rotated_n(7), board.rotated_n(3));
+
+                for _ in 0..3 {
+                    in_place.rotate_in_place();
                 }
+                assert_eq!(in_place, board);
             }
-            writeln!(f)?;
         }
-        Ok(())
+
+        // the extra regions of hyper sudoku are symmetric, so rotations keep the variant
+        let mut board = random_board(BoardSize::NineByNine);
+        board.set_variant(Variant::Hyper).unwrap();
+        assert_eq!(board.rotated().variant(), Variant::Hyper);
+        assert_eq!(board.rotated_n(1), board.rotated());
+        assert_eq!(board.rotated_n(3), board.rotated().rotated().rotated());
+        let mut in_place = board.clone();
+        in_place.rotate_in_place();
+        assert_eq!(in_place, board.rotated());
+
+        // rectangular boxes lie on their side after a quarter turn
+        let board = Board::new(BoardSize::custom(2, 3).unwrap());
+        assert_eq!(
+            board.rotated().board_size(),
+            BoardSize::custom(3, 2).unwrap()
+        );
+        assert_eq!(board.rotated_n(2).board_size(), board.board_size());
     }
-}
 
-/// Error returned when the representation of the board cannot be parsed successfully.
-///
-/// Boards have constraints that cannot be represented in easy to transfer formats (such as strings),
-/// A 9x9 board for instance must have exactly 81 cells with values ranging between 1 and 9.
-/// This error is returned when those constraints are not met.
-#[derive(Debug, Clone)]
-pub struct MalformedBoardError;
+    #[test]
+    fn mirrors() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let width = board_size.width();
+            let board = random_board(board_size);
 
-impl fmt::Display for MalformedBoardError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "This board is not correctly formed")
+            let mut horizontal = board.clone();
+            horizontal.mirror_horizontal_in_place();
+            let mut vertical = board.clone();
+            vertical.mirror_vertical_in_place();
+
+            for cell in board.iter_cells() {
+                let (l, c) = (cell.line(), cell.col());
+                assert_eq!(horizontal.get_at(l, width - 1 - c), board.get(&cell));
+                assert_eq!(vertical.get_at(width - 1 - l, c), board.get(&cell));
+            }
+
+            // mirroring both ways is the same as rotating twice
+            horizontal.mirror_vertical_in_place();
+            assert_eq!(horizontal, board.rotated_n(2));
+
+            vertical.mirror_vertical_in_place();
+            assert_eq!(vertical, board);
+        }
     }
-}
 
-// This is important for other errors to wrap this one.
-impl error::Error for MalformedBoardError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+    #[test]
+    fn symmetry_checks() {
+        let symmetric = board![
+            [_, 1, _, _, _, _, _, 2, _],
+            [_, _, _, _, 4, _, _, _, _],
+            [_, _, 6, _, _, _, 7, _, _],
+            [_, _, _, 8, _, 9, _, _, _],
+            [_, 2, _, _, 5, _, _, 6, _],
+            [_, _, _, 1, _, 3, _, _, _],
+            [_, _, 4, _, _, _, 8, _, _],
+            [_, _, _, _, 7, _, _, _, _],
+            [_, 5, _, _, _, _, _, 3, _],
+        ];
+        assert!(symmetric.has_rotational_symmetry());
+        assert_eq!(symmetric.has_mirror_symmetry(), (true, true));
+        assert!(!symmetric.has_diagonal_symmetry());
+
+        let diagonal = board![
+            [1, _, _, _, _, _, _, _, _],
+            [_, _, 3, _, _, _, _, _, _],
+            [_, 3, _, _, _, _, _, 7, _],
+            [_, _, _, 4, 2, _, _, _, _],
+            [_, _, _, 2, _, _, _, _, _],
+            [_, _, _, _, _, 9, _, _, _],
+            [_, _, _, _, _, _, _, _, _],
+            [_, _, 7, _, _, _, _, _, 5],
+            [_, _, _, _, _, _, _, 5, _],
+        ];
+        assert!(diagonal.has_diagonal_symmetry());
+        assert!(!diagonal.has_rotational_symmetry());
+        assert_eq!(diagonal.has_mirror_symmetry(), (false, false));
+
+        // moving one given breaks every symmetry
+        let mut asymmetric = symmetric.clone();
+        asymmetric.unset(&asymmetric.cell_at(0, 1));
+        asymmetric.set_at(0, 0, 1);
+        assert!(!asymmetric.has_rotational_symmetry());
+        assert_eq!(asymmetric.has_mirror_symmetry(), (false, false));
+        assert!(!asymmetric.has_diagonal_symmetry());
+
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            assert!(Board::new(board_size).has_diagonal_symmetry());
+
+            for _ in 0..10 {
+                let board = random_board(board_size);
+                let mut transposed = Board::new(board_size);
+                for cell in board.iter_cells() {
+                    if let Some(value) = board.get(&cell) {
+                        transposed.set_at(cell.col(), cell.line(), value);
+                    }
+                }
+                assert_eq!(
+                    board.has_diagonal_symmetry(),
+                    board.givens_mask() == transposed.givens_mask()
+                );
+            }
+        }
     }
-}
 
-impl FromStr for Board {
-    type Err = MalformedBoardError;
+    #[test]
+    fn apply_permutation() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let width = board_size.width();
+            let identity: Vec<usize> = (0..width).collect();
+            let values: Vec<u8> = (1..=board_size.max_value()).collect();
 
-    /// Parses a board from a string. A board will be parsed from a string with each digit
-    /// representing a value in the board. Separator characters like space ('` `'), newline ('`\n`'),
-    /// underscore ('`_`'), dash ('`-`'), and pipe ('`|`') are ignored to allow a more friendly formatting.
-    ///
-    /// ```
-    /// use sudokugen::board::Board;
-    /// let board: Board = "
-    /// 1 . . | . . . | . . .
-    /// . 2 . | . . . | . . .
-    /// . . 3 | . . . | . . .
-    /// ---------------------
-    /// . . . | 4 . . | . . .
-    /// . . . | . 5 . | . . .
-    /// . . . | . . 6 | . . .
-    /// ---------------------
-    /// . . . | . . . | 7 . .
-    /// . . . | . . . | . 8 .
-    /// . . . | . . . | . . 9
-    /// ".parse().unwrap();
-    /// ```
-    ///
-    /// Alternatively a more streamlined format can be used, which is the same but without any formatting characters.
-    /// ```
-    /// use sudokugen::board::Board;
-    /// let board: Board = "123456789........................................................................".parse().unwrap();
-    /// ```
-    ///
-    fn from_str(board_as_string: &str) -> Result<Self, Self::Err> {
-        let board_as_string = board_as_string.replace(' ', "");
-        let board_as_string = board_as_string.replace('\n', "");
-        let board_as_string = board_as_string.replace('_', "");
-        let board_as_string = board_as_string.replace('-', "");
-        let board_as_string = board_as_string.replace('|', "");
+            for _ in 0..10 {
+                let board = random_board(board_size);
+                assert_eq!(
+                    board.apply_permutation(&identity, &identity, &values),
+                    Ok(board.clone())
+                );
 
-        let base_size = (board_as_string.len() as f64).sqrt().sqrt();
+                // turning the board upside down swaps bands and the lines inside them
+                let reversed: Vec<usize> = identity.iter().rev().copied().collect();
+                let mut mirrored = board.clone();
+                mirrored.mirror_vertical_in_place();
+                assert_eq!(
+                    board.apply_permutation(&reversed, &identity, &values),
+                    Ok(mirrored)
+                );
+                assert_eq!(
+                    board.apply_permutation(&reversed, &reversed, &values),
+                    Ok(board.rotated_n(2))
+                );
 
-        if base_size.fract() != 0.0 {
-            return Err(MalformedBoardError);
+                let mut labels = values.clone();
+                labels.rotate_left(1);
+                let relabeled = board
+                    .apply_permutation(&identity, &identity, &labels)
+                    .unwrap();
+                assert_eq!(relabeled.normalize(), board.normalize());
+            }
+
+            let board = Board::new(board_size);
+            let mut split_band = identity.clone();
+            split_band.swap(0, width - 1);
+            let mut repeated = identity.clone();
+            repeated[1] = 0;
+            let mut repeated_value = values.clone();
+            repeated_value[1] = 1;
+
+            for rows in [&split_band, &repeated, &identity[1..].to_vec()] {
+                assert_eq!(
+                    board.apply_permutation(rows, &identity, &values),
+                    Err(PermutationError::Rows)
+                );
+                assert_eq!(
+                    board.apply_permutation(&identity, rows, &values),
+                    Err(PermutationError::Columns)
+                );
+            }
+            for digits in [&repeated_value, &values[1..].to_vec()] {
+                assert_eq!(
+                    board.apply_permutation(&identity, &identity, digits),
+                    Err(PermutationError::Digits)
+                );
+            }
         }
 
-        let board_size: BoardSize = (base_size as usize)
-            .try_into()
-            .map_err(|_| MalformedBoardError)?;
+        // hyper sudoku only allows the permutations that keep the extra regions in place
+        let mut board = random_board(BoardSize::NineByNine);
+        board.set_variant(Variant::Hyper).unwrap();
+        let identity: Vec<usize> = (0..9).collect();
+        let reversed: Vec<usize> = identity.iter().rev().copied().collect();
+        let values: Vec<u8> = (1..=9).collect();
 
-        let mut table = Board::new(board_size);
+        let permuted = board
+            .apply_permutation(&reversed, &reversed, &values)
+            .unwrap();
+        assert_eq!(permuted.variant(), Variant::Hyper);
+        assert_eq!(permuted, board.rotated_n(2));
 
-        // TODO: must support deserialization of tables larger than base 3
-        for (idx, c) in board_as_string.char_indices() {
-            match c {
-                '1'..='9' => {
-                    table.set(
-                        &CellLoc::new(idx, board_size),
-                        c.to_digit(10).unwrap().try_into().unwrap(),
-                    );
-                }
-                '.' => continue,
-                _ => return Err(MalformedBoardError), // _ => panic!("All characters in the board representation should be digits or a spacing character '.', '-', '|' or '\\n'")
+        let swapped_bands = [3, 4, 5, 0, 1, 2, 6, 7, 8];
+        let swapped_lines = [1, 0, 2, 3, 4, 5, 6, 7, 8];
+        for perm in [&swapped_bands, &swapped_lines] {
+            assert_eq!(
+                board.apply_permutation(perm, &identity, &values),
+                Err(PermutationError::Rows)
+            );
+            assert_eq!(
+                board.apply_permutation(&identity, perm, &values),
+                Err(PermutationError::Columns)
+            );
+        }
+        let mut standard = board.clone();
+        standard.set_variant(Variant::Standard).unwrap();
+        assert!(standard
+            .apply_permutation(&swapped_bands, &swapped_lines, &values)
+            .is_ok());
+
+        // a permuted solution is still a valid solution
+        let solution: Board = "1234 3412 2143 4321".parse().unwrap();
+        let permuted = solution
+            .apply_permutation(&[3, 2, 1, 0], &[2, 3, 1, 0], &[4, 2, 1, 3])
+            .unwrap();
+        assert!(Board::new(BoardSize::FourByFour).validate_solution(&permuted));
+    }
+
+    #[test]
+    fn swap_values() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let board = random_board(board_size);
+            let swapped = board.swap_values(1, 2).unwrap();
+            assert_eq!(swapped.swap_values(1, 2), Ok(board.clone()));
+            assert_eq!(board.swap_values(3, 3), Ok(board.clone()));
+
+            for cell in board.iter_cells() {
+                let expected = match board.get(&cell) {
+                    Some(1) => Some(2),
+                    Some(2) => Some(1),
+                    value => value,
+                };
+                assert_eq!(swapped.get(&cell), expected);
             }
+
+            let max_value = board_size.max_value();
+            assert_eq!(
+                board.swap_values(0, 1),
+                Err(ValueOutOfRangeError {
+                    value: 0,
+                    max_value
+                })
+            );
+            assert!(board.swap_values(1, max_value + 1).is_err());
         }
 
-        Ok(table)
+        // swapping values of a solution keeps it valid
+        let solution: Board =
+            "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+                .parse()
+                .unwrap();
+        let swapped = solution.swap_values(1, 9).unwrap();
+        assert_ne!(swapped, solution);
+        assert!(Board::new(BoardSize::NineByNine).validate_solution(&swapped));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::CellLoc;
-    use super::{Board, BoardSize};
-    use std::collections::BTreeSet;
+    #[test]
+    fn display_round_trip() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            for _ in 0..10 {
+                let board = random_board(board_size);
+                let display = board.to_string();
+
+                assert_eq!(display.lines().count(), board_size.width());
+                assert!(display
+                    .lines()
+                    .all(|line| line.len() == board_size.width() * 2));
+                assert_eq!(display.parse::<Board>(), Ok(board));
+            }
+        }
+
+        // the size of custom boards must be given to parse them back
+        for board_size in [
+            BoardSize::custom(2, 3).unwrap(),
+            BoardSize::custom(3, 4).unwrap(),
+        ] {
+            for _ in 0..10 {
+                let board = random_board(board_size);
+                let display = board.to_string();
+
+                assert_eq!(display.parse::<Board>(), Err(MalformedBoardError));
+                assert_eq!(Board::parse_with_size(&display, board_size), Ok(board));
+            }
+        }
+
+        // 4x4 and 9x9 boards are written exactly as before letters were used
+        let board: Board = "123456789 ......... ......... ......... ......... ......... ......... ......... ........9"
+            .parse()
+            .unwrap();
+        assert!(board.to_string().starts_with("1 2 3 4 5 6 7 8 9 \n. . . "));
+        assert!(board.to_string().ends_with(". . 9 \n"));
+    }
 
     #[test]
-    fn basics() {
-        let table = Board::new(BoardSize::FourByFour);
+    fn clear_retain_and_overlay() {
+        let puzzle = board![[1, _, _, _], [_, _, 3, _], [_, 4, _, _], [_, _, _, 2],];
+        let givens: BTreeSet<CellLoc> = puzzle
+            .iter_cells()
+            .filter(|cell| puzzle.get(cell).is_some())
+            .collect();
 
-        assert!(table.iter_cells().all(|cell| table.get(&cell).is_none()));
+        // a restart keeps the givens and drops the moves of the player
+        let mut played = puzzle.clone();
+        played.set_at(0, 1, 2);
+        played.set_at(3, 0, 3);
+        played.retain_cells(&givens);
+        assert_eq!(played, puzzle);
+
+        let mut board = puzzle.clone();
+        board.retain_cells(&BTreeSet::new());
+        assert!(board.is_empty());
+        // cells of other boards don't match any cell of this one
+        let mut board = puzzle.clone();
+        board.retain_cells(
+            &[CellLoc::at(0, 0, BoardSize::NineByNine)]
+                .into_iter()
+                .collect(),
+        );
+        assert!(board.is_empty());
+
+        let mut board = puzzle.clone();
+        board.clear();
+        assert_eq!(board, Board::new(BoardSize::FourByFour));
+
+        let moves = board![[2, 3, _, _], [_, _, _, _], [_, _, _, _], [_, _, _, 1],];
+        let mut board = puzzle.clone();
+        assert_eq!(board.overlay(&moves), Ok(()));
+        assert_eq!(
+            board,
+            board![[2, 3, _, _], [_, _, 3, _], [_, 4, _, _], [_, _, _, 1],]
+        );
+
+        let err = board
+            .overlay(&Board::new(BoardSize::NineByNine))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            BoardSizeMismatchError {
+                expected: BoardSize::FourByFour,
+                found: BoardSize::NineByNine
+            }
+        );
+        assert_eq!(err.to_string(), "Expected a 4x4 board, found a 9x9 board");
+        assert_eq!(board.clue_count(), 5);
     }
 
     #[test]
-    fn set_value() {
-        let mut table = Board::new(BoardSize::NineByNine);
-        assert_eq!(table.get_at(0, 0), None);
-        table.set(&CellLoc::new(0, BoardSize::NineByNine), 3);
-        assert_eq!(table.get_at(0, 0), Some(3));
+    fn givens_symmetry() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            for _ in 0..10 {
+                let board = random_board(board_size);
+                let mut horizontal = board.clone();
+                horizontal.mirror_horizontal_in_place();
+                let mut vertical = board.clone();
+                vertical.mirror_vertical_in_place();
+
+                assert_eq!(
+                    board.has_rotational_symmetry(),
+                    board.givens_mask() == board.rotated_n(2).givens_mask()
+                );
+                assert_eq!(
+                    board.has_mirror_symmetry(),
+                    (
+                        board.givens_mask() == horizontal.givens_mask(),
+                        board.givens_mask() == vertical.givens_mask()
+                    )
+                );
+
+                // filling the cells given in the rotated board makes it symmetric
+                let mut symmetric = board.clone();
+                let rotated = board.rotated_n(2);
+                for cell in rotated.iter_cells() {
+                    if let Some(value) = rotated.get(&cell) {
+                        symmetric.set(&cell, value);
+                    }
+                }
+                assert!(symmetric.has_rotational_symmetry());
+            }
+
+            // a single given in a corner has no symmetry at all
+            let mut board = Board::new(board_size);
+            board.set_at(0, 0, 1);
+            assert!(!board.has_rotational_symmetry());
+            assert_eq!(board.has_mirror_symmetry(), (false, false));
+
+            // the empty board is symmetric in every way
+            let empty = Board::new(board_size);
+            assert!(empty.has_rotational_symmetry());
+            assert_eq!(empty.has_mirror_symmetry(), (true, true));
+        }
     }
 
     #[test]
-    fn square() {
-        assert_eq!(CellLoc::at(0, 0, BoardSize::NineByNine).square(), 0);
-        assert_eq!(CellLoc::at(0, 3, BoardSize::NineByNine).square(), 1);
-        assert_eq!(CellLoc::at(3, 0, BoardSize::NineByNine).square(), 3);
+    fn givens_per_band_and_stack() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let base_size = board_size.get_base_size();
+            let board = random_board(board_size);
+            let givens = board.givens_mask().iter().filter(|given| **given).count();
+
+            let (bands, stacks) = board.givens_per_band_and_stack();
+            assert_eq!(bands.len(), base_size);
+            assert_eq!(stacks.len(), base_size);
+            assert_eq!(bands.iter().sum::<usize>(), givens);
+            assert_eq!(stacks.iter().sum::<usize>(), givens);
+
+            // all the givens in the last square
+            let width = board_size.width();
+            let mut board = Board::new(board_size);
+            board.set_at(width - 1, width - 1, 1);
+            board.set_at(width - base_size, width - base_size, 1);
+
+            let mut expected = vec![0; base_size];
+            expected[base_size - 1] = 2;
+            assert_eq!(
+                board.givens_per_band_and_stack(),
+                (expected.clone(), expected)
+            );
+        }
     }
 
     #[test]
-    fn iter_cells() {
-        let table = Board::new(BoardSize::NineByNine);
+    fn csv_round_trip() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let board = random_board(board_size);
+            let csv = board.to_csv();
+
+            assert_eq!(csv.lines().count(), board_size.width());
+            assert_eq!(Board::from_csv(&csv).unwrap(), board);
+        }
+    }
+
+    #[test]
+    fn csv_with_dots() {
+        let csv = "
+            ., ., ., 4, ., ., 8, 7, .
+            4, ., 3, ., ., ., ., ., .
+            2, ., ., ., ., 3, ., ., 9
+            ., ., 6, 2, ., ., ., ., 7
+            ., ., ., 9, ., 6, ., ., .
+            3, ., 9, ., 8, ., ., ., .
+            ., ., ., ., ., ., ., 4, .
+            8, 7, 2, 5, ., ., ., ., .
+            ., ., ., 7, 2, ., 6, ., .
+        ";
+        let board = Board::from_csv(csv).unwrap();
         assert_eq!(
-            table
-                .iter_cells()
-                .map(|cell| cell.idx)
-                .collect::<Vec<usize>>(),
-            (0..81).collect::<Vec<usize>>()
-        )
+            board,
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(Board::from_csv(&board.to_csv()).unwrap(), board);
+
+        // both kinds of empty cells can be mixed
+        let mixed = csv.replacen(".,", ",", 5);
+        assert_eq!(Board::from_csv(&mixed).unwrap(), board);
+        assert!(Board::from_csv(&csv.replacen('.', "..", 1)).is_err());
     }
 
     #[test]
-    fn iter_square() {
-        let cell0 = CellLoc {
-            idx: 0,
-            base_size: 3,
-        };
+    fn malformed_csv() {
+        // ragged rows
+        assert_eq!(
+            Board::from_csv("1,,,\n,2,,,\n,,3,\n,,,4"),
+            Err(MalformedCsvError::RowLength {
+                row: 2,
+                len: 5,
+                expected: 4
+            })
+        );
+        let err = Board::from_csv("1,,,\n,2,,\n\n,,3\n,,,4").unwrap_err();
+        assert_eq!(
+            err,
+            MalformedCsvError::RowLength {
+                row: 3,
+                len: 3,
+                expected: 4
+            }
+        );
+        assert_eq!(err.to_string(), "Row 3 has 3 fields instead of 4");
+        // wrong number of rows
+        assert_eq!(
+            Board::from_csv("1,,,\n,2,,\n,,3,"),
+            Err(MalformedCsvError::RowCount { rows: 3 })
+        );
+        assert_eq!(
+            Board::from_csv(""),
+            Err(MalformedCsvError::RowCount { rows: 0 })
+        );
+        // values out of range
+        for value in ["5", "0", "a", "12"] {
+            assert_eq!(
+                Board::from_csv(&format!(",,,\n,2,,\n,,3,{}\n,,,4", value)),
+                Err(MalformedCsvError::InvalidValue {
+                    row: 3,
+                    col: 4,
+                    field: value.to_string()
+                })
+            );
+        }
+        // a dot is only an empty cell on its own
+        for field in ["..", ". 1", "1."] {
+            let err = Board::from_csv(&format!("1,.,.,.\n.,{},.,.\n.,.,3,.\n.,.,.,4", field))
+                .unwrap_err();
+            assert_eq!(
+                err,
+                MalformedCsvError::InvalidValue {
+                    row: 2,
+                    col: 2,
+                    field: field.to_string()
+                }
+            );
+            assert_eq!(MalformedBoardError::from(err), MalformedBoardError);
+        }
+    }
+
+    #[test]
+    fn latex() {
+        let table: Board = "
+        1 . | . .
+        . . | 3 .
+        ---------
+        . 4 | . .
+        . . | . 2
+        "
+        .parse()
+        .unwrap();
 
         assert_eq!(
-            cell0
-                .iter_square()
-                .map(|cell| cell.idx)
-                .collect::<Vec<usize>>(),
-            &[0, 1, 2, 9, 10, 11, 18, 19, 20]
-        )
+            table.to_latex(),
+            r"\begin{tabular}{!{\vrule width 1.5pt}c|c!{\vrule width 1.5pt}c|c!{\vrule width 1.5pt}}
+\noalign{\hrule height 1.5pt}
+1 &   &   &   \\ \hline
+  &   & 3 &   \\ \noalign{\hrule height 1.5pt}
+  & 4 &   &   \\ \hline
+  &   &   & 2 \\ \noalign{\hrule height 1.5pt}
+\end{tabular}
+"
+        );
+
+        let latex = Board::new(BoardSize::SixteenBySixteen).to_latex();
+        assert_eq!(latex.lines().count(), 16 + 3);
+        assert_eq!(latex.matches("\\noalign").count(), 5);
     }
 
     #[test]
-    fn possible_values_is_zero() {
-        let mut table = Board::new(BoardSize::NineByNine);
-        table.set_at(0, 0, 1);
+    fn html_table() {
+        let table: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let html = table.to_html_table();
 
-        let mut iter = table.iter_cells();
-        let cell = iter.next().expect("table should have 81 cells");
+        assert!(html.starts_with("<table class=\"sudoku\">\n"));
+        assert!(html.ends_with("</table>\n"));
+        assert_eq!(html.matches("<tr>").count(), 9);
+        assert_eq!(html.matches("</tr>").count(), 9);
+        assert_eq!(html.matches("<td ").count(), 81);
+        assert_eq!(html.matches("</td>").count(), 81);
+        assert_eq!(html.matches("\"given").count(), table.clue_count());
+        assert_eq!(html.matches("\"empty").count(), 81 - table.clue_count());
+        // two vertical borders in each row and two horizontal borders in each column
+        assert_eq!(html.matches("box-border-right").count(), 2 * 9);
+        assert_eq!(html.matches("box-border-bottom").count(), 2 * 9);
 
-        assert_eq!(cell.idx, 0);
-        assert!(cell.get_possible_values(&table).is_none())
+        let first_row = html.lines().nth(1).unwrap();
+        assert!(first_row.starts_with(
+            "<tr><td class=\"empty\"></td><td class=\"empty\"></td><td class=\"empty box-border-right\"></td><td class=\"given\">4</td>"
+        ));
+        let third_row = html.lines().nth(3).unwrap();
+        assert!(third_row.starts_with("<tr><td class=\"given box-border-bottom\">2</td>"));
+        assert!(third_row.contains("<td class=\"empty box-border-right box-border-bottom\"></td>"));
+        assert!(!html.lines().nth(9).unwrap().contains("box-border-bottom"));
+
+        let html = Board::new(BoardSize::SixteenBySixteen).to_html_table();
+        assert_eq!(html.matches("<td ").count(), 256);
+        assert_eq!(html.matches("box-border-right").count(), 3 * 16);
     }
 
     #[test]
-    fn possible_values() {
-        let mut table = Board::new(BoardSize::NineByNine);
-        table.set_at(0, 1, 2);
-        table.set_at(0, 2, 3);
-        table.set_at(1, 0, 4);
-        table.set_at(2, 2, 5);
+    fn svg() {
+        let table: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
 
-        let mut iter = table.iter_cells();
-        let cell = iter.next().expect("table should have 81 cells");
+        for cell_size in [20, 50] {
+            let svg = table.to_svg(cell_size);
+            let size = cell_size * 9;
+
+            assert!(svg.starts_with(&format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">",
+                size = size
+            )));
+            assert!(svg.ends_with("</svg>\n"));
+            assert_eq!(svg.matches("<svg").count(), 1);
+            // ten horizontal and ten vertical lines, four of each thick
+            assert_eq!(svg.matches("<line").count(), 20);
+            assert_eq!(
+                svg.matches(&format!("stroke-width=\"{}\"", f64::from(cell_size) / 12.0))
+                    .count(),
+                8
+            );
+            assert_eq!(svg.matches("<text").count(), table.clue_count());
+            assert_eq!(svg.matches("</text>").count(), table.clue_count());
+        }
+
+        // the 4 in the first line is centered in the fourth column
+        assert!(table
+            .to_svg(40)
+            .contains("<text x=\"140\" y=\"20\" font-family=\"sans-serif\" font-size=\"24\""));
+
+        let svg = Board::new(BoardSize::SixteenBySixteen).to_svg(10);
+        assert!(svg.contains("viewBox=\"0 0 160 160\""));
+        assert_eq!(svg.matches("<line").count(), 34);
+        assert_eq!(svg.matches("<text").count(), 0);
+    }
+
+    #[cfg(feature = "colored")]
+    #[test]
+    fn colored_string() {
+        let table: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let colored = table.to_colored_string();
 
+        assert_eq!(colored.lines().count(), 9 + 2);
+        assert_eq!(colored.matches("\x1b[1m").count(), table.clue_count());
         assert_eq!(
-            cell.get_possible_values(&table),
-            Some(
-                [1u8, 6, 7, 8, 9]
-                    .iter()
-                    .map(|value| value.to_owned())
-                    .collect::<BTreeSet<u8>>()
-            )
-        )
+            colored.matches("\x1b[90m·").count(),
+            81 - table.clue_count()
+        );
+        assert_eq!(colored.matches("\x1b[0m").count(), 81);
+        assert_eq!(colored.matches('│').count(), 2 * 9);
+        assert_eq!(colored.matches('┼').count(), 2 * 2);
+        assert!(!colored.contains("\x1b[1;31m"));
+
+        // the 4 in the corner repeats the ones in its line and column, all three are red
+        let mut conflict = table;
+        conflict.set_at(0, 0, 4);
+        assert_eq!(
+            conflict.to_colored_string().matches("\x1b[1;31m4").count(),
+            3
+        );
     }
 
     #[test]
-    fn from() {
-        let table: Board = "................".parse().unwrap();
-        print!("{}", table);
-        assert_eq!(table, Board::new(BoardSize::FourByFour));
+    fn error_equality() {
+        assert_eq!("1234".parse::<Board>(), Err(MalformedBoardError));
+        assert_eq!(
+            "a...............".parse::<Board>(),
+            Err(MalformedBoardError)
+        );
+        assert_eq!(Board::from_binary(&[]), Err(MalformedBoardError));
+
+        let board_size: Result<BoardSize, _> = 5_usize.try_into();
+        assert_eq!(board_size, Err(BoardSizeOutOfRangeError(5)));
+        assert_eq!(
+            BoardSizeOutOfRangeError(5).clone(),
+            BoardSizeOutOfRangeError(5)
+        );
+    }
+
+    #[test]
+    fn default() {
+        let table = Board::default();
+
+        assert_eq!(table.board_size(), BoardSize::NineByNine);
+        assert!(table.is_empty());
     }
 }
@@ -0,0 +1,373 @@
+//! A C API to solve and generate puzzles from other languages, enabled by the `capi`
+//! feature. The declarations are in `include/sudokugen.h`.
+//!
+//! Boards are passed in and out as NUL terminated strings in the notation of
+//! [`Board::to_line_string`], one character per cell and `.` for empty cells. Every
+//! function returns one of the `SUDOKUGEN_*` status codes, when it's not
+//! [`SUDOKUGEN_OK`] [`sudokugen_last_error_message`] describes what went wrong.
+//!
+//! No function unwinds into the caller, a panic is reported as [`SUDOKUGEN_PANIC`].
+//!
+//! ```
+//! use std::ffi::{CStr, CString};
+//! use std::os::raw::c_char;
+//! use sudokugen::capi::{sudokugen_solve, SUDOKUGEN_OK};
+//!
+//! let board = CString::new("1... .... .... ...2").unwrap();
+//! let mut out = [0 as c_char; 17];
+//!
+//! let status = unsafe { sudokugen_solve(board.as_ptr(), out.as_mut_ptr(), out.len()) };
+//! assert_eq!(status, SUDOKUGEN_OK);
+//!
+//! let solution = unsafe { CStr::from_ptr(out.as_ptr()) };
+//! assert_eq!(solution.to_bytes().len(), 16);
+//! ```
+
+use crate::board::{Board, BoardSize};
+use crate::solver::generator::Puzzle;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic;
+use std::ptr;
+
+/// The call succeeded.
+pub const SUDOKUGEN_OK: c_int = 0;
+/// The board has no solution.
+pub const SUDOKUGEN_UNSOLVABLE: c_int = 1;
+/// The board couldn't be parsed.
+pub const SUDOKUGEN_MALFORMED: c_int = 2;
+/// An output buffer is too small for the board and its NUL terminator.
+pub const SUDOKUGEN_BUFFER_TOO_SMALL: c_int = 3;
+/// A pointer is null or an argument is out of range.
+pub const SUDOKUGEN_INVALID_ARGUMENT: c_int = 4;
+/// The library panicked, this is a bug.
+pub const SUDOKUGEN_PANIC: c_int = 5;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+/// Returns the message of the last error of a call made from this thread, or null if
+/// the last call succeeded.
+///
+/// The message is owned by the library and is valid until the next call from this
+/// thread, copy it to keep it longer.
+///
+/// ```
+/// use std::ffi::{CStr, CString};
+/// use std::os::raw::c_char;
+/// use sudokugen::capi::{sudokugen_last_error_message, sudokugen_solve, SUDOKUGEN_MALFORMED};
+///
+/// let board = CString::new("1..").unwrap();
+/// let mut out = [0 as c_char; 17];
+///
+/// let status = unsafe { sudokugen_solve(board.as_ptr(), out.as_mut_ptr(), out.len()) };
+/// assert_eq!(status, SUDOKUGEN_MALFORMED);
+///
+/// let message = unsafe { CStr::from_ptr(sudokugen_last_error_message()) };
+/// assert!(!message.to_bytes().is_empty());
+/// ```
+#[no_mangle]
+pub extern "C" fn sudokugen_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Solves the board in the NUL terminated string `board` and writes the solution to
+/// `out`, a buffer of `out_len` bytes. Separators are accepted in `board` same as
+/// parsing a [`Board`], the solution is written without them.
+///
+/// Returns [`SUDOKUGEN_OK`] on success, [`SUDOKUGEN_MALFORMED`] if `board` can't be
+/// parsed, [`SUDOKUGEN_UNSOLVABLE`] if it has no solution and
+/// [`SUDOKUGEN_BUFFER_TOO_SMALL`] if the solution doesn't fit in `out`, in which case
+/// `out` is left untouched.
+///
+/// # Safety
+///
+/// `board` must be null or point to a NUL terminated string, `out` must be null or
+/// point to at least `out_len` writable bytes.
+///
+/// ```
+/// use std::ffi::CString;
+/// use std::os::raw::c_char;
+/// use sudokugen::capi::{sudokugen_solve, SUDOKUGEN_BUFFER_TOO_SMALL, SUDOKUGEN_UNSOLVABLE};
+///
+/// let board = CString::new("1... .... .... ...2").unwrap();
+/// let mut out = [0 as c_char; 16];
+/// let status = unsafe { sudokugen_solve(board.as_ptr(), out.as_mut_ptr(), out.len()) };
+/// assert_eq!(status, SUDOKUGEN_BUFFER_TOO_SMALL);
+///
+/// let board = CString::new("123. ...4 .... ....").unwrap();
+/// let mut out = [0 as c_char; 17];
+/// let status = unsafe { sudokugen_solve(board.as_ptr(), out.as_mut_ptr(), out.len()) };
+/// assert_eq!(status, SUDOKUGEN_UNSOLVABLE);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn sudokugen_solve(
+    board: *const c_char,
+    out: *mut c_char,
+    out_len: usize,
+) -> c_int {
+    catch_errors(|| {
+        if board.is_null() || out.is_null() {
+            return Err((
+                SUDOKUGEN_INVALID_ARGUMENT,
+                "Null pointer argument".to_string(),
+            ));
+        }
+
+        let mut board: Board = CStr::from_ptr(board)
+            .to_str()
+            .map_err(|err| (SUDOKUGEN_MALFORMED, err.to_string()))?
+            .parse()
+            .map_err(|err: crate::board::MalformedBoardError| {
+                (SUDOKUGEN_MALFORMED, err.to_string())
+            })?;
+        board
+            .solve()
+            .map_err(|err| (SUDOKUGEN_UNSOLVABLE, err.to_string()))?;
+
+        write_board(&board, out, out_len)
+    })
+}
+
+/// Generates a new puzzle with a unique solution for a board `size` cells wide, 4, 9 or
+/// 16, writing the puzzle to `puzzle_out` and its solution to `solution_out`, two
+/// buffers of `len` bytes each.
+///
+/// Returns [`SUDOKUGEN_OK`] on success, [`SUDOKUGEN_INVALID_ARGUMENT`] for any other
+/// `size` and [`SUDOKUGEN_BUFFER_TOO_SMALL`] if the boards don't fit in the buffers,
+/// in which case the buffers are left untouched.
+///
+/// # Safety
+///
+/// `puzzle_out` and `solution_out` must be null or each point to at least `len` writable
+/// bytes.
+///
+/// ```
+/// use std::os::raw::c_char;
+/// use sudokugen::capi::{sudokugen_generate, SUDOKUGEN_OK};
+///
+/// let mut puzzle = [0 as c_char; 82];
+/// let mut solution = [0 as c_char; 82];
+/// let status = unsafe {
+///     sudokugen_generate(9, puzzle.as_mut_ptr(), solution.as_mut_ptr(), puzzle.len())
+/// };
+/// assert_eq!(status, SUDOKUGEN_OK);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn sudokugen_generate(
+    size: c_int,
+    puzzle_out: *mut c_char,
+    solution_out: *mut c_char,
+    len: usize,
+) -> c_int {
+    catch_errors(|| {
+        if puzzle_out.is_null() || solution_out.is_null() {
+            return Err((
+                SUDOKUGEN_INVALID_ARGUMENT,
+                "Null pointer argument".to_string(),
+            ));
+        }
+
+        let board_size = match size {
+            4 => BoardSize::FourByFour,
+            9 => BoardSize::NineByNine,
+            16 => BoardSize::SixteenBySixteen,
+            _ => {
+                return Err((
+                    SUDOKUGEN_INVALID_ARGUMENT,
+                    format!("{} is not a valid board size, expected 4, 9 or 16", size),
+                ))
+            }
+        };
+        if len <= board_size.cell_count() {
+            return Err(buffer_too_small(board_size.cell_count(), len));
+        }
+
+        let puzzle = Puzzle::generate(board_size);
+        write_board(puzzle.board(), puzzle_out, len)?;
+        write_board(puzzle.solution(), solution_out, len)
+    })
+}
+
+type CallError = (c_int, String);
+
+/// Runs `call` catching any panic and records its error message for
+/// [`sudokugen_last_error_message`].
+fn catch_errors<F>(call: F) -> c_int
+where
+    F: FnOnce() -> Result<(), CallError> + panic::UnwindSafe,
+{
+    let result = panic::catch_unwind(call).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        Err((SUDOKUGEN_PANIC, message))
+    });
+
+    let (status, message) = match result {
+        Ok(()) => (SUDOKUGEN_OK, None),
+        Err((status, message)) => (
+            status,
+            // a message with a NUL byte in it would be cut short anyway
+            Some(CString::new(message.replace('\0', "")).expect("NUL bytes were removed")),
+        ),
+    };
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+
+    status
+}
+
+fn buffer_too_small(cells: usize, len: usize) -> CallError {
+    (
+        SUDOKUGEN_BUFFER_TOO_SMALL,
+        format!("A buffer of {} bytes can't hold {} cells", len, cells),
+    )
+}
+
+/// Writes `board` in the line notation to `out` followed by a NUL byte.
+///
+/// # Safety
+///
+/// `out` must point to at least `out_len` writable bytes.
+unsafe fn write_board(board: &Board, out: *mut c_char, out_len: usize) -> Result<(), CallError> {
+    let line = board.to_line_string();
+    if out_len <= line.len() {
+        return Err(buffer_too_small(line.len(), out_len));
+    }
+
+    ptr::copy_nonoverlapping(line.as_ptr().cast::<c_char>(), out, line.len());
+    *out.add(line.len()) = 0;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SUDOKUGEN_BUFFER_TOO_SMALL, SUDOKUGEN_INVALID_ARGUMENT, SUDOKUGEN_MALFORMED, SUDOKUGEN_OK,
+        SUDOKUGEN_UNSOLVABLE,
+    };
+    use crate::board::Board;
+    use std::ffi::{CStr, CString};
+    use std::os::raw::{c_char, c_int};
+    use std::ptr;
+
+    // the same declarations as the C header, so the tests call the exported symbols
+    extern "C" {
+        fn sudokugen_solve(board: *const c_char, out: *mut c_char, out_len: usize) -> c_int;
+        fn sudokugen_generate(
+            size: c_int,
+            puzzle_out: *mut c_char,
+            solution_out: *mut c_char,
+            len: usize,
+        ) -> c_int;
+        fn sudokugen_last_error_message() -> *const c_char;
+    }
+
+    fn last_error() -> Option<String> {
+        let message = unsafe { sudokugen_last_error_message() };
+        if message.is_null() {
+            None
+        } else {
+            Some(
+                unsafe { CStr::from_ptr(message) }
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }
+    }
+
+    fn solve_str(board: &str, out: &mut [c_char]) -> c_int {
+        let board = CString::new(board).unwrap();
+        unsafe { sudokugen_solve(board.as_ptr(), out.as_mut_ptr(), out.len()) }
+    }
+
+    fn to_string(buffer: &[c_char]) -> String {
+        unsafe { CStr::from_ptr(buffer.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn solve() {
+        let board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6..";
+        let mut out = [0 as c_char; 82];
+        assert_eq!(solve_str(board, &mut out), SUDOKUGEN_OK);
+        assert_eq!(
+            to_string(&out),
+            "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+        );
+        assert_eq!(last_error(), None);
+
+        // the solution and its NUL terminator don't fit, the buffer is left untouched
+        let mut out = [1 as c_char; 81];
+        assert_eq!(solve_str(board, &mut out), SUDOKUGEN_BUFFER_TOO_SMALL);
+        assert!(out.iter().all(|byte| *byte == 1));
+        assert_eq!(
+            last_error().unwrap(),
+            "A buffer of 81 bytes can't hold 81 cells"
+        );
+
+        let mut out = [0 as c_char; 82];
+        assert_eq!(solve_str("1234 5", &mut out), SUDOKUGEN_MALFORMED);
+        assert_eq!(
+            last_error().unwrap(),
+            "1234 5".parse::<Board>().unwrap_err().to_string()
+        );
+
+        assert_eq!(
+            solve_str("123. ...4 .... ....", &mut out),
+            SUDOKUGEN_UNSOLVABLE
+        );
+        assert!(last_error().is_some());
+
+        let status = unsafe { sudokugen_solve(ptr::null(), out.as_mut_ptr(), out.len()) };
+        assert_eq!(status, SUDOKUGEN_INVALID_ARGUMENT);
+
+        // a successful call clears the last error
+        assert_eq!(solve_str("1... .... .... ...2", &mut out), SUDOKUGEN_OK);
+        assert_eq!(last_error(), None);
+    }
+
+    #[test]
+    fn generate() {
+        let mut puzzle = [0 as c_char; 17];
+        let mut solution = [0 as c_char; 17];
+        let status = unsafe {
+            sudokugen_generate(4, puzzle.as_mut_ptr(), solution.as_mut_ptr(), puzzle.len())
+        };
+        assert_eq!(status, SUDOKUGEN_OK);
+
+        let puzzle: Board = to_string(&puzzle).parse().unwrap();
+        let solution: Board = to_string(&solution).parse().unwrap();
+        assert!(puzzle.validate_solution(&solution));
+        assert_eq!(puzzle.has_unique_solution(), Ok(true));
+
+        let mut puzzle = [0 as c_char; 81];
+        let mut solution = [0 as c_char; 81];
+        let status = unsafe {
+            sudokugen_generate(9, puzzle.as_mut_ptr(), solution.as_mut_ptr(), puzzle.len())
+        };
+        assert_eq!(status, SUDOKUGEN_BUFFER_TOO_SMALL);
+        assert!(last_error().is_some());
+
+        let status = unsafe {
+            sudokugen_generate(5, puzzle.as_mut_ptr(), solution.as_mut_ptr(), puzzle.len())
+        };
+        assert_eq!(status, SUDOKUGEN_INVALID_ARGUMENT);
+        assert_eq!(
+            last_error().unwrap(),
+            "5 is not a valid board size, expected 4, 9 or 16"
+        );
+    }
+}
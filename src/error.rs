@@ -0,0 +1,368 @@
+//! A single error type for every error returned by this crate.
+
+use crate::board::{
+    BoardSizeMismatchError, BoardSizeOutOfRangeError, BoxSizeOutOfRangeError, MalformedBoardError,
+    MalformedCsvError, MalformedGridError, ParseBoardSizeError, PermutationError, SetValueError,
+    SizeMismatchError, UnsupportedVariantError, ValueOutOfRangeError,
+};
+use crate::play::PlayError;
+use crate::solver::generator::{ClueCountError, PoolExhaustedError, PuzzleError};
+use crate::solver::{ConsistencyError, SolveError, SolveLineError, StuckError, UnsolvableError};
+use std::error;
+use std::fmt;
+
+/// Any of the errors returned by this crate, so applications can use a single error
+/// type with the `?` operator. Every error can be converted into this one with `into`.
+///
+/// ```
+/// use sudokugen::{Board, Error};
+///
+/// fn solve(line: &str) -> Result<Board, Error> {
+///     let mut board: Board = line.parse()?;
+///     board.solve()?;
+///     Ok(board)
+/// }
+///
+/// assert!(solve("1... .... .... ....").is_ok());
+/// assert!(matches!(solve("1..."), Err(Error::MalformedBoard(_))));
+/// assert!(matches!(solve("123. ...4 .... ...."), Err(Error::Unsolvable(_))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// See [`MalformedBoardError`].
+    MalformedBoard(MalformedBoardError),
+    /// See [`ParseBoardSizeError`].
+    ParseBoardSize(ParseBoardSizeError),
+    /// See [`BoardSizeOutOfRangeError`].
+    BoardSizeOutOfRange(BoardSizeOutOfRangeError),
+    /// See [`SizeMismatchError`].
+    SizeMismatch(SizeMismatchError),
+    /// See [`UnsolvableError`].
+    Unsolvable(UnsolvableError),
+    /// See [`StuckError`].
+    Stuck(StuckError),
+    /// See [`SolveError`].
+    Solve(SolveError),
+    /// See [`SolveLineError`].
+    SolveLine(SolveLineError),
+    /// See [`PuzzleError`].
+    Puzzle(PuzzleError),
+    /// See [`PlayError`].
+    Play(PlayError),
+    /// See [`MalformedGridError`].
+    MalformedGrid(MalformedGridError),
+    /// See [`ConsistencyError`].
+    Consistency(ConsistencyError),
+    /// See [`ValueOutOfRangeError`].
+    ValueOutOfRange(ValueOutOfRangeError),
+    /// See [`SetValueError`].
+    SetValue(SetValueError),
+    /// See [`PoolExhaustedError`].
+    PoolExhausted(PoolExhaustedError),
+    /// See [`PermutationError`].
+    Permutation(PermutationError),
+    /// See [`ClueCountError`].
+    ClueCount(ClueCountError),
+    /// See [`BoardSizeMismatchError`].
+    BoardSizeMismatch(BoardSizeMismatchError),
+    /// See [`UnsupportedVariantError`].
+    UnsupportedVariant(UnsupportedVariantError),
+    /// See [`BoxSizeOutOfRangeError`].
+    BoxSizeOutOfRange(BoxSizeOutOfRangeError),
+    /// See [`MalformedCsvError`].
+    MalformedCsv(MalformedCsvError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MalformedBoard(err) => err.fmt(f),
+            Self::ParseBoardSize(err) => err.fmt(f),
+            Self::BoardSizeOutOfRange(err) => err.fmt(f),
+            Self::SizeMismatch(err) => err.fmt(f),
+            Self::Unsolvable(err) => err.fmt(f),
+            Self::Stuck(err) => err.fmt(f),
+            Self::Solve(err) => err.fmt(f),
+            Self::SolveLine(err) => err.fmt(f),
+            Self::Puzzle(err) => err.fmt(f),
+            Self::Play(err) => err.fmt(f),
+            Self::MalformedGrid(err) => err.fmt(f),
+            Self::Consistency(err) => err.fmt(f),
+            Self::ValueOutOfRange(err) => err.fmt(f),
+            Self::SetValue(err) => err.fmt(f),
+            Self::PoolExhausted(err) => err.fmt(f),
+            Self::Permutation(err) => err.fmt(f),
+            Self::ClueCount(err) => err.fmt(f),
+            Self::BoardSizeMismatch(err) => err.fmt(f),
+            Self::UnsupportedVariant(err) => err.fmt(f),
+            Self::BoxSizeOutOfRange(err) => err.fmt(f),
+            Self::MalformedCsv(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::MalformedBoard(err) => Some(err),
+            Self::ParseBoardSize(err) => Some(err),
+            Self::BoardSizeOutOfRange(err) => Some(err),
+            Self::SizeMismatch(err) => Some(err),
+            Self::Unsolvable(err) => Some(err),
+            Self::Stuck(err) => Some(err),
+            Self::Solve(err) => Some(err),
+            Self::SolveLine(err) => Some(err),
+            Self::Puzzle(err) => Some(err),
+            Self::Play(err) => Some(err),
+            Self::MalformedGrid(err) => Some(err),
+            Self::Consistency(err) => Some(err),
+            Self::ValueOutOfRange(err) => Some(err),
+            Self::SetValue(err) => Some(err),
+            Self::PoolExhausted(err) => Some(err),
+            Self::Permutation(err) => Some(err),
+            Self::ClueCount(err) => Some(err),
+            Self::BoardSizeMismatch(err) => Some(err),
+            Self::UnsupportedVariant(err) => Some(err),
+            Self::BoxSizeOutOfRange(err) => Some(err),
+            Self::MalformedCsv(err) => Some(err),
+        }
+    }
+}
+
+impl From<MalformedBoardError> for Error {
+    fn from(err: MalformedBoardError) -> Self {
+        Self::MalformedBoard(err)
+    }
+}
+
+impl From<ParseBoardSizeError> for Error {
+    fn from(err: ParseBoardSizeError) -> Self {
+        Self::ParseBoardSize(err)
+    }
+}
+
+impl From<BoardSizeOutOfRangeError> for Error {
+    fn from(err: BoardSizeOutOfRangeError) -> Self {
+        Self::BoardSizeOutOfRange(err)
+    }
+}
+
+impl From<SizeMismatchError> for Error {
+    fn from(err: SizeMismatchError) -> Self {
+        Self::SizeMismatch(err)
+    }
+}
+
+impl From<UnsolvableError> for Error {
+    fn from(err: UnsolvableError) -> Self {
+        Self::Unsolvable(err)
+    }
+}
+
+impl From<StuckError> for Error {
+    fn from(err: StuckError) -> Self {
+        Self::Stuck(err)
+    }
+}
+
+impl From<SolveError> for Error {
+    fn from(err: SolveError) -> Self {
+        Self::Solve(err)
+    }
+}
+
+impl From<SolveLineError> for Error {
+    fn from(err: SolveLineError) -> Self {
+        Self::SolveLine(err)
+    }
+}
+
+impl From<PuzzleError> for Error {
+    fn from(err: PuzzleError) -> Self {
+        Self::Puzzle(err)
+    }
+}
+
+impl From<PlayError> for Error {
+    fn from(err: PlayError) -> Self {
+        Self::Play(err)
+    }
+}
+
+impl From<MalformedGridError> for Error {
+    fn from(err: MalformedGridError) -> Self {
+        Self::MalformedGrid(err)
+    }
+}
+
+impl From<ConsistencyError> for Error {
+    fn from(err: ConsistencyError) -> Self {
+        Self::Consistency(err)
+    }
+}
+
+impl From<ValueOutOfRangeError> for Error {
+    fn from(err: ValueOutOfRangeError) -> Self {
+        Self::ValueOutOfRange(err)
+    }
+}
+
+impl From<SetValueError> for Error {
+    fn from(err: SetValueError) -> Self {
+        Self::SetValue(err)
+    }
+}
+
+impl From<PoolExhaustedError> for Error {
+    fn from(err: PoolExhaustedError) -> Self {
+        Self::PoolExhausted(err)
+    }
+}
+
+impl From<PermutationError> for Error {
+    fn from(err: PermutationError) -> Self {
+        Self::Permutation(err)
+    }
+}
+
+impl From<ClueCountError> for Error {
+    fn from(err: ClueCountError) -> Self {
+        Self::ClueCount(err)
+    }
+}
+
+impl From<BoardSizeMismatchError> for Error {
+    fn from(err: BoardSizeMismatchError) -> Self {
+        Self::BoardSizeMismatch(err)
+    }
+}
+
+impl From<UnsupportedVariantError> for Error {
+    fn from(err: UnsupportedVariantError) -> Self {
+        Self::UnsupportedVariant(err)
+    }
+}
+
+impl From<BoxSizeOutOfRangeError> for Error {
+    fn from(err: BoxSizeOutOfRangeError) -> Self {
+        Self::BoxSizeOutOfRange(err)
+    }
+}
+
+impl From<MalformedCsvError> for Error {
+    fn from(err: MalformedCsvError) -> Self {
+        Self::MalformedCsv(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::board::{Board, BoardSize, Variant};
+    use crate::play::PlaySession;
+    use crate::solver::generator::Puzzle;
+    use crate::solver::{solve_lines, ConsistencyError, SolveError, StrategyConfig, StuckError};
+    use std::convert::TryInto;
+    use std::error::Error as _;
+
+    #[test]
+    fn conversions() {
+        let errors: Vec<Error> = vec![
+            "1...".parse::<Board>().unwrap_err().into(),
+            "5x5".parse::<BoardSize>().unwrap_err().into(),
+            TryInto::<BoardSize>::try_into(5_usize).unwrap_err().into(),
+            Board::new(BoardSize::FourByFour)
+                .try_get(&Board::new(BoardSize::NineByNine).cell_at(8, 8))
+                .unwrap_err()
+                .into(),
+            "123. ...4 .... ...."
+                .parse::<Board>()
+                .unwrap()
+                .solve()
+                .unwrap_err()
+                .into(),
+            StuckError.into(),
+            Board::new(BoardSize::FourByFour)
+                .solve_with_config(StrategyConfig {
+                    allow_guessing: false,
+                    ..Default::default()
+                })
+                .unwrap_err()
+                .into(),
+            solve_lines("1...").remove(0).unwrap_err().into(),
+            Puzzle::from_solution(&Board::new(BoardSize::FourByFour), &mut rand::thread_rng())
+                .unwrap_err()
+                .into(),
+            PlaySession::new("1... .... .... ....".parse().unwrap())
+                .clear(&Board::new(BoardSize::FourByFour).cell_at(0, 0))
+                .unwrap_err()
+                .into(),
+            Board::parse_grid("1...").unwrap_err().into(),
+            ConsistencyError::MissingCell(Board::new(BoardSize::FourByFour).cell_at(0, 0)).into(),
+            Board::new(BoardSize::FourByFour)
+                .value(5)
+                .unwrap_err()
+                .into(),
+            Board::new(BoardSize::FourByFour)
+                .try_set_value(&Board::new(BoardSize::FourByFour).cell_at(0, 0), 0)
+                .unwrap_err()
+                .into(),
+            Board::random_full_pool(BoardSize::FourByFour, 289, &mut rand::thread_rng())
+                .unwrap_err()
+                .into(),
+            Board::new(BoardSize::FourByFour)
+                .apply_permutation(&[0, 1, 2, 3], &[0, 1, 2, 3], &[1, 1, 2, 3])
+                .unwrap_err()
+                .into(),
+            Puzzle::generate_with_max_clues(BoardSize::FourByFour, 3)
+                .unwrap_err()
+                .into(),
+            Board::new(BoardSize::FourByFour)
+                .overlay(&Board::new(BoardSize::NineByNine))
+                .unwrap_err()
+                .into(),
+            Board::new(BoardSize::FourByFour)
+                .set_variant(Variant::Hyper)
+                .unwrap_err()
+                .into(),
+            BoardSize::custom(4, 5).unwrap_err().into(),
+            Board::from_csv("1,,,").unwrap_err().into(),
+        ];
+
+        for (idx, err) in errors.iter().enumerate() {
+            let variant = match err {
+                Error::MalformedBoard(_) => 0,
+                Error::ParseBoardSize(_) => 1,
+                Error::BoardSizeOutOfRange(_) => 2,
+                Error::SizeMismatch(_) => 3,
+                Error::Unsolvable(_) => 4,
+                Error::Stuck(_) => 5,
+                Error::Solve(SolveError::RequiresGuessing { .. }) => 6,
+                Error::Solve(
+                    SolveError::Unsolvable
+                    | SolveError::StepLimitExceeded
+                    | SolveError::InvalidValue(_),
+                ) => unreachable!(),
+                Error::SolveLine(_) => 7,
+                Error::Puzzle(_) => 8,
+                Error::Play(_) => 9,
+                Error::MalformedGrid(_) => 10,
+                Error::Consistency(_) => 11,
+                Error::ValueOutOfRange(_) => 12,
+                Error::SetValue(_) => 13,
+                Error::PoolExhausted(_) => 14,
+                Error::Permutation(_) => 15,
+                Error::ClueCount(_) => 16,
+                Error::BoardSizeMismatch(_) => 17,
+                Error::UnsupportedVariant(_) => 18,
+                Error::BoxSizeOutOfRange(_) => 19,
+                Error::MalformedCsv(_) => 20,
+            };
+            assert_eq!(variant, idx);
+
+            // the message is the one of the wrapped error
+            assert_eq!(err.to_string(), err.source().unwrap().to_string());
+        }
+    }
+}
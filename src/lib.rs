@@ -100,9 +100,10 @@
 //! the [Puzzle] structure and it's static [`Puzzle::generate`] function.
 //!
 //! # Puzzle quality
-//! Grading puzzles is beyond the scope of this crate. The reason behind it is that grading puzzles
-//! correctly, requires solving them like a human would and some of the more complex techniques to solve
-//! a puzzle like a human would require a lot of computations that do not always payoff performance-wise.
+//! Puzzles can be given a rough grade with [`Puzzle::difficulty`], which rates them by the hardest
+//! strategy needed to solve them: singles only, pair eliminations or guessing. It's not a full
+//! human-style grading, the more complex techniques people use would require a lot of computations
+//! that do not always payoff performance-wise.
 //!
 //! That being said, the generated puzzles consistently have between 22 and 26 clues making them likely
 //! on the harder side of most generally available puzzles.
@@ -122,8 +123,13 @@
 #![warn(rustdoc::missing_doc_code_examples)]
 
 pub mod board;
+#[cfg(feature = "capi")]
+pub mod capi;
+mod error;
+pub mod play;
 pub mod solver;
 
 pub use board::Board;
 pub use board::BoardSize;
+pub use error::Error;
 pub use solver::generator::Puzzle;
@@ -0,0 +1,180 @@
+//! Command line interface to solve and generate sudoku puzzles.
+//!
+//! ```text
+//! sudokugen solve [--format text|json]
+//! sudokugen gen [--count N] [--size 4x4|9x9|16x16] [--format text|json]
+//! ```
+//!
+//! `solve` reads one puzzle per line from stdin, in any of the formats accepted by
+//! [`Board`]'s `from_str`, and writes the solutions to stdout. `gen` writes `count`
+//! new puzzles to stdout.
+//!
+//! The text format writes one board per line, the json format writes an array with an
+//! object for each puzzle solved or generated.
+
+use serde_json::json;
+use std::env;
+use std::io::{self, BufRead};
+use std::process;
+use std::time::Instant;
+use sudokugen::solver::Solver;
+use sudokugen::{Board, BoardSize, Puzzle};
+
+const USAGE: &str = "\
+Usage:
+    sudokugen solve [--format text|json]
+    sudokugen gen [--count N] [--size 4x4|9x9|16x16] [--format text|json]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Solve,
+    Generate { count: usize, board_size: BoardSize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Solved,
+    Unsolvable,
+    Invalid,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Solved => "solved",
+            Status::Unsolvable => "unsolvable",
+            Status::Invalid => "invalid",
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = parse_args(&args).and_then(|(command, format)| match command {
+        Command::Solve => solve(format),
+        Command::Generate { count, board_size } => generate(count, board_size, format),
+    });
+
+    if let Err(err) = result {
+        eprintln!("{}\n\n{}", err, USAGE);
+        process::exit(2);
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(Command, Format), String> {
+    let mut args = args.iter();
+    let command = args.next().ok_or("Missing command")?;
+
+    let mut format = Format::Text;
+    let mut count = 1;
+    let mut board_size = BoardSize::NineByNine;
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))?;
+
+        match flag.as_str() {
+            "--format" => {
+                format = match value.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err(format!("Unknown format {}", value)),
+                }
+            }
+            "--count" => {
+                count = value
+                    .parse()
+                    .map_err(|_| format!("Invalid count {}", value))?
+            }
+            "--size" => board_size = value.parse().map_err(|err| format!("{}", err))?,
+            _ => return Err(format!("Unknown option {}", flag)),
+        }
+    }
+
+    match command.as_str() {
+        "solve" => Ok((Command::Solve, format)),
+        "gen" => Ok((Command::Generate { count, board_size }, format)),
+        _ => Err(format!("Unknown command {}", command)),
+    }
+}
+
+fn solve(format: Format) -> Result<(), String> {
+    let mut solver = Solver::new();
+    let mut results = Vec::new();
+
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|err| format!("Could not read stdin: {}", err))?;
+        let input = line.trim();
+        if input.is_empty() {
+            continue;
+        }
+
+        let start = Instant::now();
+        let (status, solution) = match input.parse::<Board>() {
+            Ok(mut board) => match solver.solve(&mut board) {
+                Ok(()) => (Status::Solved, Some(board)),
+                Err(_) => (Status::Unsolvable, None),
+            },
+            Err(_) => (Status::Invalid, None),
+        };
+        let millis = start.elapsed().as_millis();
+
+        match format {
+            Format::Text => match &solution {
+                Some(solution) => println!("{}", solution.to_line_string()),
+                None => println!("{}", status.as_str()),
+            },
+            Format::Json => results.push(json!({
+                "input": input,
+                "solution": solution,
+                "status": status.as_str(),
+                "millis": millis,
+            })),
+        }
+    }
+
+    if format == Format::Json {
+        println!("{}", json!(results));
+    }
+
+    Ok(())
+}
+
+fn generate(count: usize, board_size: BoardSize, format: Format) -> Result<(), String> {
+    let mut results = Vec::new();
+
+    for _ in 0..count {
+        let puzzle = Puzzle::generate(board_size);
+
+        match format {
+            Format::Text => println!("{}", puzzle.board().to_line_string()),
+            Format::Json => {
+                let board = puzzle.board();
+                let clues = board
+                    .iter_cells()
+                    .filter(|cell| board.get(cell).is_some())
+                    .count();
+
+                results.push(json!({
+                    "puzzle": board,
+                    "solution": puzzle.solution(),
+                    "clues": clues,
+                }));
+            }
+        }
+    }
+
+    if format == Format::Json {
+        println!("{}", json!(results));
+    }
+
+    Ok(())
+}
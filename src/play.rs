@@ -0,0 +1,520 @@
+//! Keeps track of the moves of a player solving a puzzle by hand, so they can be undone
+//! and redone, see [`PlaySession`].
+//!
+//! ```
+//! use sudokugen::board::Board;
+//! use sudokugen::play::PlaySession;
+//!
+//! let board: Board = "1... .... .... ....".parse().unwrap();
+//! let mut session = PlaySession::new(board);
+//!
+//! let cell = session.board().cell_at(0, 1);
+//! session.set(&cell, 2).unwrap();
+//! session.undo();
+//! assert_eq!(session.board().get(&cell), None);
+//!
+//! session.redo();
+//! assert_eq!(session.board().get(&cell), Some(2));
+//! ```
+
+use crate::board::{Board, CellLoc, SizeMismatchError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error;
+use std::fmt;
+
+/// A value placed in or cleared from a cell by the player, see [`PlaySession::moves`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerMove {
+    cell: CellLoc,
+    value: Option<u8>,
+    previous: Option<u8>,
+}
+
+impl PlayerMove {
+    /// Returns the cell changed by this move.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(session.moves()[0].cell(), cell);
+    /// ```
+    pub fn cell(&self) -> CellLoc {
+        self.cell
+    }
+
+    /// Returns the value placed in the cell, `None` if the cell was cleared.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    /// session.clear(&cell).unwrap();
+    ///
+    /// assert_eq!(session.moves()[0].value(), Some(2));
+    /// assert_eq!(session.moves()[1].value(), None);
+    /// ```
+    pub fn value(&self) -> Option<u8> {
+        self.value
+    }
+
+    /// Returns the value in the cell before this move.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    /// session.set(&cell, 3).unwrap();
+    ///
+    /// assert_eq!(session.moves()[1].previous(), Some(2));
+    /// ```
+    pub fn previous(&self) -> Option<u8> {
+        self.previous
+    }
+}
+
+/// Error returned by [`PlaySession`] when a move can't be made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayError {
+    /// The cell was filled when the session started, so it can't be changed.
+    Given(CellLoc),
+    /// The value is not between 1 and the maximum value of the board.
+    InvalidValue(u8),
+    /// The cell is not a valid location in the board of the session.
+    SizeMismatch(SizeMismatchError),
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Given(cell) => write!(f, "Cell {} is a given and can't be changed", cell),
+            Self::InvalidValue(value) => write!(f, "{} is not a valid value for this board", value),
+            Self::SizeMismatch(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for PlayError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::SizeMismatch(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<SizeMismatchError> for PlayError {
+    fn from(err: SizeMismatchError) -> Self {
+        Self::SizeMismatch(err)
+    }
+}
+
+/// A board being solved by a player, with the history of their moves.
+///
+/// The cells filled when the session is created are the givens of the puzzle and can't
+/// be changed. Every other change is recorded so it can be undone and redone, making a
+/// new move after undoing discards the moves that could be redone.
+///
+/// Pencil marks are notes of the values the player thinks could go in a cell, they
+/// don't affect the board and are not part of the history.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::play::{PlayError, PlaySession};
+///
+/// let board: Board = "1... .... .... ....".parse().unwrap();
+/// let mut session = PlaySession::new(board);
+///
+/// let given = session.board().cell_at(0, 0);
+/// assert_eq!(session.set(&given, 2), Err(PlayError::Given(given)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlaySession {
+    start: Board,
+    board: Board,
+    moves: Vec<PlayerMove>,
+    /// Moves undone most recent last, so the next one to redo is at the end.
+    undone: Vec<PlayerMove>,
+    pencil_marks: BTreeMap<CellLoc, BTreeSet<u8>>,
+}
+
+impl PlaySession {
+    /// Starts a session to solve `board`, its filled cells become the givens.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// let session = PlaySession::new(board.clone());
+    ///
+    /// assert_eq!(session.board(), &board);
+    /// ```
+    pub fn new(board: Board) -> Self {
+        PlaySession {
+            start: board.clone(),
+            board,
+            moves: Vec::new(),
+            undone: Vec::new(),
+            pencil_marks: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the board with the moves made so far.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(session.board().get(&cell), Some(2));
+    /// ```
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns true if the cell was filled when the session started.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    ///
+    /// assert!(session.is_given(&session.board().cell_at(0, 0)));
+    /// assert!(!session.is_given(&session.board().cell_at(0, 1)));
+    /// ```
+    pub fn is_given(&self, cell: &CellLoc) -> bool {
+        self.start.try_get(cell).ok().flatten().is_some()
+    }
+
+    /// Places `value` in `cell` and returns the value that was there before.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::{PlayError, PlaySession};
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    ///
+    /// assert_eq!(session.set(&cell, 2), Ok(None));
+    /// assert_eq!(session.set(&cell, 3), Ok(Some(2)));
+    /// assert_eq!(session.set(&cell, 5), Err(PlayError::InvalidValue(5)));
+    /// ```
+    pub fn set(&mut self, cell: &CellLoc, value: u8) -> Result<Option<u8>, PlayError> {
+        if value == 0 || value > self.board.board_size().max_value() {
+            return Err(PlayError::InvalidValue(value));
+        }
+
+        self.play(cell, Some(value))
+    }
+
+    /// Removes the value in `cell` and returns it, does nothing if the cell is empty.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(session.clear(&cell), Ok(Some(2)));
+    /// assert_eq!(session.clear(&cell), Ok(None));
+    /// assert_eq!(session.moves().len(), 2);
+    /// ```
+    pub fn clear(&mut self, cell: &CellLoc) -> Result<Option<u8>, PlayError> {
+        if self.board.try_get(cell)?.is_none() {
+            return Ok(None);
+        }
+
+        self.play(cell, None)
+    }
+
+    fn play(&mut self, cell: &CellLoc, value: Option<u8>) -> Result<Option<u8>, PlayError> {
+        if self.start.try_get(cell)?.is_some() {
+            return Err(PlayError::Given(*cell));
+        }
+
+        let mov = PlayerMove {
+            cell: *cell,
+            value,
+            previous: self.board.get(cell),
+        };
+        self.apply(cell, value);
+        self.moves.push(mov);
+        self.undone.clear();
+
+        Ok(mov.previous)
+    }
+
+    fn apply(&mut self, cell: &CellLoc, value: Option<u8>) {
+        match value {
+            Some(value) => self.board.set(cell, value),
+            None => self.board.unset(cell),
+        };
+    }
+
+    /// Reverts the last move and returns it, `None` if there are no moves left to undo.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(session.undo().map(|mov| mov.value()), Some(Some(2)));
+    /// assert_eq!(session.board().get(&cell), None);
+    /// assert_eq!(session.undo(), None);
+    /// ```
+    pub fn undo(&mut self) -> Option<PlayerMove> {
+        let mov = self.moves.pop()?;
+        self.apply(&mov.cell, mov.previous);
+        self.undone.push(mov);
+
+        Some(mov)
+    }
+
+    /// Makes the last undone move again and returns it, `None` if there are no moves
+    /// left to redo.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.set(&cell, 2).unwrap();
+    /// session.undo();
+    ///
+    /// assert!(session.redo().is_some());
+    /// assert_eq!(session.board().get(&cell), Some(2));
+    /// assert_eq!(session.redo(), None);
+    /// ```
+    pub fn redo(&mut self) -> Option<PlayerMove> {
+        let mov = self.undone.pop()?;
+        self.apply(&mov.cell, mov.value);
+        self.moves.push(mov);
+
+        Some(mov)
+    }
+
+    /// Returns the board to the state it was when the session started, discarding every
+    /// move and pencil mark.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// let mut session = PlaySession::new(board.clone());
+    /// session.set(&board.cell_at(0, 1), 2).unwrap();
+    /// session.reset_to_start();
+    ///
+    /// assert_eq!(session.board(), &board);
+    /// assert!(session.moves().is_empty());
+    /// ```
+    pub fn reset_to_start(&mut self) {
+        self.board = self.start.clone();
+        self.moves.clear();
+        self.undone.clear();
+        self.pencil_marks.clear();
+    }
+
+    /// Returns the moves made since the session started, oldest first, without the ones
+    /// that were undone.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// session.set(&session.board().cell_at(0, 1), 2).unwrap();
+    /// session.set(&session.board().cell_at(0, 2), 3).unwrap();
+    /// session.undo();
+    ///
+    /// assert_eq!(session.moves().len(), 1);
+    /// ```
+    pub fn moves(&self) -> &[PlayerMove] {
+        &self.moves
+    }
+
+    /// Adds `value` to the pencil marks of `cell`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.add_pencil_mark(&cell, 2).unwrap();
+    ///
+    /// assert!(session.pencil_marks(&cell).any(|value| value == 2));
+    /// ```
+    pub fn add_pencil_mark(&mut self, cell: &CellLoc, value: u8) -> Result<(), PlayError> {
+        self.check_pencil_mark(cell, value)?;
+        self.pencil_marks.entry(*cell).or_default().insert(value);
+        Ok(())
+    }
+
+    /// Removes `value` from the pencil marks of `cell`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.add_pencil_mark(&cell, 2).unwrap();
+    /// session.remove_pencil_mark(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(session.pencil_marks(&cell).count(), 0);
+    /// ```
+    pub fn remove_pencil_mark(&mut self, cell: &CellLoc, value: u8) -> Result<(), PlayError> {
+        self.check_pencil_mark(cell, value)?;
+
+        if let Some(marks) = self.pencil_marks.get_mut(cell) {
+            marks.remove(&value);
+            if marks.is_empty() {
+                self.pencil_marks.remove(cell);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_pencil_mark(&self, cell: &CellLoc, value: u8) -> Result<(), PlayError> {
+        if value == 0 || value > self.board.board_size().max_value() {
+            return Err(PlayError::InvalidValue(value));
+        }
+
+        if self.start.try_get(cell)?.is_some() {
+            return Err(PlayError::Given(*cell));
+        }
+        Ok(())
+    }
+
+    /// Iterates over the pencil marks of `cell` in ascending order.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::play::PlaySession;
+    ///
+    /// let mut session = PlaySession::new("1... .... .... ....".parse().unwrap());
+    /// let cell = session.board().cell_at(0, 1);
+    /// session.add_pencil_mark(&cell, 4).unwrap();
+    /// session.add_pencil_mark(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(session.pencil_marks(&cell).collect::<Vec<u8>>(), vec![2, 4]);
+    /// ```
+    pub fn pencil_marks(&self, cell: &CellLoc) -> impl Iterator<Item = u8> + '_ {
+        self.pencil_marks
+            .get(cell)
+            .into_iter()
+            .flat_map(|marks| marks.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlayError, PlaySession};
+    use crate::board::{Board, BoardSize};
+
+    fn session() -> PlaySession {
+        PlaySession::new("1... .... .... ...2".parse().unwrap())
+    }
+
+    #[test]
+    fn undo_redo_interleaved() {
+        let mut session = session();
+        let start = session.board().clone();
+        let (a, b) = (start.cell_at(0, 1), start.cell_at(1, 1));
+
+        session.set(&a, 2).unwrap();
+        session.set(&b, 3).unwrap();
+        session.set(&a, 4).unwrap();
+        let after_moves = session.board().clone();
+
+        session.undo().unwrap();
+        assert_eq!(session.board().get(&a), Some(2));
+        session.undo().unwrap();
+        assert_eq!(session.board().get(&b), None);
+        session.redo().unwrap();
+        assert_eq!(session.board().get(&b), Some(3));
+        session.redo().unwrap();
+        assert_eq!(session.board(), &after_moves);
+        assert_eq!(session.redo(), None);
+
+        while session.undo().is_some() {}
+        assert_eq!(session.board(), &start);
+        assert!(session.moves().is_empty());
+
+        // a new move discards the moves that could be redone
+        session.redo().unwrap();
+        session.clear(&a).unwrap();
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.moves().len(), 2);
+        assert_eq!(session.board(), &start);
+
+        session.undo().unwrap();
+        assert_eq!(session.board().get(&a), Some(2));
+    }
+
+    #[test]
+    fn givens_are_protected() {
+        let mut session = session();
+        let given = session.board().cell_at(3, 3);
+
+        assert!(session.is_given(&given));
+        assert_eq!(session.set(&given, 1), Err(PlayError::Given(given)));
+        assert_eq!(session.clear(&given), Err(PlayError::Given(given)));
+        assert_eq!(
+            session.add_pencil_mark(&given, 1),
+            Err(PlayError::Given(given))
+        );
+        assert_eq!(session.board().get(&given), Some(2));
+        assert!(session.moves().is_empty());
+
+        let other_size = Board::new(BoardSize::NineByNine).cell_at(8, 8);
+        assert!(matches!(
+            session.set(&other_size, 1),
+            Err(PlayError::SizeMismatch(_))
+        ));
+        assert_eq!(
+            session.set(&session.board().cell_at(0, 1), 0),
+            Err(PlayError::InvalidValue(0))
+        );
+    }
+
+    #[test]
+    fn pencil_marks() {
+        let mut session = session();
+        let cell = session.board().cell_at(0, 1);
+
+        session.add_pencil_mark(&cell, 3).unwrap();
+        session.add_pencil_mark(&cell, 2).unwrap();
+        session.set(&cell, 2).unwrap();
+        session.undo().unwrap();
+        assert_eq!(session.pencil_marks(&cell).collect::<Vec<u8>>(), vec![2, 3]);
+
+        session.remove_pencil_mark(&cell, 3).unwrap();
+        assert_eq!(session.pencil_marks(&cell).collect::<Vec<u8>>(), vec![2]);
+
+        session.reset_to_start();
+        assert_eq!(session.pencil_marks(&cell).count(), 0);
+    }
+}
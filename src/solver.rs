@@ -33,30 +33,50 @@
 //! [`solve`]: fn.solve.html
 
 mod candidate_cache;
+mod candidates9;
+pub mod dlx;
 pub mod generator;
 mod indexed_map;
+mod specialized;
 
-use crate::board::{Board, CellLoc};
+use crate::board::{
+    Board, BoardSize, CellLoc, MalformedBoardError, SetValueError, SizeMismatchError, Variant,
+};
 use candidate_cache::CandidateCache;
+use candidates9::Candidates9;
 use indexed_map::Map;
 use rand::seq::IteratorRandom;
-use std::collections::BTreeSet;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::error;
 use std::fmt;
 
-#[derive(Debug, Clone, Copy)]
-enum Strategy {
+pub use candidate_cache::{Block, ConsistencyError};
+
+/// The strategy used by the solver to place a value in a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// The value is the only one left that can be placed in the cell.
     NakedSingle,
+    /// The cell is the only one left in a line, column or square that can hold the value.
     HiddenSingle,
+    /// No strategy could be applied so the solver is trying one of the cell's candidates.
     Guess,
 }
 
+/// A value deduced for a cell, with the strategy and block used to deduce it.
+type Deduction = (u8, Strategy, Option<Block>);
+
 #[derive(Debug, Clone)]
 enum MoveLog {
     SetValue {
         strategy: Strategy,
         cell: CellLoc,
         value: u8,
+        block: Option<Block>,
         undo_candidates: candidate_cache::UndoSetValue,
     },
 }
@@ -81,6 +101,214 @@ impl MoveLog {
     }
 }
 
+/// A single value placed in the board while solving it, see [`Board::solution_steps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveStep {
+    cell: CellLoc,
+    value: u8,
+    strategy: Strategy,
+    block: Option<Block>,
+}
+
+impl SolveStep {
+    /// Returns the cell where the value was placed.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let steps = board.solution_steps().unwrap();
+    ///
+    /// assert_eq!(steps[0].cell(), board.cell_at(0, 3));
+    /// ```
+    pub fn cell(&self) -> CellLoc {
+        self.cell
+    }
+
+    /// Returns the value placed in the cell.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let steps = board.solution_steps().unwrap();
+    ///
+    /// assert_eq!(steps[0].value(), 4);
+    /// ```
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// Returns the strategy used to find the value.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::Strategy;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let steps = board.solution_steps().unwrap();
+    ///
+    /// assert_eq!(steps[0].strategy(), Strategy::NakedSingle);
+    /// ```
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// Returns the block where the cell was found to be the only candidate for
+    /// the value, only available for [`Strategy::HiddenSingle`] steps.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let steps = board.solution_steps().unwrap();
+    ///
+    /// assert_eq!(steps[0].block(), None);
+    /// ```
+    pub fn block(&self) -> Option<Block> {
+        self.block
+    }
+
+    /// Describes this step in plain english, cells are referenced by their 1 based
+    /// row and column numbers, e.g. `R1C3`.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let steps = board.solution_steps().unwrap();
+    ///
+    /// assert_eq!(steps[0].explain(board.board_size()), "R1C4 can only be 4");
+    /// ```
+    pub fn explain(&self, board_size: BoardSize) -> String {
+        let cell = format!("R{}C{}", self.cell.line() + 1, self.cell.col() + 1);
+
+        match (self.strategy, self.block) {
+            (Strategy::HiddenSingle, Some(block)) => {
+                let block = match block {
+                    Block::Line(line) => format!("row {}", line + 1),
+                    Block::Col(col) => format!("column {}", col + 1),
+                    Block::Square(square) => {
+                        let (box_rows, box_cols) = (board_size.box_rows(), board_size.box_cols());
+                        let line = (square / box_rows) * box_rows;
+                        let col = (square % box_rows) * box_cols;
+                        format!(
+                            "the square from R{}C{} to R{}C{}",
+                            line + 1,
+                            col + 1,
+                            line + box_rows,
+                            col + box_cols
+                        )
+                    }
+                    Block::Extra(extra) => format!("extra region {}", extra + 1),
+                };
+
+                format!(
+                    "{} must be {} because it is the only cell in {} that can contain {}",
+                    cell, self.value, block, self.value
+                )
+            }
+            (Strategy::NakedSingle, _) | (Strategy::HiddenSingle, None) => {
+                format!("{} can only be {}", cell, self.value)
+            }
+            (Strategy::Guess, _) => format!(
+                "No other strategy can be applied, guessing that {} is {}",
+                cell, self.value
+            ),
+        }
+    }
+}
+
+/// A value removed from the board while backtracking, see [`Board::solve_traced`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktrackStep {
+    cell: CellLoc,
+    tried: u8,
+    reason: BacktrackReason,
+}
+
+impl BacktrackStep {
+    /// Returns the cell that was cleared.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::TraceStep;
+    ///
+    /// let board: Board = "1... .... .... ...2".parse().unwrap();
+    /// for step in board.clone().solve_traced().unwrap() {
+    ///     if let TraceStep::Backtrack(backtrack) = step {
+    ///         assert!(board.get(&backtrack.cell()).is_none());
+    ///     }
+    /// }
+    /// ```
+    pub fn cell(&self) -> CellLoc {
+        self.cell
+    }
+
+    /// Returns the value that was in the cell before it was cleared.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::TraceStep;
+    ///
+    /// let mut board: Board = "................".parse().unwrap();
+    /// for step in board.solve_traced().unwrap() {
+    ///     if let TraceStep::Backtrack(backtrack) = step {
+    ///         println!("{} was not {}", backtrack.cell(), backtrack.tried());
+    ///     }
+    /// }
+    /// ```
+    pub fn tried(&self) -> u8 {
+        self.tried
+    }
+
+    /// Returns why the value was removed.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::{BacktrackReason, TraceStep};
+    ///
+    /// let mut board: Board = "................".parse().unwrap();
+    /// let wrong_guesses = board
+    ///     .solve_traced()
+    ///     .unwrap()
+    ///     .iter()
+    ///     .filter(|step| match step {
+    ///         TraceStep::Backtrack(backtrack) => backtrack.reason() == BacktrackReason::WrongGuess,
+    ///         TraceStep::Place(_) => false,
+    ///     })
+    ///     .count();
+    ///
+    /// println!("The solver guessed wrong {} times", wrong_guesses);
+    /// ```
+    pub fn reason(&self) -> BacktrackReason {
+        self.reason
+    }
+}
+
+/// Why a value was removed from the board while backtracking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktrackReason {
+    /// The value was a guess that led to a board without solution.
+    WrongGuess,
+    /// The value was deduced after a guess that turned out to be wrong.
+    FollowedWrongGuess,
+}
+
+/// A single step of [`SolveTrace`], either placing a value or removing it while
+/// backtracking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceStep {
+    /// A value was placed in a cell.
+    Place(SolveStep),
+    /// A value was removed from a cell.
+    Backtrack(BacktrackStep),
+}
+
+/// Every step taken by the solver in the order they happened, including the guesses
+/// that turned out to be wrong, see [`Board::solve_traced`].
+pub type SolveTrace = Vec<TraceStep>;
+
 /// An error to represent that this board is not solvable in it's current state
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UnsolvableError;
@@ -99,18 +327,329 @@ impl error::Error for UnsolvableError {
     }
 }
 
+/// Error returned by [`Board::solve_checked`] when a cell holds a value that can't be
+/// part of a solution, 0 or larger than the largest value of the board. Values are
+/// checked when they are set, so boards created through this crate never hold them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValueError {
+    cell: CellLoc,
+    value: u8,
+    board_size: BoardSize,
+}
+
+impl InvalidValueError {
+    /// Returns the cell holding the invalid value.
+    pub fn cell(&self) -> CellLoc {
+        self.cell
+    }
+
+    /// Returns the invalid value.
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+impl fmt::Display for InvalidValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Cell {} holds {}, values of a {} board go from 1 to {}",
+            self.cell,
+            self.value,
+            self.board_size,
+            self.board_size.max_value()
+        )
+    }
+}
+
+impl error::Error for InvalidValueError {}
+
+/// Receives events from the solver as it fills and clears cells in the board.
+///
+/// All methods have an empty default implementation, implement only the ones you're
+/// interested in. Events are reported in the order they happen, including the placement
+/// and removal of guesses that later turned out to be wrong.
+///
+/// ```
+/// use sudokugen::board::{Board, CellLoc};
+/// use sudokugen::solver::{SolveObserver, Strategy};
+///
+/// #[derive(Default)]
+/// struct CountGuesses(usize);
+///
+/// impl SolveObserver for CountGuesses {
+///     fn on_place(&mut self, _cell: CellLoc, _value: u8, strategy: Strategy) {
+///         if strategy == Strategy::Guess {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// let mut board: Board = "1... .... .... ....".parse().unwrap();
+/// let mut observer = CountGuesses::default();
+/// board.solve_with_observer(&mut observer).unwrap();
+///
+/// println!("The solver guessed {} times", observer.0);
+/// ```
+pub trait SolveObserver {
+    /// Called after `value` was placed in `cell` using `strategy`.
+    fn on_place(&mut self, _cell: CellLoc, _value: u8, _strategy: Strategy) {}
+
+    /// Called after `value` was removed from `cell` while backtracking.
+    fn on_undo(&mut self, _cell: CellLoc, _value: u8) {}
+
+    /// Called when the solver reaches a dead end and starts backtracking.
+    fn on_backtrack_start(&mut self) {}
+}
+
+impl<T: SolveObserver + ?Sized> SolveObserver for &mut T {
+    fn on_place(&mut self, cell: CellLoc, value: u8, strategy: Strategy) {
+        (**self).on_place(cell, value, strategy);
+    }
+
+    fn on_undo(&mut self, cell: CellLoc, value: u8) {
+        (**self).on_undo(cell, value);
+    }
+
+    fn on_backtrack_start(&mut self) {
+        (**self).on_backtrack_start();
+    }
+}
+
+/// An error to represent that this board cannot be solved without guessing.
+///
+/// This is returned when solving a board using only the strategies that don't
+/// require guessing, either because the solver got stuck or because the board has
+/// no solution at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StuckError;
+
+impl fmt::Display for StuckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The board cannot be solved without guessing")
+    }
+}
+
+impl error::Error for StuckError {}
+
+/// Options to control how the solver picks its guesses, see [`Board::solve_with_options`].
+///
+/// ```
+/// use sudokugen::solver::SolveOptions;
+///
+/// let options = SolveOptions {
+///     deterministic: false,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveOptions {
+    /// When true the smallest possible value is guessed, otherwise the value is picked at
+    /// random. With `true`, the default, solving the same board always makes the same moves.
+    pub deterministic: bool,
+    /// When true, the default, the solver guesses the value of the cell with the fewest
+    /// possible values, otherwise it guesses the first empty cell. Ties between cells
+    /// are always broken by picking the cell with the lowest index, see [`CellLoc::get_index`].
+    ///
+    /// [`CellLoc::get_index`]: ../board/struct.CellLoc.html#method.get_index
+    pub prefer_fewest_candidates: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            deterministic: true,
+            prefer_fewest_candidates: true,
+        }
+    }
+}
+
+/// Selects the strategies the solver may use, see [`Board::solve_with_config`].
+///
+/// The default enables the same strategies [`Board::solve`] uses, naked and hidden
+/// singles and guessing. Naked pairs and pointing pairs only remove candidates, they
+/// are disabled by default since guessing is usually faster than looking for them.
+///
+/// ```
+/// use sudokugen::solver::StrategyConfig;
+///
+/// let config = StrategyConfig {
+///     naked_pairs: true,
+///     pointing_pairs: true,
+///     allow_guessing: false,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrategyConfig {
+    /// Place values that are the only candidate left for a cell.
+    pub naked_singles: bool,
+    /// Place values that can only go in one cell of a line, column or square.
+    pub hidden_singles: bool,
+    /// When two cells of a line, column or square have the same two candidates, remove
+    /// those candidates from the other cells of the block.
+    pub naked_pairs: bool,
+    /// When every candidate cell for a value in a square is in the same line or column,
+    /// remove the value from the cells of that line or column outside the square.
+    pub pointing_pairs: bool,
+    /// Guess a value when no other strategy can be applied, backtracking if it was wrong.
+    pub allow_guessing: bool,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        StrategyConfig {
+            naked_singles: true,
+            hidden_singles: true,
+            naked_pairs: false,
+            pointing_pairs: false,
+            allow_guessing: true,
+        }
+    }
+}
+
+/// Error returned by [`Board::solve_with_config`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The board has no solution.
+    Unsolvable,
+    /// None of the strategies enabled can be applied and guessing is not allowed, with
+    /// the board as far as those strategies could take it.
+    RequiresGuessing {
+        /// The board with every value placed before the solver got stuck.
+        board_state: Board,
+    },
+    /// The solver placed more values than allowed by [`Board::solve_with_step_limit`].
+    StepLimitExceeded,
+    /// A cell of the board holds a value out of range.
+    InvalidValue(InvalidValueError),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unsolvable => write!(f, "{}", UnsolvableError),
+            Self::RequiresGuessing { .. } => write!(f, "{}", StuckError),
+            Self::StepLimitExceeded => {
+                write!(f, "The board could not be solved within the step limit")
+            }
+            Self::InvalidValue(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Unsolvable => Some(&UnsolvableError),
+            Self::RequiresGuessing { .. } => Some(&StuckError),
+            Self::StepLimitExceeded => None,
+            Self::InvalidValue(err) => Some(err),
+        }
+    }
+}
+
+impl From<UnsolvableError> for SolveError {
+    fn from(_: UnsolvableError) -> Self {
+        SolveError::Unsolvable
+    }
+}
+
+impl From<InvalidValueError> for SolveError {
+    fn from(err: InvalidValueError) -> Self {
+        SolveError::InvalidValue(err)
+    }
+}
+
+/// The cells filled by the solver in [`Board::solve_annotated`], every other cell of the
+/// board was a given of the puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolvedAnnotation {
+    filled: BTreeSet<CellLoc>,
+}
+
+impl SolvedAnnotation {
+    /// Returns the cells filled by the solver.
+    pub fn filled_cells(&self) -> &BTreeSet<CellLoc> {
+        &self.filled
+    }
+
+    /// Returns true if the value in the cell was placed by the solver, false if it
+    /// was a given.
+    pub fn is_filled(&self, cell: &CellLoc) -> bool {
+        self.filled.contains(cell)
+    }
+
+    /// Returns the number of cells filled by the solver.
+    pub fn len(&self) -> usize {
+        self.filled.len()
+    }
+
+    /// Returns true if the board was already solved, with no cells left to fill.
+    pub fn is_empty(&self) -> bool {
+        self.filled.is_empty()
+    }
+}
+
+/// A [`SolveObserver`] that ignores every event, used by [`SudokuSolver`] when no other
+/// observer is given.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopObserver;
+
+impl SolveObserver for NoopObserver {}
+
+/// Solves a single board one step at a time, for instance to report the progress of
+/// the solver. [`Board::solve`] is simpler to use when only the solution is needed.
+///
+/// Every step places at least one value in the board, or removes values while
+/// backtracking after a wrong guess, so the progress can go down as well as up.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::SudokuSolver;
+///
+/// let mut board: Board =
+///     "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+///         .parse()
+///         .unwrap();
+/// let mut solver = SudokuSolver::new(&mut board);
+///
+/// while !solver.is_solved() {
+///     solver.step().unwrap();
+///     println!("{:.0}% solved", solver.progress() * 100.0);
+/// }
+/// ```
 #[derive(Debug)]
-struct SudokuSolver<'a> {
+pub struct SudokuSolver<'a, O: SolveObserver = NoopObserver> {
     board: &'a mut Board,
     candidate_cache: CandidateCache,
     move_log: Vec<MoveLog>,
-    random: bool,
+    /// Candidates removed by the pair strategies, with the length of the move log when
+    /// they were removed so they are undone before the moves made until then.
+    eliminations: Vec<(usize, candidate_cache::UndoEliminate)>,
+    options: SolveOptions,
+    config: StrategyConfig,
+    rng: Option<ChaCha8Rng>,
+    observer: O,
+    /// Number of empty cells when the solver was created.
+    empty_cells: usize,
+    /// Every value placed and removed, only recorded when it's `Some`.
+    trace: Option<SolveTrace>,
+    /// Number of values placed so far, including the ones undone while backtracking.
+    steps: usize,
+    /// The first value out of range found in the board, which makes it unsolvable.
+    invalid_value: Option<InvalidValueError>,
+    /// Candidates allowed for some of the empty cells on top of the ones the board
+    /// allows, see [`Board::solve_with_candidates`].
+    allowed_candidates: HashMap<CellLoc, Candidates9>,
 }
 
 impl Board {
     /// Solves the sudoku puzzle.
     ///
-    /// Updates the current board with the solution to that sudoku puzzle.
+    /// Updates the current board with the solution to that sudoku puzzle. The solver uses
+    /// fixed size structures for each board size, but makes the same moves as a
+    /// [`SudokuSolver`] so both find the same solution.
     ///
     /// ```
     /// use sudokugen::board::Board;
@@ -145,197 +684,1998 @@ impl Board {
     ///
     /// ```
     /// # use sudokugen::board::Board;
+    /// # use sudokugen::solver::UnsolvableError;
     /// #
     /// let mut board: Board = "123. ...4 .... ....".parse().unwrap();
     /// assert!(matches!(board.solve(), Err(UnsolvableError)));
     /// ```
     ///
+    /// A cell holding a value out of range also makes the board unsolvable, use
+    /// [`Board::solve_checked`] to tell both cases apart.
+    ///
     /// [`board`]: ../board/struct.Board.html
     /// [`UnsolvableError`]: struct.UnsolvableError.html
     pub fn solve(&mut self) -> Result<(), UnsolvableError> {
-        let mut solver = SudokuSolver::new(self);
-        solver.solve()?;
-        Ok(())
+        specialized::solve(self)
     }
-}
 
-impl<'a> SudokuSolver<'a> {
-    fn new(board: &'a mut Board) -> Self {
-        let candidate_cache = CandidateCache::from_board(board);
-
-        SudokuSolver {
-            board,
-            move_log: Vec::new(),
-            candidate_cache,
-            random: false,
+    /// Solves the sudoku puzzle same as [`Board::solve`], but returns
+    /// [`SolveError::InvalidValue`] instead of [`SolveError::Unsolvable`] if a cell holds
+    /// a value out of range, 0 or larger than the largest value of the board. Values are
+    /// checked when they are set, so boards created through this crate never hold them.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SolveError;
+    ///
+    /// let mut board: Board = "123. ...4 .... ....".parse().unwrap();
+    /// assert_eq!(board.solve_checked(), Err(SolveError::Unsolvable));
+    /// ```
+    pub fn solve_checked(&mut self) -> Result<(), SolveError> {
+        // the specialized solver doesn't handle values out of range, so they are only
+        // checked once by the generic solver
+        match specialized::try_solve(self) {
+            Some(result) => Ok(result?),
+            None => SudokuSolver::new(self).solve_with_config(),
         }
     }
 
-    fn new_random(board: &'a mut Board) -> Self {
-        let mut solver = Self::new(board);
-        solver.random = true;
-        solver
+    /// Solves the sudoku puzzle same as [`Board::solve`], choosing how to guess when
+    /// no other strategy can be applied with [`SolveOptions`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SolveOptions;
+    ///
+    /// let mut board: Board = "................".parse().unwrap();
+    /// let options = SolveOptions {
+    ///     deterministic: false,
+    ///     prefer_fewest_candidates: false,
+    /// };
+    ///
+    /// assert!(board.solve_with_options(options).is_ok());
+    /// ```
+    pub fn solve_with_options(&mut self, options: SolveOptions) -> Result<(), UnsolvableError> {
+        SudokuSolver::with_options(self, options).solve()
     }
 
-    fn solve(&mut self) -> Result<(), UnsolvableError> {
-        if self
-            .candidate_cache
-            .possible_values()
-            .iter()
-            .any(|(_, values)| values.is_empty())
-        {
-            return Err(UnsolvableError);
-        }
-
-        while !self.candidate_cache.possible_values().is_empty() {
-            self.solve_iteration()?;
-        }
-        Ok(())
+    /// Solves the sudoku puzzle same as [`Board::solve`], with the candidates of some of
+    /// the empty cells already narrowed down, for instance a state imported with
+    /// [`Board::parse_with_candidates`]. The solution only has values in the candidates
+    /// given for each cell, even if ruling out the others doesn't follow from the board
+    /// alone. Candidates of filled cells and values that aren't possible anyway are
+    /// ignored, cells not in `candidates` can hold any possible value.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use std::collections::{BTreeSet, HashMap};
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// let cell = board.cell_at(0, 1);
+    ///
+    /// let mut candidates = HashMap::new();
+    /// candidates.insert(cell, vec![4].into_iter().collect::<BTreeSet<u8>>());
+    /// board.solve_with_candidates(&candidates).unwrap();
+    ///
+    /// assert_eq!(board.get(&cell), Some(4));
+    /// ```
+    pub fn solve_with_candidates(
+        &mut self,
+        candidates: &HashMap<CellLoc, BTreeSet<u8>>,
+    ) -> Result<(), UnsolvableError> {
+        let mut solver = SudokuSolver::new(self);
+        solver.restrict_candidates(candidates)?;
+        solver.solve()
     }
 
-    fn naked_singles(&self) -> BTreeSet<(CellLoc, u8)> {
-        self.candidate_cache
-            .possible_values()
-            .iter()
-            .filter_map(|(cell, values)| match values.len() {
-                1 => Some((*cell, *(values.iter().next().unwrap()))),
-                _ => None,
-            })
-            .collect()
+    /// Consumes the board and returns it solved, useful where the board is moved
+    /// rather than borrowed, e.g. when chaining calls or mapping over an iterator.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let solutions: Vec<Board> = vec!["1... .... .... ....", "..3. .... .... ..1."]
+    ///     .into_iter()
+    ///     .map(|line| line.parse::<Board>().unwrap().into_solved().unwrap())
+    ///     .collect();
+    ///
+    /// assert!(solutions.iter().all(|board| board.iter_cells().all(|cell| board.get(&cell).is_some())));
+    /// ```
+    pub fn into_solved(mut self) -> Result<Board, UnsolvableError> {
+        self.solve()?;
+        Ok(self)
     }
 
-    fn hidden_singles(&self) -> BTreeSet<(CellLoc, u8)> {
-        self.candidate_cache
-            .iter_candidates()
-            .filter_map(|candidate| {
-                if candidate.cells.len() != 1 {
-                    return None;
-                }
+    /// Returns a solved copy of the board, leaving the board itself unchanged.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// let solution = board.solved_copy().unwrap();
+    ///
+    /// assert_eq!(board.get_at(0, 1), None);
+    /// assert!(solution.get_at(0, 1).is_some());
+    /// ```
+    pub fn solved_copy(&self) -> Result<Board, UnsolvableError> {
+        self.clone().into_solved()
+    }
 
-                Some((*candidate.cells.iter().next().unwrap(), *candidate.value))
-            })
-            .collect()
+    /// Returns true if the board has exactly one solution and false if it has more than
+    /// one, the search stops as soon as a second solution is found. The board itself is
+    /// not changed.
+    ///
+    /// Unlike [`Puzzle::is_solution_unique`](generator::Puzzle::is_solution_unique) it
+    /// works with any board, not only generated ones.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "1... ..1. .... ...1".parse().unwrap();
+    /// assert_eq!(board.has_unique_solution(), Ok(false));
+    ///
+    /// let board: Board = "1... ..2. .3.. ...4".parse().unwrap();
+    /// assert_eq!(board.has_unique_solution(), Ok(true));
+    ///
+    /// let board: Board = "123. ...4 .... ....".parse().unwrap();
+    /// assert!(board.has_unique_solution().is_err());
+    /// ```
+    pub fn has_unique_solution(&self) -> Result<bool, UnsolvableError> {
+        let mut board = self.clone();
+        let solutions = SudokuSolver::new(&mut board).count_solutions(2)?;
+        Ok(solutions == 1)
     }
 
-    fn guess(&self) -> (CellLoc, u8) {
-        let rng = if self.random {
-            Some(rand::thread_rng())
-        } else {
-            None
-        };
+    /// Solves the sudoku puzzle same as [`Board::solve`], and returns which cells were
+    /// filled by the solver so they can be told apart from the givens afterwards.
+    ///
+    /// Only the cells empty before solving are reported, values placed by wrong guesses
+    /// and then removed while backtracking are not. To keep just the givens, take a
+    /// [`givens_mask`](Board::givens_mask) before calling [`solve`](Board::solve).
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "1... .... .... ...2".parse().unwrap();
+    /// let annotation = board.solve_annotated().unwrap();
+    ///
+    /// assert_eq!(annotation.len(), 14);
+    /// assert!(!annotation.is_filled(&board.cell_at(0, 0)));
+    /// assert!(annotation.is_filled(&board.cell_at(0, 1)));
+    /// ```
+    pub fn solve_annotated(&mut self) -> Result<SolvedAnnotation, UnsolvableError> {
+        let filled = self
+            .iter_cells()
+            .filter(|cell| self.get(cell).is_none())
+            .collect();
 
-        self.candidate_cache
-            .possible_values()
-            .iter()
-            .min_by_key(|(_cell, possibilities)| possibilities.len())
-            .map(|(cell, possibilities)| {
-                let value = rng
-                    .and_then(|mut rng| possibilities.iter().choose(&mut rng))
-                    .or_else(|| possibilities.iter().next())
-                    .expect("Empty possibilities should have been caught while registering a move");
-
-                (*cell, *value)
-            })
-            .expect("If the table is full then the method should have finished")
+        self.solve()?;
+        Ok(SolvedAnnotation { filled })
     }
 
-    #[cfg(debug)]
-    fn assert_possible_values(&self) {
-        let gen_possible_values = self.candidate_cache.possible_values();
-
-        if self.possible_values != gen_possible_values {
-            for cell in self
-                .possible_values
-                .keys()
-                .chain(gen_possible_values.keys())
-            {
-                if self.possible_values.get(cell) != gen_possible_values.get(cell) {
-                    println!("main {} -> {:?}", cell, self.possible_values.get(cell));
-                    println!("cache {} -> {:?}", cell, gen_possible_values.get(cell));
-                }
-            }
-            panic!();
-        }
+    /// Solves the sudoku puzzle using only strategies that don't require guessing.
+    ///
+    /// If the solver reaches a point where it would need to guess, it stops and returns
+    /// [`StuckError`], leaving in the board every value it was able to place until then.
+    /// This is [`solve_logical`](Self::solve_logical) without the partially solved board
+    /// in the error.
+    #[deprecated(note = "use `solve_logical`, which also returns the board state it got stuck on")]
+    pub fn solve_without_guessing(&mut self) -> Result<(), StuckError> {
+        self.solve_logical().map_err(|_| StuckError)
+    }
+
+    /// Solves a copy of the sudoku puzzle and returns the steps taken to reach the solution,
+    /// without the guesses that turned out to be wrong. The board itself is not changed.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let steps = board.solution_steps().unwrap();
+    ///
+    /// assert_eq!(steps.len(), 13);
+    /// ```
+    pub fn solution_steps(&self) -> Result<Vec<SolveStep>, UnsolvableError> {
+        let mut board = self.clone();
+        let mut solver = SudokuSolver::new(&mut board);
+        solver.solve()?;
+
+        Ok(solver
+            .move_log
+            .iter()
+            .map(|mov| match mov {
+                MoveLog::SetValue {
+                    strategy,
+                    cell,
+                    value,
+                    block,
+                    ..
+                } => SolveStep {
+                    cell: *cell,
+                    value: *value,
+                    strategy: *strategy,
+                    block: *block,
+                },
+            })
+            .collect())
+    }
+
+    /// Solves the sudoku puzzle same as [`Board::solve`] and returns every step taken by
+    /// the solver, including the values placed after a wrong guess and their removal
+    /// while backtracking. Replaying the steps on a copy of the puzzle leads to the same
+    /// solution.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::TraceStep;
+    ///
+    /// let puzzle: Board = "1... .... .... ...2".parse().unwrap();
+    /// let mut board = puzzle.clone();
+    /// let trace = board.solve_traced().unwrap();
+    ///
+    /// let mut replay = puzzle.clone();
+    /// for step in trace {
+    ///     match step {
+    ///         TraceStep::Place(step) => replay.set(&step.cell(), step.value()),
+    ///         TraceStep::Backtrack(step) => replay.unset(&step.cell()),
+    ///     };
+    /// }
+    ///
+    /// assert_eq!(replay, board);
+    /// ```
+    pub fn solve_traced(&mut self) -> Result<SolveTrace, UnsolvableError> {
+        let mut solver = SudokuSolver::new(self);
+        solver.trace = Some(Vec::new());
+        solver.solve()?;
+
+        Ok(solver.trace.take().unwrap_or_default())
+    }
+
+    /// Fills the empty cells in `cells`, and only those, with values that don't repeat in
+    /// any line, column or square of the filled cells of the board. The other empty cells
+    /// are left empty and are not required to have any value left, so a region can be
+    /// solved even when the whole board can't.
+    ///
+    /// Returns [`UnsolvableError`] if the region can't be filled, or if the filled cells
+    /// of the board already repeat a value, leaving the board unchanged. Cells in `cells`
+    /// that already have a value are kept. Panics like [`Board::set`] if any of the cells
+    /// was created for a board of a different size.
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board = "
+    /// 1 . | . .
+    /// . . | 2 .
+    /// ---------
+    /// . 3 | . .
+    /// . . | . .
+    /// ".parse().unwrap();
+    ///
+    /// let square: BTreeSet<_> = board
+    ///     .iter_cells()
+    ///     .filter(|cell| cell.square() == 0)
+    ///     .collect();
+    /// board.solve_region(&square).unwrap();
+    ///
+    /// assert_eq!(board, "12.. 342. .3.. ....".parse().unwrap());
+    /// ```
+    pub fn solve_region(&mut self, cells: &BTreeSet<CellLoc>) -> Result<(), UnsolvableError> {
+        let repeats_value = self.iter_cells().any(|cell| {
+            let value = self.get(&cell);
+            value.is_some()
+                && cell
+                    .iter_peers(self.variant())
+                    .any(|other| other != cell && self.get(&other) == value)
+        });
+        if repeats_value {
+            return Err(UnsolvableError);
+        }
+
+        let mut solver = SudokuSolver::new(self);
+        solver.restrict_to_region(cells);
+        solver.solve()
+    }
+
+    /// Describes each of the [`solution_steps`] of this board in plain english,
+    /// see [`SolveStep::explain`].
+    ///
+    /// [`solution_steps`]: Board::solution_steps
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    ///
+    /// for explanation in board.explain_solution().unwrap() {
+    ///     println!("{}", explanation);
+    /// }
+    /// ```
+    pub fn explain_solution(&self) -> Result<Vec<String>, UnsolvableError> {
+        let board_size = self.board_size();
+
+        Ok(self
+            .solution_steps()?
+            .iter()
+            .map(|step| step.explain(board_size))
+            .collect())
+    }
+
+    /// Solves the sudoku puzzle same as [`Board::solve`], reporting every value
+    /// placed and removed by the solver to `observer`.
+    ///
+    /// ```
+    /// use sudokugen::board::{Board, CellLoc};
+    /// use sudokugen::solver::{SolveObserver, Strategy};
+    ///
+    /// struct PrintMoves;
+    ///
+    /// impl SolveObserver for PrintMoves {
+    ///     fn on_place(&mut self, cell: CellLoc, value: u8, strategy: Strategy) {
+    ///         println!("{} at {} ({:?})", value, cell, strategy);
+    ///     }
+    /// }
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// board.solve_with_observer(&mut PrintMoves).unwrap();
+    /// ```
+    pub fn solve_with_observer<O: SolveObserver>(
+        &mut self,
+        observer: &mut O,
+    ) -> Result<(), UnsolvableError> {
+        let mut solver = SudokuSolver::with_observer(self, observer);
+        solver.solve()?;
+        Ok(())
+    }
+
+    /// Solves the sudoku puzzle using only the strategies enabled in `config`.
+    ///
+    /// If guessing is not allowed and none of the other strategies can be applied the
+    /// solver stops and returns [`SolveError::RequiresGuessing`] with a copy of the board,
+    /// which also keeps every value it was able to place until then.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::{SolveError, StrategyConfig};
+    ///
+    /// let config = StrategyConfig {
+    ///     allow_guessing: false,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut board: Board =
+    ///     "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+    ///         .parse()
+    ///         .unwrap();
+    /// assert_eq!(board.solve_with_config(config), Ok(()));
+    ///
+    /// let mut empty: Board = "................".parse().unwrap();
+    /// assert!(matches!(
+    ///     empty.solve_with_config(config),
+    ///     Err(SolveError::RequiresGuessing { .. })
+    /// ));
+    /// ```
+    pub fn solve_with_config(&mut self, config: StrategyConfig) -> Result<(), SolveError> {
+        let mut solver = SudokuSolver::new(self);
+        solver.config = config;
+        solver.solve_with_config()
+    }
+
+    /// Solves the sudoku puzzle using only naked and hidden singles, to check whether it
+    /// can be solved by logic alone.
+    ///
+    /// If the solver would need to guess it stops and returns
+    /// [`SolveError::RequiresGuessing`] with a copy of the partially solved board, which is
+    /// also left in `self`, for instance to show how far logic gets.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SolveError;
+    ///
+    /// let mut board: Board = "1... ..4. .... ...1".parse().unwrap();
+    ///
+    /// match board.solve_logical() {
+    ///     Err(SolveError::RequiresGuessing { board_state }) => {
+    ///         assert_eq!(board_state, board);
+    ///         assert!(board_state.clue_count() > 3);
+    ///     }
+    ///     other => panic!("expected to get stuck, got {:?}", other),
+    /// }
+    /// ```
+    pub fn solve_logical(&mut self) -> Result<(), SolveError> {
+        self.solve_with_config(StrategyConfig {
+            allow_guessing: false,
+            ..Default::default()
+        })
+    }
+
+    /// Solves the sudoku puzzle same as [`Board::solve`], giving up with
+    /// [`SolveError::StepLimitExceeded`] if that takes more than `max_steps` steps, to
+    /// bound the time spent on boards from untrusted sources.
+    ///
+    /// Every value placed counts as a step, including guesses and the values placed
+    /// after a wrong guess that are undone while backtracking. Solving a puzzle with
+    /// only singles takes as many steps as empty cells. The board is left as it was when
+    /// the solver gave up.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SolveError;
+    ///
+    /// let mut board: Board = "123. .... .... ....".parse().unwrap();
+    /// assert_eq!(board.clone().solve_with_step_limit(5), Err(SolveError::StepLimitExceeded));
+    ///
+    /// assert_eq!(board.solve_with_step_limit(1000), Ok(()));
+    /// assert!(board.is_complete());
+    /// ```
+    pub fn solve_with_step_limit(&mut self, max_steps: usize) -> Result<(), SolveError> {
+        let mut solver = SudokuSolver::new(self);
+        solver.solve_with_step_limit(max_steps)
+    }
+
+    /// Returns the empty cells that have a single value left that can be placed in them,
+    /// with that value, ordered by cell.
+    ///
+    /// Use a [`BoardAnalyzer`] to look for both naked and hidden singles without computing
+    /// the candidates of each cell twice.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    ///
+    /// assert_eq!(board.get_naked_singles(), vec![(board.cell_at(0, 3), 4)]);
+    /// ```
+    pub fn get_naked_singles(&self) -> Vec<(CellLoc, u8)> {
+        BoardAnalyzer::new(self).naked_singles()
+    }
+
+    /// Returns the values that can only be placed in one empty cell of a line, column or
+    /// square, with that cell, ordered by cell.
+    ///
+    /// Use a [`BoardAnalyzer`] to look for both naked and hidden singles without computing
+    /// the candidates of each cell twice.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    ///
+    /// assert!(board.get_hidden_singles().contains(&(board.cell_at(0, 3), 4)));
+    /// ```
+    pub fn get_hidden_singles(&self) -> Vec<(CellLoc, u8)> {
+        BoardAnalyzer::new(self).hidden_singles()
+    }
+}
+
+#[cfg(feature = "async")]
+impl Board {
+    /// Same as [`Board::solve`] but solves a copy of the board on tokio's blocking thread
+    /// pool, so it doesn't block the async executor. The board is only changed if a
+    /// solution is found. Requires the `async` feature.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// board.solve_async().await.unwrap();
+    ///
+    /// assert!(board.is_complete());
+    /// # });
+    /// ```
+    pub async fn solve_async(&mut self) -> Result<(), UnsolvableError> {
+        let mut board = self.clone();
+        let (board, result) = tokio::task::spawn_blocking(move || {
+            let result = board.solve();
+            (board, result)
+        })
+        .await
+        .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()));
+
+        if result.is_ok() {
+            *self = board;
+        }
+        result
+    }
+}
+
+/// Finds the values the solver would place next in a board, without changing it.
+///
+/// The candidates of every cell are computed once when the analyzer is created and reused
+/// by every query, the board can't be changed while the analyzer is alive.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::BoardAnalyzer;
+///
+/// let board: Board = "123. .... .... ....".parse().unwrap();
+/// let analyzer = BoardAnalyzer::new(&board);
+///
+/// for (cell, value) in analyzer.naked_singles() {
+///     println!("{} can only be {}", cell, value);
+/// }
+/// for (cell, value) in analyzer.hidden_singles() {
+///     println!("{} is the only place left for {}", cell, value);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoardAnalyzer<'a> {
+    board: &'a Board,
+    candidate_cache: CandidateCache,
+}
+
+impl<'a> BoardAnalyzer<'a> {
+    /// Creates an analyzer for `board`, computing the candidates of each of its cells.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::BoardAnalyzer;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// let analyzer = BoardAnalyzer::new(&board);
+    /// ```
+    pub fn new(board: &'a Board) -> Self {
+        BoardAnalyzer {
+            board,
+            candidate_cache: CandidateCache::from_board(board),
+        }
+    }
+
+    /// Returns the board being analyzed.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::BoardAnalyzer;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// assert_eq!(BoardAnalyzer::new(&board).board(), &board);
+    /// ```
+    pub fn board(&self) -> &'a Board {
+        self.board
+    }
+
+    /// Same as [`Board::get_naked_singles`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::BoardAnalyzer;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let analyzer = BoardAnalyzer::new(&board);
+    ///
+    /// assert_eq!(analyzer.naked_singles(), vec![(board.cell_at(0, 3), 4)]);
+    /// ```
+    pub fn naked_singles(&self) -> Vec<(CellLoc, u8)> {
+        naked_singles(&self.candidate_cache).into_iter().collect()
+    }
+
+    /// Same as [`Board::get_hidden_singles`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::BoardAnalyzer;
+    ///
+    /// let board: Board = "123. .... .... ....".parse().unwrap();
+    /// let analyzer = BoardAnalyzer::new(&board);
+    ///
+    /// assert!(analyzer.hidden_singles().contains(&(board.cell_at(0, 3), 4)));
+    /// ```
+    pub fn hidden_singles(&self) -> Vec<(CellLoc, u8)> {
+        hidden_singles(&self.candidate_cache).into_keys().collect()
+    }
+}
+
+/// A board that keeps the candidates of its empty cells up to date as values are set,
+/// instead of computing them again for every query.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::CachedBoard;
+///
+/// let mut board = CachedBoard::new("1... .... .... ....".parse().unwrap());
+/// let cell = board.board().cell_at(0, 1);
+///
+/// board.set(&cell, 2).unwrap();
+/// assert_eq!(
+///     board.candidates_at(&board.board().cell_at(0, 2)),
+///     Some(vec![3, 4].into_iter().collect())
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachedBoard {
+    board: Board,
+    candidate_cache: CandidateCache,
+}
+
+impl CachedBoard {
+    /// Wraps `board`, computing the candidates of each of its empty cells.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::CachedBoard;
+    ///
+    /// let board = CachedBoard::new("1... .... .... ....".parse().unwrap());
+    /// ```
+    pub fn new(board: Board) -> Self {
+        CachedBoard {
+            candidate_cache: CandidateCache::from_board(&board),
+            board,
+        }
+    }
+
+    /// Returns the board with the values set so far.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::CachedBoard;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// assert_eq!(CachedBoard::new(board.clone()).board(), &board);
+    /// ```
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the wrapped board, dropping the candidates.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::CachedBoard;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// assert_eq!(CachedBoard::new(board.clone()).into_board(), board);
+    /// ```
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Same as [`Board::try_set_value`], updating the candidates of the cells that see `cell`.
+    ///
+    /// Placing a candidate in an empty cell only updates the cells it affects, any other
+    /// change computes the candidates of the whole board again.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::CachedBoard;
+    ///
+    /// let mut board = CachedBoard::new("1... .... .... ....".parse().unwrap());
+    /// let cell = board.board().cell_at(1, 1);
+    ///
+    /// assert_eq!(board.set(&cell, 2), Ok(None));
+    /// assert_eq!(board.set(&cell, 3), Ok(Some(2)));
+    /// assert!(board.set(&cell, 5).is_err());
+    /// ```
+    pub fn set(&mut self, cell: &CellLoc, value: u8) -> Result<Option<u8>, SetValueError> {
+        let previous = self.board.try_set_value(cell, value)?;
+
+        let is_candidate = self
+            .candidate_cache
+            .possible_values()
+            .get(cell)
+            .map_or(false, |candidates| candidates.contains(value));
+        if previous.is_some()
+            || !is_candidate
+            || self.candidate_cache.set_value(value, *cell).is_err()
+        {
+            self.candidate_cache.reset(&self.board);
+        }
+        self.candidate_cache.clear_undo_log();
+
+        Ok(previous)
+    }
+
+    /// Same as [`CellLoc::get_possible_values`] without computing the candidates again,
+    /// returns `None` if the cell is filled or belongs to a board of a different size.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::CachedBoard;
+    ///
+    /// let board = CachedBoard::new("1... .... .... ....".parse().unwrap());
+    /// let cell = board.board().cell_at(0, 1);
+    ///
+    /// assert_eq!(board.candidates_at(&cell), cell.get_possible_values(board.board()));
+    /// assert_eq!(board.candidates_at(&board.board().cell_at(0, 0)), None);
+    /// ```
+    pub fn candidates_at(&self, cell: &CellLoc) -> Option<BTreeSet<u8>> {
+        if self.board.try_get(cell).is_err() {
+            return None;
+        }
+
+        self.candidate_cache
+            .possible_values()
+            .get(cell)
+            .map(|candidates| candidates.iter().collect())
+    }
+
+    /// Checks that the candidates kept for each cell and block are the ones computed
+    /// again from scratch for the board, returning the first difference found. Meant for
+    /// debugging, the candidates of a `CachedBoard` are always up to date.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::CachedBoard;
+    ///
+    /// let mut board = CachedBoard::new("1... .... .... ....".parse().unwrap());
+    /// board.set(&board.board().cell_at(1, 1), 2).unwrap();
+    ///
+    /// assert_eq!(board.verify_consistency(), Ok(()));
+    /// ```
+    pub fn verify_consistency(&self) -> Result<(), ConsistencyError> {
+        self.candidate_cache.verify_consistency(&self.board)
+    }
+}
+
+/// Keeps track of whether a board being edited can still be solved, updating the
+/// candidates of its cells with each change instead of computing them again.
+///
+/// Values placed with [`place`](Self::place) are kept in a stack so removing one only
+/// undoes the values placed after it and places them again. Values that contradict the
+/// board are kept aside until the values they contradict are removed, and the board is
+/// unsolvable while there are any.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::LiveSolver;
+///
+/// let mut solver = LiveSolver::new("1... .... .... ....".parse().unwrap());
+/// assert_eq!(solver.solution_status(2), Ok(2));
+///
+/// let cell = solver.board().cell_at(0, 1);
+/// solver.place(&cell, 1).unwrap();
+/// assert!(solver.is_obviously_unsolvable());
+///
+/// solver.remove(&cell).unwrap();
+/// assert!(!solver.is_obviously_unsolvable());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LiveSolver {
+    board: Board,
+    candidate_cache: CandidateCache,
+    /// Values placed in the candidate cache, in the order they were placed.
+    placed: Vec<(CellLoc, u8, candidate_cache::UndoSetValue)>,
+    /// Values in the board that contradict the candidate cache, in the order they were
+    /// placed.
+    conflicts: Vec<(CellLoc, u8)>,
+}
+
+impl LiveSolver {
+    /// Creates a solver for `board`, computing the candidates of each of its empty cells.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::LiveSolver;
+    ///
+    /// let solver = LiveSolver::new("1... .... .... ....".parse().unwrap());
+    /// ```
+    pub fn new(board: Board) -> Self {
+        LiveSolver {
+            candidate_cache: CandidateCache::from_board(&board),
+            board,
+            placed: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Returns the board with the changes made so far.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::LiveSolver;
+    ///
+    /// let board: Board = "1... .... .... ....".parse().unwrap();
+    /// assert_eq!(LiveSolver::new(board.clone()).board(), &board);
+    /// ```
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Places `value` in `cell`, replacing the value already there, and returns the
+    /// previous value. Returns [`SetValueError`] without changing the board if the cell
+    /// or the value are not valid for the board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::LiveSolver;
+    ///
+    /// let mut solver = LiveSolver::new("1... .... .... ....".parse().unwrap());
+    /// let cell = solver.board().cell_at(1, 1);
+    ///
+    /// assert_eq!(solver.place(&cell, 2), Ok(None));
+    /// assert_eq!(solver.place(&cell, 3), Ok(Some(2)));
+    /// assert!(solver.place(&cell, 5).is_err());
+    /// ```
+    pub fn place(&mut self, cell: &CellLoc, value: u8) -> Result<Option<u8>, SetValueError> {
+        self.board
+            .value(value)
+            .map_err(SetValueError::ValueOutOfRange)?;
+        let previous = self.remove(cell).map_err(SetValueError::SizeMismatch)?;
+
+        self.board.set(cell, value);
+        self.apply(*cell, value);
+
+        Ok(previous)
+    }
+
+    /// Empties `cell` and returns the value it had. Removing one of the values the
+    /// board was created with computes the candidates of the whole board again.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::LiveSolver;
+    ///
+    /// let mut solver = LiveSolver::new("1... .... .... ....".parse().unwrap());
+    /// let cell = solver.board().cell_at(1, 1);
+    /// solver.place(&cell, 2).unwrap();
+    ///
+    /// assert_eq!(solver.remove(&cell), Ok(Some(2)));
+    /// assert_eq!(solver.remove(&cell), Ok(None));
+    /// ```
+    pub fn remove(&mut self, cell: &CellLoc) -> Result<Option<u8>, SizeMismatchError> {
+        let previous = match self.board.try_get(cell)? {
+            Some(previous) => previous,
+            None => return Ok(None),
+        };
+
+        if let Some(idx) = self.conflicts.iter().position(|(other, _)| other == cell) {
+            // conflicts were never placed in the cache
+            self.conflicts.remove(idx);
+            self.board.unset(cell);
+        } else if let Some(idx) = self.placed.iter().position(|(other, ..)| other == cell) {
+            let mut replaced = Vec::with_capacity(self.placed.len() - idx);
+            while self.placed.len() > idx {
+                let (other, value, undo) = self.placed.pop().expect("idx is in the stack");
+                self.candidate_cache.undo(undo);
+                replaced.push((other, value));
+            }
+
+            self.board.unset(cell);
+            for (other, value) in replaced.into_iter().rev().skip(1) {
+                self.apply(other, value);
+            }
+            self.retry_conflicts();
+        } else {
+            self.board.unset(cell);
+
+            let mut board = self.board.clone();
+            let changes: Vec<(CellLoc, u8)> = self
+                .placed
+                .drain(..)
+                .map(|(other, value, _)| (other, value))
+                .chain(self.conflicts.drain(..))
+                .collect();
+            for (other, _) in &changes {
+                board.unset(other);
+            }
+
+            self.candidate_cache.reset(&board);
+            for (other, value) in changes {
+                self.apply(other, value);
+            }
+        }
+
+        Ok(Some(previous))
+    }
+
+    /// Returns true if the board can't be solved without doing any search: a value
+    /// contradicts the values around it, an empty cell has no candidates left, or a
+    /// value has nowhere left to go in a line, column or square.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::LiveSolver;
+    ///
+    /// let solver = LiveSolver::new("123. ...4 .... ....".parse().unwrap());
+    /// assert!(solver.is_obviously_unsolvable());
+    /// ```
+    pub fn is_obviously_unsolvable(&self) -> bool {
+        !self.conflicts.is_empty()
+            || self
+                .candidate_cache
+                .possible_values()
+                .values()
+                .any(|values| values.is_empty())
+            || self
+                .candidate_cache
+                .has_block_without_candidates(&self.board)
+    }
+
+    /// Counts the solutions of the board up to `limit`, reusing the candidates kept up
+    /// to date with the board. Returns [`UnsolvableError`] if there are none, at least
+    /// one solution is always looked for.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::{LiveSolver, UnsolvableError};
+    ///
+    /// let mut solver = LiveSolver::new("1... .... .... ....".parse().unwrap());
+    /// assert_eq!(solver.solution_status(2), Ok(2));
+    ///
+    /// solver.place(&solver.board().cell_at(0, 1), 1).unwrap();
+    /// assert_eq!(solver.solution_status(2), Err(UnsolvableError));
+    /// ```
+    pub fn solution_status(&self, limit: usize) -> Result<usize, UnsolvableError> {
+        if self.is_obviously_unsolvable() {
+            return Err(UnsolvableError);
+        }
+
+        let mut board = self.board.clone();
+        SudokuSolver::from_cache(&mut board, self.candidate_cache.clone()).count_solutions(limit)
+    }
+
+    /// Places a value already set in the board in the candidate cache, or keeps it aside
+    /// if it contradicts the cache.
+    fn apply(&mut self, cell: CellLoc, value: u8) {
+        match self.candidate_cache.set_value(value, cell) {
+            Ok(undo) => self.placed.push((cell, value, undo)),
+            Err(_) => self.conflicts.push((cell, value)),
+        }
+    }
+
+    /// Tries to place the values kept aside again, after a value they may have
+    /// contradicted was removed.
+    fn retry_conflicts(&mut self) {
+        for (cell, value) in std::mem::take(&mut self.conflicts) {
+            self.apply(cell, value);
+        }
+    }
+}
+
+/// A sudoku solver that can be reused to solve many boards.
+///
+/// A [`SudokuSolver`] allocates its internal structures every time it's created, when
+/// solving a lot of boards of the same size a `Solver` reuses them between calls instead.
+///
+/// ```
+/// use sudokugen::{Board, BoardSize};
+/// use sudokugen::solver::Solver;
+///
+/// let mut solver = Solver::with_capacity(BoardSize::NineByNine);
+///
+/// for board in &[
+///     "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6..",
+///     ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21",
+/// ] {
+///     let mut board: Board = board.parse().unwrap();
+///     solver.solve(&mut board).unwrap();
+///     println!("{}", board);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Solver {
+    candidate_cache: CandidateCache,
+    move_log: Vec<MoveLog>,
+}
+
+impl Solver {
+    /// Creates a new solver, memory is allocated when solving the first board.
+    ///
+    /// ```
+    /// use sudokugen::solver::Solver;
+    ///
+    /// let solver = Solver::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new solver with memory already allocated to solve boards of `board_size`.
+    ///
+    /// ```
+    /// use sudokugen::BoardSize;
+    /// use sudokugen::solver::Solver;
+    ///
+    /// let solver = Solver::with_capacity(BoardSize::NineByNine);
+    /// ```
+    pub fn with_capacity(board_size: BoardSize) -> Self {
+        Solver {
+            candidate_cache: CandidateCache::from_board(&Board::new(board_size)),
+            move_log: Vec::with_capacity(board_size.cell_count()),
+        }
+    }
+
+    /// Solves the sudoku puzzle, same as [`Board::solve`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::Solver;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// Solver::new().solve(&mut board).unwrap();
+    ///
+    /// assert!(board.iter_cells().all(|cell| board.get(&cell).is_some()));
+    /// ```
+    pub fn solve(&mut self, board: &mut Board) -> Result<(), UnsolvableError> {
+        let mut solver = SudokuSolver::from_parts(
+            board,
+            std::mem::take(&mut self.candidate_cache),
+            std::mem::take(&mut self.move_log),
+            NoopObserver,
+        );
+        let result = solver.solve();
+
+        self.candidate_cache = solver.candidate_cache;
+        self.move_log = solver.move_log;
+
+        result
+    }
+}
+
+/// Error returned by [`solve_lines`] for each line that could not be solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveLineError {
+    /// The line at this 1 based line number is not a valid board.
+    Malformed {
+        /// The 1 based number of the line in the input
+        line: usize,
+        /// The error returned while parsing the board
+        error: MalformedBoardError,
+    },
+    /// The board at this 1 based line number has no solution.
+    Unsolvable {
+        /// The 1 based number of the line in the input
+        line: usize,
+    },
+}
+
+impl fmt::Display for SolveLineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed { line, error } => write!(f, "Line {}: {}", line, error),
+            Self::Unsolvable { line } => write!(f, "Line {}: {}", line, UnsolvableError),
+        }
+    }
+}
+
+impl error::Error for SolveLineError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Malformed { error, .. } => Some(error),
+            Self::Unsolvable { .. } => Some(&UnsolvableError),
+        }
+    }
+}
+
+/// Solves many boards in parallel, returning the solutions in the same order as the boards.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::solve_all;
+///
+/// let boards: Vec<Board> = vec![
+///     "1... .... .... ....".parse().unwrap(),
+///     "123. ...4 .... ....".parse().unwrap(),
+/// ];
+///
+/// let solutions = solve_all(boards);
+///
+/// assert!(solutions[0].is_ok());
+/// assert!(solutions[1].is_err());
+/// ```
+pub fn solve_all<I: IntoParallelIterator<Item = Board>>(
+    boards: I,
+) -> Vec<Result<Board, UnsolvableError>> {
+    boards
+        .into_par_iter()
+        .map_init(Solver::new, |solver, mut board| {
+            solver.solve(&mut board).map(|_| board)
+        })
+        .collect()
+}
+
+/// Parses each non empty line of `input` as a board and solves them in parallel,
+/// returning the results in the same order as the lines.
+///
+/// ```
+/// use sudokugen::solver::{solve_lines, SolveLineError};
+///
+/// let results = solve_lines("
+/// 1... .... .... ....
+/// not a board
+/// 123. ...4 .... ....
+/// ");
+///
+/// assert!(results[0].is_ok());
+/// assert!(matches!(results[1], Err(SolveLineError::Malformed { line: 3, .. })));
+/// assert_eq!(results[2], Err(SolveLineError::Unsolvable { line: 4 }));
+/// ```
+pub fn solve_lines(input: &str) -> Vec<Result<Board, SolveLineError>> {
+    let lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| (idx + 1, line))
+        .collect();
+
+    lines
+        .into_par_iter()
+        .map_init(Solver::new, |solver, (line, board)| {
+            let mut board: Board = board
+                .parse()
+                .map_err(|error| SolveLineError::Malformed { line, error })?;
+
+            solver
+                .solve(&mut board)
+                .map_err(|_| SolveLineError::Unsolvable { line })?;
+
+            Ok(board)
+        })
+        .collect()
+}
+
+/// Counts, for each given of `board`, the solutions of the board with only that given
+/// removed, stopping at `max_count` solutions. The givens are checked in parallel and
+/// returned in the order of their cells.
+///
+/// A puzzle with a unique solution is minimal when every count is at least 2, givens
+/// with a count of 1 can be removed without making the solution ambiguous. Counts are 0
+/// when the board has no solution even without the given, at least one solution is
+/// always looked for.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::solution_count_profile;
+///
+/// let board: Board = "12.. ..2. .3.. ...4".parse().unwrap();
+/// let profile = solution_count_profile(&board, 10);
+///
+/// // the 2 in the first line can be removed, the solution is still unique
+/// let counts: Vec<usize> = profile.iter().map(|(_, count)| *count).collect();
+/// assert_eq!(counts, vec![2, 1, 2, 3, 3]);
+/// assert_eq!(profile[1].0, board.cell_at(0, 1));
+/// ```
+pub fn solution_count_profile(board: &Board, max_count: usize) -> Vec<(CellLoc, usize)> {
+    let givens: Vec<CellLoc> = board
+        .iter_cells()
+        .filter(|cell| board.get(cell).is_some())
+        .collect();
+
+    givens
+        .into_par_iter()
+        .map(|cell| {
+            let mut without = board.clone();
+            without.unset(&cell);
+            let count = SudokuSolver::new(&mut without)
+                .count_solutions(max_count)
+                .unwrap_or(0);
+            (cell, count)
+        })
+        .collect()
+}
+
+/// The outcome of checking a proposed solution against its puzzle, see [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyResult {
+    /// The attempt is complete, follows the rules and keeps every given of the puzzle.
+    Correct,
+    /// The puzzle and the attempt are boards of different sizes.
+    SizeMismatch {
+        /// The size of the puzzle
+        puzzle: BoardSize,
+        /// The size of the attempt
+        attempt: BoardSize,
+    },
+    /// The cells where the attempt has a different value than the one given in the puzzle.
+    MismatchWithGivens(Vec<CellLoc>),
+    /// The pairs of cells with the same value in a line, column or square, each pair
+    /// listed once with the smaller cell first.
+    RuleViolation(Vec<(CellLoc, CellLoc)>),
+    /// The cells left empty in the attempt.
+    Incomplete(Vec<CellLoc>),
+}
+
+impl VerifyResult {
+    /// Returns true if the attempt is a correct solution of the puzzle.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::verify;
+    ///
+    /// let puzzle: Board = "1... ..1. .... ....".parse().unwrap();
+    /// let attempt: Board = "1324 4213 3142 2431".parse().unwrap();
+    ///
+    /// assert!(verify(&puzzle, &attempt).is_correct());
+    /// ```
+    pub fn is_correct(&self) -> bool {
+        *self == VerifyResult::Correct
+    }
+}
+
+/// Checks whether `attempt` is a correct solution of `puzzle` without solving the
+/// puzzle, only scanning the cells of both boards once, so it's a cheap way to check
+/// solutions entered by a user.
+///
+/// When the attempt has several problems only the first one in the order of
+/// [`VerifyResult`] is returned, a board with a value that contradicts a given is
+/// reported as [`VerifyResult::MismatchWithGivens`] even if it also breaks the rules or
+/// has empty cells.
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::{verify, VerifyResult};
+///
+/// let puzzle: Board = "1... ..1. .... ....".parse().unwrap();
+///
+/// let attempt: Board = "1324 4213 3142 2431".parse().unwrap();
+/// assert_eq!(verify(&puzzle, &attempt), VerifyResult::Correct);
+///
+/// let attempt: Board = "1324 4213 3142 243.".parse().unwrap();
+/// assert_eq!(
+///     verify(&puzzle, &attempt),
+///     VerifyResult::Incomplete(vec![attempt.cell_at(3, 3)])
+/// );
+///
+/// let attempt: Board = "1324 4213 3142 2434".parse().unwrap();
+/// assert!(matches!(verify(&puzzle, &attempt), VerifyResult::RuleViolation(_)));
+/// ```
+pub fn verify(puzzle: &Board, attempt: &Board) -> VerifyResult {
+    let board_size = puzzle.board_size();
+    if attempt.board_size() != board_size {
+        return VerifyResult::SizeMismatch {
+            puzzle: board_size,
+            attempt: attempt.board_size(),
+        };
+    }
+
+    let mismatches: Vec<CellLoc> = puzzle
+        .iter_cells()
+        .filter(|cell| match (puzzle.get(cell), attempt.get(cell)) {
+            (Some(given), Some(value)) => given != value,
+            _ => false,
+        })
+        .collect();
+    if !mismatches.is_empty() {
+        return VerifyResult::MismatchWithGivens(mismatches);
+    }
+
+    // values seen so far in each line, column, square and extra region, in that order
+    let width = board_size.width();
+    let variant = puzzle.variant();
+    let mut seen = vec![0_u32; 3 * width + variant.extra_block_count()];
+    let mut repeated = false;
+    for cell in attempt.iter_cells() {
+        if let Some(value) = attempt.get(&cell) {
+            for block in cell.iter_blocks(variant) {
+                let mask = &mut seen[block.index(width)];
+                repeated |= *mask & 1 << value != 0;
+                *mask |= 1 << value;
+            }
+        }
+    }
+    if repeated {
+        return VerifyResult::RuleViolation(conflicting_pairs(attempt, variant));
+    }
+
+    let empty: Vec<CellLoc> = attempt
+        .iter_cells()
+        .filter(|cell| attempt.get(cell).is_none())
+        .collect();
+    if !empty.is_empty() {
+        return VerifyResult::Incomplete(empty);
+    }
+
+    VerifyResult::Correct
+}
+
+/// Returns every pair of cells with the same value in a line, column, square or extra
+/// region of `variant`.
+fn conflicting_pairs(board: &Board, variant: Variant) -> Vec<(CellLoc, CellLoc)> {
+    // the cells holding each value in each line, column, square and extra region, in
+    // that order
+    let width = board.board_size().width();
+    let mut blocks: Vec<Vec<CellLoc>> =
+        vec![Vec::new(); (3 * width + variant.extra_block_count()) * width];
+    for cell in board.iter_cells() {
+        if let Some(value) = board.get(&cell) {
+            let value = value as usize - 1;
+            for block in cell.iter_blocks(variant) {
+                blocks[block.index(width) * width + value].push(cell);
+            }
+        }
+    }
+
+    let mut conflicts = BTreeSet::new();
+    for cells in blocks.iter().filter(|cells| cells.len() > 1) {
+        for (i, first) in cells.iter().enumerate() {
+            for second in &cells[i + 1..] {
+                conflicts.insert((*first, *second));
+            }
+        }
+    }
+    conflicts.into_iter().collect()
+}
+
+impl<'a> SudokuSolver<'a> {
+    /// Creates a solver for `board`, the board is only changed by calling
+    /// [`step`](Self::step) or [`solve`](Self::solve).
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// let solver = SudokuSolver::new(&mut board);
+    ///
+    /// assert_eq!(solver.remaining_cells(), 15);
+    /// ```
+    pub fn new(board: &'a mut Board) -> Self {
+        Self::with_observer(board, NoopObserver)
+    }
+
+    /// Creates a solver using a candidate cache that is already up to date with `board`.
+    fn from_cache(board: &'a mut Board, candidate_cache: CandidateCache) -> Self {
+        SudokuSolver {
+            empty_cells: candidate_cache.possible_values().len(),
+            board,
+            move_log: Vec::new(),
+            eliminations: Vec::new(),
+            candidate_cache,
+            options: SolveOptions::default(),
+            config: StrategyConfig::default(),
+            rng: None,
+            observer: NoopObserver,
+            trace: None,
+            steps: 0,
+            invalid_value: None,
+            allowed_candidates: HashMap::new(),
+        }
+    }
+
+    /// Creates a solver with the given options. When `options.deterministic` is false
+    /// guesses are picked with a generator seeded from [`rand::thread_rng`].
+    fn with_options(board: &'a mut Board, options: SolveOptions) -> Self {
+        let mut solver = Self::new(board);
+        solver.options = options;
+        solver
+    }
+}
+
+impl<'a, O: SolveObserver> SudokuSolver<'a, O> {
+    /// Creates a solver for `board` same as [`SudokuSolver::new`], reporting every value
+    /// placed and removed to `observer`, see [`Board::solve_with_observer`].
+    ///
+    /// ```
+    /// use sudokugen::board::{Board, CellLoc};
+    /// use sudokugen::solver::{SolveObserver, Strategy, SudokuSolver};
+    ///
+    /// struct PrintMoves;
+    ///
+    /// impl SolveObserver for PrintMoves {
+    ///     fn on_place(&mut self, cell: CellLoc, value: u8, strategy: Strategy) {
+    ///         println!("{} at {} ({:?})", value, cell, strategy);
+    ///     }
+    /// }
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// SudokuSolver::with_observer(&mut board, PrintMoves).solve().unwrap();
+    /// ```
+    pub fn with_observer(board: &'a mut Board, observer: O) -> Self {
+        Self::from_parts(board, CandidateCache::default(), Vec::new(), observer)
+    }
+
+    /// Creates a solver reusing the memory of a candidate cache and move log
+    /// from a previous solver.
+    fn from_parts(
+        board: &'a mut Board,
+        mut candidate_cache: CandidateCache,
+        mut move_log: Vec<MoveLog>,
+        observer: O,
+    ) -> Self {
+        let invalid_value = candidate_cache.try_reset(board).err();
+        move_log.clear();
+
+        SudokuSolver {
+            empty_cells: candidate_cache.possible_values().len(),
+            board,
+            move_log,
+            eliminations: Vec::new(),
+            candidate_cache,
+            options: SolveOptions::default(),
+            config: StrategyConfig::default(),
+            rng: None,
+            observer,
+            trace: None,
+            steps: 0,
+            invalid_value,
+            allowed_candidates: HashMap::new(),
+        }
+    }
+
+    /// Solves the rest of the board, same as [`Board::solve`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// let mut solver = SudokuSolver::new(&mut board);
+    /// solver.solve().unwrap();
+    ///
+    /// assert!(solver.is_solved());
+    /// ```
+    pub fn solve(&mut self) -> Result<(), UnsolvableError> {
+        if self.has_cell_without_candidates() {
+            return Err(UnsolvableError);
+        }
+
+        self.solve_rest()
+    }
+
+    /// Places the values found by one round of the solver's strategies, or guesses a
+    /// value if none could be found, backtracking if the board turns out to be unsolvable.
+    /// Does nothing if the board is already solved.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "123. .... .... ....".parse().unwrap();
+    /// let mut solver = SudokuSolver::new(&mut board);
+    /// solver.step().unwrap();
+    ///
+    /// assert!(solver.remaining_cells() < 13);
+    ///
+    /// let mut unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
+    /// assert!(SudokuSolver::new(&mut unsolvable).step().is_err());
+    /// ```
+    pub fn step(&mut self) -> Result<(), UnsolvableError> {
+        if self.is_solved() {
+            return Ok(());
+        }
+
+        #[cfg(debug_assertions)]
+        self.assert_possible_values();
+
+        if self.has_cell_without_candidates() {
+            return Err(UnsolvableError);
+        }
+
+        self.solve_iteration()
+    }
+
+    /// Checks that the candidates the solver keeps for each cell and block match the board
+    /// being solved, returning the first difference found. Meant for debugging, a solver
+    /// used only through its public methods is always consistent. Cells may have fewer
+    /// candidates than the board alone allows, once eliminations or backtracking ruled
+    /// some of them out.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "123. .... .... ....".parse().unwrap();
+    /// let mut solver = SudokuSolver::new(&mut board);
+    ///
+    /// while solver.remaining_cells() > 0 {
+    ///     solver.step().unwrap();
+    ///     assert_eq!(solver.verify_consistency(), Ok(()));
+    /// }
+    /// ```
+    pub fn verify_consistency(&self) -> Result<(), ConsistencyError> {
+        self.candidate_cache.verify_candidates(self.board)
+    }
+
+    /// Returns the number of empty cells left in the board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    /// assert_eq!(SudokuSolver::new(&mut board).remaining_cells(), 12);
+    /// ```
+    pub fn remaining_cells(&self) -> usize {
+        self.candidate_cache.possible_values().len()
+    }
+
+    /// Returns the fraction of the cells that were empty when the solver was created that
+    /// are filled now, from 0.0 to 1.0.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "1... .2.. ..3. ...4".parse().unwrap();
+    /// let mut solver = SudokuSolver::new(&mut board);
+    /// assert_eq!(solver.progress(), 0.0);
+    ///
+    /// solver.solve().unwrap();
+    /// assert_eq!(solver.progress(), 1.0);
+    /// ```
+    pub fn progress(&self) -> f64 {
+        if self.empty_cells == 0 {
+            return 1.0;
+        }
+
+        let filled = self.empty_cells.saturating_sub(self.remaining_cells());
+        filled as f64 / self.empty_cells as f64
+    }
+
+    /// Returns true when there are no empty cells left in the board.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::SudokuSolver;
+    ///
+    /// let mut board: Board = "1234 3412 2143 4321".parse().unwrap();
+    /// assert!(SudokuSolver::new(&mut board).is_solved());
+    /// ```
+    pub fn is_solved(&self) -> bool {
+        self.remaining_cells() == 0
     }
 
-    fn solve_iteration(&mut self) -> Result<(), UnsolvableError> {
-        let naked_singles = self.naked_singles();
-
-        if !naked_singles.is_empty() {
-            for (cell, value) in naked_singles {
-                if let Ok(ref mut moves) = self.register_move(Strategy::NakedSingle, &cell, value) {
-                    self.move_log.append(moves);
-                } else {
-                    return self.backtrack().and(Ok(()));
+    /// Rules out the candidates of each cell in `candidates` that aren't in its set.
+    /// These eliminations are part of the starting state of the solver, so they are
+    /// never undone, not even when backtracking.
+    fn restrict_candidates(
+        &mut self,
+        candidates: &HashMap<CellLoc, BTreeSet<u8>>,
+    ) -> Result<(), UnsolvableError> {
+        let max_value = self.board.board_size().max_value();
+        let mut eliminations = BTreeSet::new();
+        for (cell, values) in self.candidate_cache.possible_values().iter() {
+            if let Some(allowed) = candidates.get(cell) {
+                for value in values.iter().filter(|value| !allowed.contains(value)) {
+                    eliminations.insert((*cell, value));
                 }
+
+                // kept to restore the candidates of the cell when backtracking
+                let allowed = allowed
+                    .iter()
+                    .copied()
+                    .filter(|value| (1..=max_value).contains(value))
+                    .collect();
+                self.allowed_candidates.insert(*cell, allowed);
             }
-            return Ok(());
         }
 
-        // Hidden Singles
-        let hidden_singles = self.hidden_singles();
+        self.candidate_cache
+            .eliminate(&eliminations)
+            .map_err(|_| UnsolvableError)?;
+        self.candidate_cache.clear_undo_log();
+        Ok(())
+    }
+
+    /// Leaves the empty cells outside of `region` out of the solve, as if they were
+    /// givens, see [`Board::solve_region`]. The blocks crossing the region don't need to
+    /// hold every value, so hidden singles are turned off.
+    fn restrict_to_region(&mut self, region: &BTreeSet<CellLoc>) {
+        self.candidate_cache.fix_cells_outside(region);
+        self.empty_cells = self.remaining_cells();
+        self.config.hidden_singles = false;
+    }
 
-        if !hidden_singles.is_empty() {
-            for (cell, value) in hidden_singles {
-                if let Ok(ref mut moves) = self.register_move(Strategy::HiddenSingle, &cell, value)
-                {
-                    self.move_log.append(moves);
-                } else {
-                    return self.backtrack().and(Ok(()));
+    /// Returns true if the board has a value out of range or an empty cell where no
+    /// value can be placed, in both cases it has no solution.
+    fn has_cell_without_candidates(&self) -> bool {
+        self.invalid_value.is_some()
+            || self
+                .candidate_cache
+                .possible_values()
+                .values()
+                .any(|values| values.is_empty())
+    }
+
+    fn naked_singles(&self) -> BTreeSet<(CellLoc, u8)> {
+        naked_singles(&self.candidate_cache)
+    }
+
+    fn hidden_singles(&self) -> BTreeMap<(CellLoc, u8), Block> {
+        hidden_singles(&self.candidate_cache)
+    }
+
+    /// Removes the candidates found by the pair strategies enabled in the config, returns
+    /// `None` if there was nothing to remove.
+    fn eliminate_candidates(&mut self) -> Option<Result<(), UnsolvableError>> {
+        let mut eliminations = BTreeSet::new();
+
+        if self.config.naked_pairs {
+            eliminations.extend(self.naked_pairs());
+        }
+
+        if eliminations.is_empty() && self.config.pointing_pairs {
+            eliminations.extend(self.pointing_pairs());
+        }
+
+        if eliminations.is_empty() {
+            return None;
+        }
+
+        match self.candidate_cache.eliminate(&eliminations) {
+            Ok(undo) => {
+                self.eliminations.push((self.move_log.len(), undo));
+                Some(Ok(()))
+            }
+            Err(_) => Some(Err(UnsolvableError)),
+        }
+    }
+
+    /// Finds two cells in the same block with the same two candidates and returns
+    /// those candidates in the other cells of the block.
+    fn naked_pairs(&self) -> BTreeSet<(CellLoc, u8)> {
+        let possible_values = self.candidate_cache.possible_values();
+        let mut eliminations = BTreeSet::new();
+
+        for (cell, values) in possible_values.iter() {
+            if values.len() != 2 {
+                continue;
+            }
+
+            let blocks: [Vec<CellLoc>; 3] = [
+                cell.iter_line().collect(),
+                cell.iter_col().collect(),
+                cell.iter_square().collect(),
+            ];
+
+            for block in &blocks {
+                // each pair is found from both of its cells, start from the first one
+                let is_pair = |other: &CellLoc| possible_values.get(other) == Some(values);
+                if !block.iter().any(|other| other > cell && is_pair(other)) {
+                    continue;
+                }
+
+                for other in block.iter().filter(|other| !is_pair(other)) {
+                    if let Some(other_values) = possible_values.get(other) {
+                        for value in values.iter().filter(|value| other_values.contains(*value)) {
+                            eliminations.insert((*other, value));
+                        }
+                    }
                 }
             }
-            return Ok(());
+        }
+
+        eliminations
+    }
+
+    /// Finds the values whose candidate cells in a square are all in the same line or
+    /// column and returns that value in the cells of the line or column outside the square.
+    fn pointing_pairs(&self) -> BTreeSet<(CellLoc, u8)> {
+        let possible_values = self.candidate_cache.possible_values();
+        let mut eliminations = BTreeSet::new();
+
+        for candidate in self.candidate_cache.iter_candidates() {
+            if !matches!(candidate.block, Block::Square(_)) || candidate.len() < 2 {
+                continue;
+            }
+
+            let mut cells = candidate.cells();
+            let first = cells.next().expect("the square has candidates");
+            let (same_line, same_col) = cells.fold((true, true), |(line, col), cell| {
+                (
+                    line && cell.line() == first.line(),
+                    col && cell.col() == first.col(),
+                )
+            });
+
+            let outside_square: Vec<CellLoc> = match (same_line, same_col) {
+                (true, _) => first.iter_line().collect(),
+                (_, true) => first.iter_col().collect(),
+                _ => continue,
+            };
+
+            for other in outside_square {
+                if other.square() == first.square() {
+                    continue;
+                }
+
+                if let Some(other_values) = possible_values.get(&other) {
+                    if other_values.contains(candidate.value) {
+                        eliminations.insert((other, candidate.value));
+                    }
+                }
+            }
+        }
+
+        eliminations
+    }
+
+    /// Picks the cell and value to guess. Cells are visited in index order so ties
+    /// are broken by the lowest index, and the smallest value is picked unless the
+    /// solver is not deterministic.
+    fn guess(&mut self) -> (CellLoc, u8) {
+        let mut best = None;
+        for (cell, possibilities) in self.candidate_cache.possible_values().iter() {
+            if best.map_or(true, |(_, best_len)| possibilities.len() < best_len) {
+                best = Some(((cell, possibilities), possibilities.len()));
+                // naked singles are always handled before guessing, so no cell can have
+                // fewer than two options and the search can stop at the first cell with two
+                if !self.options.prefer_fewest_candidates || possibilities.len() <= 2 {
+                    break;
+                }
+            }
+        }
+
+        let rng = &mut self.rng;
+        best.map(|((cell, possibilities), _)| {
+            let value = if self.options.deterministic {
+                possibilities.iter().next()
+            } else {
+                // solvers that weren't given a generator seed one the first time they guess
+                let rng = rng.get_or_insert_with(|| {
+                    ChaCha8Rng::from_rng(rand::thread_rng()).expect("thread_rng never fails")
+                });
+                possibilities.iter().choose(rng)
+            }
+            .expect("Empty possibilities should have been caught while registering a move");
+
+            (*cell, value)
+        })
+        .expect("If the table is full then the method should have finished")
+    }
+
+    /// Counts the solutions of the board up to `limit`, returns [`UnsolvableError`] if
+    /// there are none. Once a solution is found the solver backtracks from it to look
+    /// for the next one, so the board is left in any state.
+    fn count_solutions(&mut self, limit: usize) -> Result<usize, UnsolvableError> {
+        self.solve()?;
+
+        let mut solutions = 1;
+        while solutions < limit && self.backtrack().is_ok() {
+            if self.solve_rest().is_err() {
+                break;
+            }
+            solutions += 1;
+        }
+
+        Ok(solutions)
+    }
+
+    /// Same as [`solve`](Self::solve), without checking for cells without candidates first.
+    fn solve_rest(&mut self) -> Result<(), UnsolvableError> {
+        while !self.is_solved() {
+            self.solve_iteration()?;
+        }
+        Ok(())
+    }
+
+    /// Same as [`solve`](Self::solve) when guessing is allowed, otherwise stops as soon
+    /// as the solver would need to guess.
+    fn solve_with_config(&mut self) -> Result<(), SolveError> {
+        if let Some(err) = &self.invalid_value {
+            return Err(SolveError::InvalidValue(err.clone()));
+        }
+
+        if self.config.allow_guessing {
+            return Ok(self.solve()?);
+        }
+
+        if self.has_cell_without_candidates() {
+            return Err(SolveError::Unsolvable);
+        }
+
+        while !self.is_solved() {
+            match self.logic_iteration() {
+                Some(Ok(())) => continue,
+                // nothing was guessed so there is nothing to backtrack
+                Some(Err(_)) => return Err(SolveError::Unsolvable),
+                None => {
+                    return Err(SolveError::RequiresGuessing {
+                        board_state: self.board.clone(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [`solve`](Self::solve), but stops once more than `max_steps` values
+    /// were placed. The limit is only checked between iterations, so the solver may
+    /// place a few values past it before giving up.
+    fn solve_with_step_limit(&mut self, max_steps: usize) -> Result<(), SolveError> {
+        if let Some(err) = &self.invalid_value {
+            return Err(SolveError::InvalidValue(err.clone()));
+        }
+
+        if self.has_cell_without_candidates() {
+            return Err(SolveError::Unsolvable);
+        }
+
+        while !self.is_solved() {
+            if self.steps > max_steps {
+                return Err(SolveError::StepLimitExceeded);
+            }
+            self.solve_iteration()?;
+        }
+
+        if self.steps > max_steps {
+            return Err(SolveError::StepLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Panics, printing the cells that differ, if the candidates of each cell don't
+    /// match the candidate cells of their blocks.
+    #[cfg(debug_assertions)]
+    fn assert_possible_values(&self) {
+        let possible_values: HashMap<CellLoc, BTreeSet<u8>> = self
+            .candidate_cache
+            .possible_values()
+            .iter()
+            .filter(|(_, values)| !values.is_empty())
+            .map(|(cell, values)| (*cell, values.iter().collect()))
+            .collect();
+        let gen_possible_values = self.candidate_cache.possible_values_from_candidates();
+
+        if possible_values != gen_possible_values {
+            for cell in possible_values.keys().chain(gen_possible_values.keys()) {
+                if possible_values.get(cell) != gen_possible_values.get(cell) {
+                    println!("cells {} -> {:?}", cell, possible_values.get(cell));
+                    println!("blocks {} -> {:?}", cell, gen_possible_values.get(cell));
+                }
+            }
+            panic!("The candidates of the cells and blocks don't match");
+        }
+    }
+
+    fn solve_iteration(&mut self) -> Result<(), UnsolvableError> {
+        match self.logic_iteration() {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(_)) => return self.backtrack().and(Ok(())),
+            None => (),
         }
 
         // Guesses
         let (cell, value) = self.guess();
 
-        if let Ok(ref mut moves) = self.register_move(Strategy::Guess, &cell, value) {
-            self.move_log.append(moves);
+        if self.register_move(Strategy::Guess, &cell, value).is_ok() {
             Ok(())
         } else {
             self.backtrack().and(Ok(()))
         }
     }
 
+    /// Applies one round of the strategies that don't require guessing, returns `None`
+    /// if none of them could be applied.
+    fn logic_iteration(&mut self) -> Option<Result<(), UnsolvableError>> {
+        let deductions = match self.deductions() {
+            Ok(deductions) => deductions,
+            Err(err) => return Some(Err(err)),
+        };
+
+        if deductions.is_empty() {
+            return self.eliminate_candidates();
+        }
+
+        for (cell, (value, strategy, block)) in deductions {
+            // deductions are unique per cell, but don't rely on it to avoid setting a
+            // cell twice in the same pass
+            if self.board.get(&cell).is_some() {
+                continue;
+            }
+
+            if let Err(err) = self.register_move_in_block(strategy, &cell, value, block) {
+                return Some(Err(err));
+            }
+        }
+
+        Some(Ok(()))
+    }
+
+    /// Merges the naked and hidden singles found in the board into a single deduction per
+    /// cell, preferring naked singles when a cell is both. Returns [`UnsolvableError`] if
+    /// the same cell must hold two different values.
+    fn deductions(&self) -> Result<BTreeMap<CellLoc, Deduction>, UnsolvableError> {
+        let mut deductions = BTreeMap::new();
+
+        if self.config.naked_singles {
+            for (cell, value) in self.naked_singles() {
+                deductions.insert(cell, (value, Strategy::NakedSingle, None));
+            }
+        }
+
+        if !self.config.hidden_singles {
+            return Ok(deductions);
+        }
+
+        for ((cell, value), block) in self.hidden_singles() {
+            match deductions.entry(cell) {
+                Entry::Vacant(entry) => {
+                    entry.insert((value, Strategy::HiddenSingle, Some(block)));
+                }
+                Entry::Occupied(entry) => {
+                    if entry.get().0 != value {
+                        return Err(UnsolvableError);
+                    }
+                }
+            }
+        }
+
+        Ok(deductions)
+    }
+
     fn register_move(
         &mut self,
         strategy: Strategy,
         cell: &CellLoc,
         value: u8,
-    ) -> Result<Vec<MoveLog>, UnsolvableError> {
+    ) -> Result<(), UnsolvableError> {
+        self.register_move_in_block(strategy, cell, value, None)
+    }
+
+    fn register_move_in_block(
+        &mut self,
+        strategy: Strategy,
+        cell: &CellLoc,
+        value: u8,
+        block: Option<Block>,
+    ) -> Result<(), UnsolvableError> {
         let undo_candidates = self
             .candidate_cache
             .set_value(value, *cell)
             .or(Err(UnsolvableError))?;
 
         self.board.set(cell, value);
+        self.steps += 1;
+        self.observer.on_place(*cell, value, strategy);
+        debug_assert_eq!(
+            self.candidate_cache
+                .verify_placement(self.board, *cell, value),
+            Ok(())
+        );
 
-        let log = vec![MoveLog::SetValue {
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceStep::Place(SolveStep {
+                cell: *cell,
+                value,
+                strategy,
+                block,
+            }));
+        }
+
+        self.move_log.push(MoveLog::SetValue {
             strategy,
             cell: *cell,
             value,
+            block,
             undo_candidates,
-        }];
+        });
 
-        Ok(log)
+        Ok(())
     }
 
     fn undo_move(&mut self, mov: MoveLog) {
         match mov {
             MoveLog::SetValue {
+                strategy,
                 cell,
+                value,
                 undo_candidates,
                 ..
             } => {
                 self.board.unset(&cell);
                 self.candidate_cache.undo(undo_candidates);
+                self.observer.on_undo(cell, value);
+
+                if let Some(trace) = &mut self.trace {
+                    let reason = match strategy {
+                        Strategy::Guess => BacktrackReason::WrongGuess,
+                        _ => BacktrackReason::FollowedWrongGuess,
+                    };
+                    trace.push(TraceStep::Backtrack(BacktrackStep {
+                        cell,
+                        tried: value,
+                        reason,
+                    }));
+                }
             }
         }
     }
 
     fn backtrack(&mut self) -> Result<CellLoc, UnsolvableError> {
+        self.observer.on_backtrack_start();
+
         while let Some(mov) = self.move_log.pop() {
+            // candidates removed after this move must be restored before undoing it
+            while let Some((move_count, _)) = self.eliminations.last() {
+                if *move_count <= self.move_log.len() {
+                    break;
+                }
+                let (_, undo) = self.eliminations.pop().unwrap();
+                self.candidate_cache.undo_eliminate(undo);
+            }
+
             let cell = mov.get_cell();
             let value = mov.get_value();
             let strategy = mov.get_strategy();
@@ -363,20 +2703,22 @@ impl<'a> SudokuSolver<'a> {
                         .to_owned();
                     for next_guess_value in guesses {
                         // if the move is not immediately rejected
-                        if let Ok(ref mut moves) =
-                            self.register_move(Strategy::Guess, &cell, next_guess_value)
+                        if self
+                            .register_move(Strategy::Guess, &cell, next_guess_value)
+                            .is_ok()
                         {
                             // guess seems to work for now, lets keep solving
-                            self.move_log.append(moves);
                             return Ok(cell);
                         }
                     }
                 }
 
                 // none of the possible guesses worked we keep backtracking
-                let possible_values = cell
-                    .get_possible_values(self.board)
+                let mut possible_values = candidate_cache::candidates_for(self.board, &cell)
                     .expect("cell was unset therefore the value must be Some");
+                if let Some(allowed) = self.allowed_candidates.get(&cell) {
+                    possible_values = possible_values.intersection(allowed);
+                }
 
                 self.candidate_cache
                     .reset_candidates(&cell, possible_values);
@@ -387,10 +2729,56 @@ impl<'a> SudokuSolver<'a> {
     }
 }
 
+/// Returns the cells with a single candidate left, with that candidate.
+fn naked_singles(candidate_cache: &CandidateCache) -> BTreeSet<(CellLoc, u8)> {
+    candidate_cache
+        .possible_values()
+        .iter()
+        .filter_map(|(cell, values)| match values.len() {
+            1 => Some((*cell, values.iter().next().unwrap())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the values that can only be placed in one cell of a line, column or square,
+/// with the cell and the block where it was found.
+fn hidden_singles(candidate_cache: &CandidateCache) -> BTreeMap<(CellLoc, u8), Block> {
+    let mut hidden_singles = BTreeMap::new();
+
+    for candidate in candidate_cache.iter_candidates() {
+        if candidate.len() != 1 {
+            continue;
+        }
+
+        let cell = candidate.cells().next().unwrap();
+
+        // the same hidden single can be found in more than one block, keep the
+        // smallest one so explanations don't depend on the iteration order
+        let block = hidden_singles
+            .entry((cell, candidate.value))
+            .or_insert(candidate.block);
+        if candidate.block < *block {
+            *block = candidate.block;
+        }
+    }
+
+    hidden_singles
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Strategy, SudokuSolver, UnsolvableError};
-    use std::collections::HashSet;
+    use super::{
+        conflicting_pairs, BacktrackReason, Block, BoardAnalyzer, CachedBoard, CandidateCache,
+        LiveSolver, SolveError, SolveObserver, SolveOptions, SolveStep, Strategy, StrategyConfig,
+        StuckError, SudokuSolver, TraceStep, UnsolvableError, VerifyResult,
+    };
+    use crate::board;
+    use crate::board::{Board, BoardSize, CellLoc, Variant};
+    use crate::solver::generator::Puzzle;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::{BTreeSet, HashMap, HashSet};
 
     #[test]
     fn naked_singles() {
@@ -438,14 +2826,369 @@ mod tests {
         .parse()
         .unwrap();
 
-        let solver = SudokuSolver::new(&mut board);
+        let solver = SudokuSolver::new(&mut board);
+
+        assert_eq!(
+            solver.hidden_singles(),
+            vec![((solver.board.cell_at(0, 8), 9), Block::Line(0))]
+                .drain(..)
+                .collect()
+        );
+    }
+
+    #[test]
+    fn conflicting_deductions() {
+        // the top left cell is the only place for a 3 in the first line and
+        // the only place for a 4 in the first column
+        let mut board: Board = "
+        .1..
+        2.3.
+        ....
+        .4..
+        "
+        .parse()
+        .unwrap();
+
+        let mut solver = SudokuSolver::new(&mut board);
+        let cell = solver.board.cell_at(0, 0);
+        assert!(solver.hidden_singles().contains_key(&(cell, 3)));
+        assert!(solver.hidden_singles().contains_key(&(cell, 4)));
+
+        assert_eq!(solver.deductions(), Err(UnsolvableError));
+        assert_eq!(solver.logic_iteration(), Some(Err(UnsolvableError)));
+        assert_eq!(solver.solve(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn conflicting_naked_and_hidden_singles() {
+        // a cell with a single candidate can't be a hidden single for another value,
+        // so the conflict shows up as the same value in two cells of the top left
+        // square: a 1 is the only candidate of R1C1 and R2C2 is the only place for a 1
+        // in the second column
+        let mut board: Board = "
+        .243
+        ....
+        ..1.
+        .3..
+        "
+        .parse()
+        .unwrap();
+
+        let mut solver = SudokuSolver::new(&mut board);
+        let naked = solver.board.cell_at(0, 0);
+        let hidden = solver.board.cell_at(1, 1);
+        assert!(solver.naked_singles().contains(&(naked, 1)));
+        assert!(!solver
+            .naked_singles()
+            .iter()
+            .any(|(cell, _)| *cell == hidden));
+        assert!(solver.hidden_singles().contains_key(&(hidden, 1)));
+
+        let deductions = solver.deductions().unwrap();
+        assert_eq!(deductions[&naked].1, Strategy::NakedSingle);
+        assert_eq!(deductions[&hidden].1, Strategy::HiddenSingle);
+
+        assert_eq!(solver.logic_iteration(), Some(Err(UnsolvableError)));
+        assert_eq!(solver.solve(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn progress() {
+        // solved without guessing so progress never goes back
+        let mut board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let mut solver = SudokuSolver::new(&mut board);
+        assert_eq!(solver.progress(), 0.0);
+        assert!(!solver.is_solved());
+
+        let mut last = 0.0;
+        while !solver.is_solved() {
+            solver.step().unwrap();
+            assert!(solver.progress() > last);
+            last = solver.progress();
+        }
+        assert_eq!(solver.progress(), 1.0);
+        assert_eq!(solver.remaining_cells(), 0);
+
+        // with guesses it still ends at 1.0
+        let mut board = Board::new(BoardSize::NineByNine);
+        let mut solver = SudokuSolver::new(&mut board);
+        let mut steps = 0;
+        while !solver.is_solved() {
+            solver.step().unwrap();
+            assert!((0.0..=1.0).contains(&solver.progress()));
+            steps += 1;
+        }
+        assert!(steps > 1);
+        assert_eq!(solver.progress(), 1.0);
+        solver.step().unwrap();
+        assert!(board.iter_cells().all(|cell| board.get(&cell).is_some()));
+    }
+
+    #[test]
+    fn strategy_config() {
+        let singles = StrategyConfig {
+            allow_guessing: false,
+            ..Default::default()
+        };
+        let naked_pairs = StrategyConfig {
+            naked_pairs: true,
+            ..singles
+        };
+        let pointing_pairs = StrategyConfig {
+            pointing_pairs: true,
+            ..singles
+        };
+
+        // stuck with singles, solved once naked pairs are enabled
+        let board: Board =
+            ".4..3.....9...1...8569..4....4..89..7..1.5.......62..........6........79......351"
+                .parse()
+                .unwrap();
+        let solution = board.solved_copy().unwrap();
+
+        let mut stuck = board.clone();
+        assert_eq!(
+            stuck.solve_with_config(singles),
+            Err(SolveError::RequiresGuessing {
+                board_state: stuck.clone()
+            })
+        );
+        let mut guessed = board.clone();
+        assert_eq!(guessed.solve_with_config(StrategyConfig::default()), Ok(()));
+        assert_eq!(guessed, solution);
+        let mut paired = board.clone();
+        assert_eq!(paired.solve_with_config(naked_pairs), Ok(()));
+        assert_eq!(paired, solution);
+
+        // stuck with singles, solved once pointing pairs are enabled
+        let board: Board =
+            "..4..26....15...8.8297..4..36.........7.28......93............9......2.6......713"
+                .parse()
+                .unwrap();
+        let mut stuck = board.clone();
+        assert_eq!(
+            stuck.solve_with_config(singles),
+            Err(SolveError::RequiresGuessing {
+                board_state: stuck.clone()
+            })
+        );
+        let mut pointed = board.clone();
+        assert_eq!(pointed.solve_with_config(pointing_pairs), Ok(()));
+        assert_eq!(pointed, board.solved_copy().unwrap());
+
+        let mut unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(
+            unsolvable.solve_with_config(singles),
+            Err(SolveError::Unsolvable)
+        );
+    }
+
+    #[test]
+    fn solve_logical() {
+        let mut board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let solution = board.solved_copy().unwrap();
+        assert_eq!(board.solve_logical(), Ok(()));
+        assert_eq!(board, solution);
+
+        // the puzzle used in the benchmarks needs guessing, but logic gets some way
+        let puzzle: Board =
+            ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21"
+                .parse()
+                .unwrap();
+        let mut board = puzzle.clone();
+        let board_state = match board.solve_logical() {
+            Err(SolveError::RequiresGuessing { board_state }) => board_state,
+            other => panic!("expected the solver to get stuck, got {:?}", other),
+        };
+        assert_eq!(board_state, board);
+        assert!(board_state.clue_count() > puzzle.clue_count());
+        assert!(!board_state.is_complete());
+        assert!(puzzle.is_subset_of(&board_state));
+        assert!(board_state.is_subset_of(&puzzle.solved_copy().unwrap()));
+        assert_eq!(
+            SolveError::RequiresGuessing { board_state }.to_string(),
+            StuckError.to_string()
+        );
+
+        let mut unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(unsolvable.solve_logical(), Err(SolveError::Unsolvable));
+    }
+
+    #[test]
+    fn verify() {
+        let puzzle = board![
+            [_, 7, 2, 4, _, _, 3, _, _],
+            [_, _, _, _, _, _, 4, 9, _],
+            [_, _, _, _, _, _, _, _, 2],
+            [9, 2, 1, _, _, _, 5, _, 7],
+            [_, _, 4, _, 6, _, _, _, 3],
+            [_, _, _, _, _, _, 2, _, _],
+            [_, 4, _, _, 7, _, _, _, _],
+            [_, 3, _, _, 1, 9, 6, _, _],
+            [_, _, 5, _, _, 4, _, 2, 1],
+        ];
+        let solution = puzzle.solved_copy().unwrap();
+        assert_eq!(super::verify(&puzzle, &solution), VerifyResult::Correct);
+        assert!(super::verify(&puzzle, &solution).is_correct());
+
+        // a different valid solution that contradicts the givens
+        let mut relabeled = solution.clone();
+        for cell in solution.iter_cells() {
+            relabeled.set(&cell, solution.get(&cell).unwrap() % 9 + 1);
+        }
+        assert_eq!(
+            super::verify(&puzzle, &relabeled),
+            VerifyResult::MismatchWithGivens(
+                puzzle
+                    .iter_cells()
+                    .filter(|cell| puzzle.get(cell).is_some())
+                    .collect()
+            )
+        );
+
+        // swapping two values in a line breaks both columns and squares
+        let mut swapped = solution.clone();
+        let (first, second) = (swapped.cell_at(0, 0), swapped.cell_at(0, 4));
+        let (a, b) = (
+            solution.get(&first).unwrap(),
+            solution.get(&second).unwrap(),
+        );
+        swapped.set(&first, b);
+        swapped.set(&second, a);
+        let conflicts = match super::verify(&puzzle, &swapped) {
+            VerifyResult::RuleViolation(conflicts) => conflicts,
+            other => panic!("expected a rule violation, got {:?}", other),
+        };
+        assert!(conflicts.iter().any(|(cell, _)| *cell == first));
+        assert!(conflicts.iter().any(|(cell, _)| *cell == second));
+        for (first, second) in &conflicts {
+            assert!(first < second);
+            assert_eq!(swapped.get(first), swapped.get(second));
+            assert!(
+                first.line() == second.line()
+                    || first.col() == second.col()
+                    || first.square() == second.square()
+            );
+        }
+
+        // a value repeated in a line and a square is one conflict
+        let mut repeated = puzzle.clone();
+        repeated.set_at(0, 0, 7);
+        assert_eq!(
+            super::verify(&puzzle, &repeated),
+            VerifyResult::RuleViolation(vec![(puzzle.cell_at(0, 0), puzzle.cell_at(0, 1))])
+        );
+
+        let mut partial = solution.clone();
+        partial.unset(&partial.cell_at(8, 8));
+        partial.unset(&partial.cell_at(2, 3));
+        assert_eq!(
+            super::verify(&puzzle, &partial),
+            VerifyResult::Incomplete(vec![puzzle.cell_at(2, 3), puzzle.cell_at(8, 8)])
+        );
+        assert_eq!(
+            super::verify(&puzzle, &puzzle),
+            VerifyResult::Incomplete(
+                puzzle
+                    .iter_cells()
+                    .filter(|cell| puzzle.get(cell).is_none())
+                    .collect()
+            )
+        );
+
+        assert_eq!(
+            super::verify(&puzzle, &Board::new(BoardSize::FourByFour)),
+            VerifyResult::SizeMismatch {
+                puzzle: BoardSize::NineByNine,
+                attempt: BoardSize::FourByFour
+            }
+        );
+
+        let puzzle = Puzzle::generate(BoardSize::FourByFour);
+        assert!(puzzle.verify(puzzle.solution()).is_correct());
+        assert!(matches!(
+            puzzle.verify(puzzle.board()),
+            VerifyResult::Incomplete(_)
+        ));
+    }
+
+    #[test]
+    fn solve_with_step_limit() {
+        // only needs singles, so one step per empty cell
+        let board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let empty_cells = 81 - board.clue_count();
+
+        let mut solved = board.clone();
+        assert_eq!(solved.solve_with_step_limit(empty_cells), Ok(()));
+        assert_eq!(solved, board.solved_copy().unwrap());
+        assert_eq!(
+            board.clone().solve_with_step_limit(empty_cells - 1),
+            Err(SolveError::StepLimitExceeded)
+        );
+
+        // needs well over a thousand steps with all the wrong guesses
+        let hard: Board =
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+                .parse()
+                .unwrap();
+        let mut gave_up = hard.clone();
+        assert_eq!(
+            gave_up.solve_with_step_limit(1000),
+            Err(SolveError::StepLimitExceeded)
+        );
+        assert!(hard.is_subset_of(&gave_up));
+        let mut solved = hard.clone();
+        assert_eq!(solved.solve_with_step_limit(10_000), Ok(()));
+        assert_eq!(solved, hard.solved_copy().unwrap());
 
+        let mut unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
         assert_eq!(
-            solver.hidden_singles(),
-            vec![(solver.board.cell_at(0, 8), 9)].drain(..).collect()
+            unsolvable.solve_with_step_limit(100),
+            Err(SolveError::Unsolvable)
         );
     }
 
+    #[test]
+    fn pair_strategies_with_guessing() {
+        let config = StrategyConfig {
+            naked_pairs: true,
+            pointing_pairs: true,
+            ..Default::default()
+        };
+
+        // eliminations made after a wrong guess must be undone when backtracking
+        for board in &[
+            ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21",
+            "................",
+            "123. .... .... ...1",
+        ] {
+            let board: Board = board.parse().unwrap();
+            let mut solved = board.clone();
+
+            match board.solved_copy() {
+                Ok(solution) => {
+                    assert_eq!(solved.solve_with_config(config), Ok(()));
+                    assert_eq!(solved, solution);
+                }
+                Err(_) => {
+                    assert_eq!(
+                        solved.solve_with_config(config),
+                        Err(SolveError::Unsolvable)
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn hidden_singles_after_backtrack() {
         let mut board = "
@@ -458,16 +3201,15 @@ mod tests {
         .unwrap();
         let mut solver = SudokuSolver::new(&mut board);
 
-        let mut log = solver
+        solver
             .register_move(Strategy::Guess, &solver.board.cell_at(3, 3), 3)
             .unwrap();
-        solver.move_log.append(&mut log);
 
         assert_eq!(
             solver.hidden_singles(),
             vec![
-                (solver.board.cell_at(0, 2), 3),
-                (solver.board.cell_at(2, 1), 3)
+                ((solver.board.cell_at(0, 2), 3), Block::Line(0)),
+                ((solver.board.cell_at(2, 1), 3), Block::Line(2))
             ]
             .drain(..)
             .collect()
@@ -499,4 +3241,817 @@ mod tests {
             UnsolvableError
         );
     }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Place(CellLoc, u8, Strategy),
+        Undo(CellLoc, u8),
+        BacktrackStart,
+    }
+
+    #[derive(Default)]
+    struct RecordEvents(Vec<Event>);
+
+    impl SolveObserver for RecordEvents {
+        fn on_place(&mut self, cell: CellLoc, value: u8, strategy: Strategy) {
+            self.0.push(Event::Place(cell, value, strategy));
+        }
+
+        fn on_undo(&mut self, cell: CellLoc, value: u8) {
+            self.0.push(Event::Undo(cell, value));
+        }
+
+        fn on_backtrack_start(&mut self) {
+            self.0.push(Event::BacktrackStart);
+        }
+    }
+
+    #[test]
+    fn deterministic_move_log() {
+        let puzzle: Board =
+            ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21"
+                .parse()
+                .unwrap();
+
+        let move_log = |options| {
+            let mut board = puzzle.clone();
+            let mut solver = SudokuSolver::with_options(&mut board, options);
+            solver.solve().unwrap();
+
+            solver
+                .move_log
+                .iter()
+                .map(|mov| (mov.get_cell(), mov.get_value(), mov.get_strategy()))
+                .collect::<Vec<_>>()
+        };
+
+        for prefer_fewest_candidates in [true, false] {
+            let options = SolveOptions {
+                deterministic: true,
+                prefer_fewest_candidates,
+            };
+            let first = move_log(options);
+
+            assert_eq!(first, move_log(options));
+            assert!(first
+                .iter()
+                .any(|(_, _, strategy)| *strategy == Strategy::Guess));
+        }
+
+        let mut board = Board::new(BoardSize::NineByNine);
+        let mut solver = SudokuSolver::with_options(&mut board, SolveOptions::default());
+        solver.solve().unwrap();
+        let (cell, value, _) = solver
+            .move_log
+            .iter()
+            .map(|mov| (mov.get_cell(), mov.get_value(), mov.get_strategy()))
+            .next()
+            .unwrap();
+        assert_eq!((cell, value), (CellLoc::new(0, BoardSize::NineByNine), 1));
+
+        // without a generator one is seeded for the first guess
+        let options = SolveOptions {
+            deterministic: false,
+            ..Default::default()
+        };
+        let mut board = Board::new(BoardSize::NineByNine);
+        let mut solver = SudokuSolver::with_options(&mut board, options);
+        assert!(solver.rng.is_none());
+        solver.solve().unwrap();
+        assert!(solver.rng.is_some());
+        assert!(solver.is_solved());
+    }
+
+    #[test]
+    fn into_solved() {
+        let board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let expected: Board =
+            "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+                .parse()
+                .unwrap();
+
+        assert_eq!(board.solved_copy(), Ok(expected.clone()));
+        assert_eq!(board.into_solved(), Ok(expected));
+
+        let unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(unsolvable.into_solved(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn observer_sees_every_move() {
+        let mut board: Board =
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+                .parse()
+                .unwrap();
+        let empty_cells = board
+            .iter_cells()
+            .filter(|cell| board.get(cell).is_none())
+            .count();
+
+        let mut observer = RecordEvents::default();
+        board.solve_with_observer(&mut observer).unwrap();
+
+        let places = observer
+            .0
+            .iter()
+            .filter(|event| matches!(event, Event::Place(..)))
+            .count();
+        let undos = observer
+            .0
+            .iter()
+            .filter(|event| matches!(event, Event::Undo(..)))
+            .count();
+
+        assert_eq!(places - undos, empty_cells);
+        assert!(observer.0.contains(&Event::BacktrackStart));
+    }
+
+    #[test]
+    fn explain_steps() {
+        let board = Board::new(BoardSize::NineByNine);
+        let step = |line, col, strategy, block| SolveStep {
+            cell: board.cell_at(line, col),
+            value: 9,
+            strategy,
+            block,
+        };
+
+        assert_eq!(
+            step(3, 6, Strategy::HiddenSingle, Some(Block::Col(6))).explain(BoardSize::NineByNine),
+            "R4C7 must be 9 because it is the only cell in column 7 that can contain 9"
+        );
+        assert_eq!(
+            step(3, 6, Strategy::HiddenSingle, Some(Block::Line(3))).explain(BoardSize::NineByNine),
+            "R4C7 must be 9 because it is the only cell in row 4 that can contain 9"
+        );
+        assert_eq!(
+            step(3, 6, Strategy::HiddenSingle, Some(Block::Square(5)))
+                .explain(BoardSize::NineByNine),
+            "R4C7 must be 9 because it is the only cell in the square from R4C7 to R6C9 that can contain 9"
+        );
+        assert_eq!(
+            step(0, 2, Strategy::NakedSingle, None).explain(BoardSize::NineByNine),
+            "R1C3 can only be 9"
+        );
+        assert_eq!(
+            step(0, 0, Strategy::Guess, None).explain(BoardSize::NineByNine),
+            "No other strategy can be applied, guessing that R1C1 is 9"
+        );
+    }
+
+    #[test]
+    fn explain_solution() {
+        let board: Board = "
+        ...45.78.
+        9........
+        .........
+        .........
+        .........
+        .........
+        .........
+        .........
+        .....9...
+        "
+        .parse()
+        .unwrap();
+
+        let explanations = board.explain_solution().unwrap();
+
+        assert_eq!(
+            explanations[0],
+            "R1C9 must be 9 because it is the only cell in row 1 that can contain 9"
+        );
+        assert_eq!(explanations.len(), 81 - 6);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn error_equality() {
+        use super::StuckError;
+
+        let mut board: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(board.solve(), Err(UnsolvableError));
+
+        let mut board = Board::new(BoardSize::FourByFour);
+        assert_eq!(board.solve_without_guessing(), Err(StuckError));
+        assert_eq!(StuckError.clone(), StuckError);
+    }
+
+    #[test]
+    fn solve_annotated() {
+        // needs guessing, so the solver backtracks before finding the solution
+        let puzzle: Board =
+            ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21"
+                .parse()
+                .unwrap();
+        let empty_count = puzzle
+            .iter_cells()
+            .filter(|cell| puzzle.get(cell).is_none())
+            .count();
+
+        let mut board = puzzle.clone();
+        let annotation = board.solve_annotated().unwrap();
+
+        assert_eq!(annotation.len(), empty_count);
+        assert!(annotation
+            .filled_cells()
+            .iter()
+            .all(|cell| puzzle.get(cell).is_none() && board.get(cell).is_some()));
+
+        let mut board: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(board.solve_annotated(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn has_unique_solution() {
+        assert_eq!(
+            Board::new(BoardSize::FourByFour).has_unique_solution(),
+            Ok(false)
+        );
+
+        let complete: Board =
+            "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+                .parse()
+                .unwrap();
+        assert_eq!(complete.has_unique_solution(), Ok(true));
+
+        let unique: Board =
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+                .parse()
+                .unwrap();
+        assert_eq!(unique.has_unique_solution(), Ok(true));
+
+        // generated puzzles are minimal, so removing any clue adds solutions
+        let mut rng = StdRng::seed_from_u64(0);
+        let puzzle = Puzzle::generate_with_rng(BoardSize::NineByNine, &mut rng);
+        let mut board = puzzle.board().clone();
+        assert_eq!(board.has_unique_solution(), Ok(true));
+        let clue = board
+            .iter_cells()
+            .find(|cell| board.get(cell).is_some())
+            .unwrap();
+        board.unset(&clue);
+        assert_eq!(board.has_unique_solution(), Ok(false));
+
+        let unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(unsolvable.has_unique_solution(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn hyper_variant() {
+        let solution: Board =
+            "541398267268417359793562814812759643376241598954683172427136985639875421185924736"
+                .parse()
+                .unwrap();
+        let mut board: Board =
+            "..1..82...........79....8....2..9.4.3.6..15...54...1...2...6...6................."
+                .parse()
+                .unwrap();
+
+        // the extra regions are needed for the solution to be unique
+        assert_eq!(board.has_unique_solution(), Ok(false));
+        board.set_variant(Variant::Hyper).unwrap();
+        assert_eq!(board.has_unique_solution(), Ok(true));
+
+        let mut solved = board.clone();
+        solved.solve().unwrap();
+        assert_eq!(solved.to_line_string(), solution.to_line_string());
+        assert_eq!(solved.variant(), Variant::Hyper);
+
+        let mut solved = board.clone();
+        solved.solve_dlx().unwrap();
+        assert_eq!(solved.to_line_string(), solution.to_line_string());
+
+        // 9 is only repeated in the top left extra region
+        let mut conflict = board.clone();
+        conflict.set(&conflict.cell_at(1, 3), 9);
+        assert_eq!(conflict.clone().solve(), Err(UnsolvableError));
+        assert_eq!(conflict.solve_dlx(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn rectangular_boxes() {
+        let board_size = BoardSize::custom(2, 3).unwrap();
+        let board =
+            Board::parse_with_size(".5.2...4.......31...2.5.5......315..", board_size).unwrap();
+        let solution = "156243243165465312312456524631631524";
+        assert_eq!(board.has_unique_solution(), Ok(true));
+
+        let mut solved = board.clone();
+        solved.solve().unwrap();
+        assert_eq!(solved.to_line_string(), solution);
+        assert_eq!(solved.board_size(), board_size);
+
+        let mut solved = board.clone();
+        solved.solve_dlx().unwrap();
+        assert_eq!(solved.to_line_string(), solution);
+
+        // a 5 at R2C3 only conflicts with the one at R1C2 through their box
+        let mut conflict = board.clone();
+        conflict.set(&conflict.cell_at(1, 2), 5);
+        assert_eq!(conflict.clone().solve(), Err(UnsolvableError));
+        assert_eq!(conflict.solve_dlx(), Err(UnsolvableError));
+
+        let mut empty = Board::new(BoardSize::custom(3, 4).unwrap());
+        empty.solve().unwrap();
+        assert!(empty.is_complete());
+        assert!(empty.validate_solution(&empty));
+    }
+
+    #[test]
+    fn solution_count_profile() {
+        let puzzle = Puzzle::generate(BoardSize::NineByNine);
+        let board = puzzle.board();
+        let profile = super::solution_count_profile(board, 2);
+
+        // every given of a minimal puzzle is needed for the solution to be unique
+        let givens: Vec<CellLoc> = board
+            .iter_cells()
+            .filter(|cell| board.get(cell).is_some())
+            .collect();
+        assert_eq!(
+            profile.iter().map(|(cell, _)| *cell).collect::<Vec<_>>(),
+            givens
+        );
+        assert!(profile.iter().all(|(_, count)| *count >= 2));
+
+        // a value of the solution added to the puzzle is redundant
+        let mut redundant = board.clone();
+        let cell = board
+            .iter_cells()
+            .find(|cell| board.get(cell).is_none())
+            .unwrap();
+        redundant.set(&cell, puzzle.solution().get(&cell).unwrap());
+        let profile = super::solution_count_profile(&redundant, 2);
+        assert!(profile.contains(&(cell, 1)));
+        assert!(profile.iter().all(|(_, count)| *count >= 1));
+
+        let unsolvable: Board = "123. ...4 .... ...1".parse().unwrap();
+        assert!(super::solution_count_profile(&unsolvable, 2)
+            .iter()
+            .any(|(_, count)| *count == 0));
+    }
+
+    #[test]
+    fn solve_with_candidates() {
+        let board: Board = "12.. ..2. .... ...4".parse().unwrap();
+        assert_eq!(
+            SudokuSolver::new(&mut board.clone()).count_solutions(10),
+            Ok(3)
+        );
+
+        // ruling out 4 for (2, 0) can't be derived from the board, but leaves one solution
+        let mut candidates = HashMap::new();
+        candidates.insert(board.cell_at(2, 0), vec![1, 2, 3].into_iter().collect());
+
+        let mut restricted = board.clone();
+        let mut solver = SudokuSolver::new(&mut restricted);
+        solver.restrict_candidates(&candidates).unwrap();
+        assert_eq!(solver.count_solutions(10), Ok(1));
+
+        let mut solved = board.clone();
+        solved.solve_with_candidates(&candidates).unwrap();
+        assert_eq!(solved, "1243 4321 3412 2134".parse().unwrap());
+
+        // 2 is possible for (2, 0) but not in any solution
+        candidates.insert(board.cell_at(2, 0), vec![2].into_iter().collect());
+        assert_eq!(
+            board.clone().solve_with_candidates(&candidates),
+            Err(UnsolvableError)
+        );
+
+        // no candidates left for (2, 0)
+        candidates.insert(board.cell_at(2, 0), BTreeSet::new());
+        assert_eq!(
+            board.clone().solve_with_candidates(&candidates),
+            Err(UnsolvableError)
+        );
+
+        // candidates of filled cells are ignored
+        candidates.clear();
+        candidates.insert(board.cell_at(0, 0), vec![3].into_iter().collect());
+        let mut solved = board.clone();
+        solved.solve_with_candidates(&candidates).unwrap();
+        assert_eq!(solved.get(&board.cell_at(0, 0)), Some(1));
+    }
+
+    #[test]
+    fn solve_with_candidates_after_backtracking() {
+        use rand::Rng;
+
+        // with half the cells restricted most guesses run out of options, the
+        // candidates of those cells must stay restricted after backtracking
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut exhausted = 0;
+        for _ in 0..50 {
+            let board = Board::new(BoardSize::NineByNine);
+            let mut candidates = HashMap::new();
+            for cell in board.iter_cells() {
+                if rng.gen_bool(0.5) {
+                    let allowed: BTreeSet<u8> = (1..=9).filter(|_| rng.gen_bool(0.8)).collect();
+                    candidates.insert(cell, allowed);
+                }
+            }
+
+            let mut restricted = board.clone();
+            let mut solver = SudokuSolver::new(&mut restricted);
+            solver.trace = Some(Default::default());
+            if solver.restrict_candidates(&candidates).is_err() {
+                continue;
+            }
+            let result = solver.solve();
+            // a guess with no options left is followed by removing an earlier value
+            // instead of placing the next option
+            let trace = solver.trace.take().unwrap();
+            exhausted += trace
+                .windows(2)
+                .filter(|steps| match steps {
+                    [TraceStep::Backtrack(guess), TraceStep::Backtrack(_)] => {
+                        guess.reason == BacktrackReason::WrongGuess
+                    }
+                    _ => false,
+                })
+                .count();
+            if result.is_err() {
+                continue;
+            }
+
+            for (cell, allowed) in &candidates {
+                let value = restricted.get(cell).unwrap();
+                assert!(allowed.contains(&value), "{} at {}", value, cell);
+            }
+        }
+        assert!(exhausted > 0);
+    }
+
+    #[test]
+    fn invalid_value() {
+        let mut board = Board::new(BoardSize::NineByNine);
+        board.set_unchecked(0, 0, Some(12));
+
+        let err = match board.clone().solve_checked() {
+            Err(SolveError::InvalidValue(err)) => err,
+            result => panic!("expected an invalid value, got {:?}", result),
+        };
+        assert_eq!(err.cell(), board.cell_at(0, 0));
+        assert_eq!(err.value(), 12);
+        assert_eq!(
+            err.to_string(),
+            "Cell (0, 0) holds 12, values of a 9x9 board go from 1 to 9"
+        );
+        let source = |err: &SolveError| std::error::Error::source(err).map(ToString::to_string);
+        assert_eq!(
+            source(&SolveError::InvalidValue(err.clone())),
+            Some(err.to_string())
+        );
+        assert_eq!(
+            source(&SolveError::Unsolvable),
+            Some(UnsolvableError.to_string())
+        );
+
+        // the other solvers don't find a solution either
+        assert_eq!(board.clone().solve(), Err(UnsolvableError));
+        assert_eq!(board.clone().into_solved(), Err(UnsolvableError));
+        assert_eq!(
+            SudokuSolver::new(&mut board.clone()).solve(),
+            Err(UnsolvableError)
+        );
+        assert_eq!(board.has_unique_solution(), Err(UnsolvableError));
+        assert!(matches!(
+            board.clone().solve_with_config(StrategyConfig::default()),
+            Err(SolveError::InvalidValue(_))
+        ));
+
+        // values that don't fit in a set of candidates are reported instead of panicking
+        for (board_size, value) in [
+            (BoardSize::FourByFour, 0),
+            (BoardSize::SixteenBySixteen, 17),
+        ] {
+            let mut board = Board::new(board_size);
+            board.set_unchecked(1, 1, Some(value));
+            assert!(matches!(
+                board.clone().solve_checked(),
+                Err(SolveError::InvalidValue(_))
+            ));
+            assert_eq!(board.into_solved(), Err(UnsolvableError));
+        }
+    }
+
+    #[test]
+    fn solve_traced_replays_to_solution() {
+        let puzzles: Vec<Board> = vec![
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+                .parse()
+                .unwrap(),
+            "1... .... .... ...2".parse().unwrap(),
+            Board::new(BoardSize::NineByNine),
+        ];
+
+        for puzzle in puzzles {
+            let mut board = puzzle.clone();
+            let trace = board.solve_traced().unwrap();
+
+            let mut replay = puzzle.clone();
+            for step in &trace {
+                match step {
+                    TraceStep::Place(step) => {
+                        assert_eq!(replay.set(&step.cell(), step.value()), None);
+                    }
+                    TraceStep::Backtrack(step) => {
+                        assert_eq!(replay.unset(&step.cell()), Some(step.tried()));
+                    }
+                }
+            }
+
+            assert_eq!(replay, board);
+            assert_eq!(board, puzzle.solved_copy().unwrap());
+        }
+    }
+
+    #[test]
+    fn solve_traced_backtracks() {
+        let mut board: Board =
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+                .parse()
+                .unwrap();
+        let trace = board.solve_traced().unwrap();
+
+        assert!(trace.iter().any(|step| matches!(
+            step,
+            TraceStep::Backtrack(step) if step.reason() == BacktrackReason::WrongGuess
+        )));
+
+        let mut board: Board = "123. ...4 .... ....".parse().unwrap();
+        assert_eq!(board.solve_traced(), Err(UnsolvableError));
+    }
+
+    #[test]
+    fn solve_region() {
+        // the last cell of the first line has no value left, so the board has no solution
+        let mut board = board![
+            [1, 2, 3, 4, 5, 6, 7, 8, _],
+            [_, _, _, _, _, _, _, _, 9],
+            [_, _, _, _, _, _, _, _, _],
+            [_, _, _, 7, _, _, _, _, _],
+            [_, _, _, _, _, _, _, 5, _],
+            [_, _, _, _, _, 3, _, _, _],
+            [_, _, _, _, _, _, _, _, _],
+            [_, _, _, _, 4, _, _, _, _],
+            [_, _, _, _, _, _, _, _, _],
+        ];
+        let original = board.clone();
+        assert!(board.clone().solve().is_err());
+
+        let center: BTreeSet<CellLoc> = board
+            .iter_cells()
+            .filter(|cell| cell.square() == 4)
+            .collect();
+        board.solve_region(&center).unwrap();
+
+        for cell in board.iter_cells() {
+            if center.contains(&cell) {
+                let value = board.get(&cell).unwrap();
+                assert!(cell
+                    .iter_line()
+                    .chain(cell.iter_col())
+                    .chain(cell.iter_square())
+                    .all(|other| other == cell || board.get(&other) != Some(value)));
+            } else {
+                assert_eq!(board.get(&cell), original.get(&cell));
+            }
+        }
+        assert_eq!(board.get_at(3, 3), Some(7));
+        assert_eq!(board.get_at(5, 5), Some(3));
+
+        let mut unsolvable = original.clone();
+        let last_cell: BTreeSet<CellLoc> = [unsolvable.cell_at(0, 8)].into_iter().collect();
+        assert_eq!(unsolvable.solve_region(&last_cell), Err(UnsolvableError));
+        assert_eq!(unsolvable, original);
+
+        let mut repeated = original.clone();
+        repeated.set_at(8, 8, 9);
+        assert_eq!(repeated.solve_region(&center), Err(UnsolvableError));
+
+        let mut empty = original;
+        assert_eq!(empty.solve_region(&BTreeSet::new()), Ok(()));
+
+        // the only cell of the line left to solve is not the only place for each value
+        let mut board: Board = "1... .... .... ....".parse().unwrap();
+        let cell: BTreeSet<CellLoc> = [board.cell_at(0, 1)].into_iter().collect();
+        let mut solver = SudokuSolver::new(&mut board);
+        solver.restrict_to_region(&cell);
+        assert_eq!(solver.remaining_cells(), 1);
+        assert_eq!(solver.verify_consistency(), Ok(()));
+        assert_eq!(solver.solve(), Ok(()));
+        assert_eq!(board, "12.. .... .... ....".parse().unwrap());
+    }
+
+    #[test]
+    fn analyzer_matches_next_solver_step() {
+        let puzzles: Vec<Board> = vec![
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap(),
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+                .parse()
+                .unwrap(),
+            "123. .... .... ....".parse().unwrap(),
+        ];
+
+        for puzzle in puzzles {
+            let analyzer = BoardAnalyzer::new(&puzzle);
+            let naked_singles = analyzer.naked_singles();
+            let hidden_singles = analyzer.hidden_singles();
+            assert_eq!(naked_singles, puzzle.get_naked_singles());
+            assert_eq!(hidden_singles, puzzle.get_hidden_singles());
+
+            let mut expected: Vec<(CellLoc, u8, Strategy)> = naked_singles
+                .iter()
+                .map(|&(cell, value)| (cell, value, Strategy::NakedSingle))
+                .collect();
+            for &(cell, value) in &hidden_singles {
+                if !naked_singles.iter().any(|&(other, _)| other == cell) {
+                    expected.push((cell, value, Strategy::HiddenSingle));
+                }
+            }
+            expected.sort_by_key(|&(cell, _, _)| cell);
+
+            let mut board = puzzle.clone();
+            let mut observer = RecordEvents::default();
+            SudokuSolver::with_observer(&mut board, &mut observer)
+                .step()
+                .unwrap();
+
+            let placed: Vec<(CellLoc, u8, Strategy)> = observer
+                .0
+                .iter()
+                .map(|event| match event {
+                    Event::Place(cell, value, strategy) => (*cell, *value, *strategy),
+                    _ => panic!("unexpected event {:?}", event),
+                })
+                .collect();
+
+            // the solver only guesses when there are no singles left
+            if expected.is_empty() {
+                assert!(matches!(placed[..], [(_, _, Strategy::Guess)]));
+            } else {
+                assert_eq!(placed, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn cached_board_stays_consistent() {
+        let puzzle: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let solution = puzzle.clone().into_solved().unwrap();
+        let mut board = CachedBoard::new(puzzle.clone());
+
+        let assert_fresh = |board: &CachedBoard| {
+            for cell in board.board().iter_cells() {
+                assert_eq!(
+                    board.candidates_at(&cell),
+                    cell.get_possible_values(board.board())
+                );
+            }
+            assert_eq!(board.verify_consistency(), Ok(()));
+        };
+
+        // placing the solution one cell at a time only updates the cells affected
+        for cell in puzzle
+            .iter_cells()
+            .filter(|cell| puzzle.get(cell).is_none())
+            .take(20)
+        {
+            let value = solution.get(&cell).unwrap();
+            assert_eq!(board.set(&cell, value), Ok(None));
+            assert_fresh(&board);
+        }
+
+        // replacing a value and placing a value that isn't a candidate anymore
+        let cell = puzzle
+            .iter_cells()
+            .filter(|cell| board.board().get(cell).is_none())
+            .last()
+            .unwrap();
+        let value = solution.get(&cell).unwrap();
+        assert_eq!(board.set(&cell, value), Ok(None));
+        assert_eq!(board.set(&cell, 4), Ok(Some(value)));
+        assert_fresh(&board);
+        let cell = puzzle
+            .iter_cells()
+            .find(|cell| board.board().get(cell).is_none())
+            .unwrap();
+        assert_eq!(board.set(&cell, 4), Ok(None));
+        assert_fresh(&board);
+
+        assert!(board.set(&cell, 10).is_err());
+        assert!(board
+            .set(&CellLoc::at(0, 0, BoardSize::FourByFour), 1)
+            .is_err());
+        assert_eq!(
+            board.candidates_at(&CellLoc::at(0, 1, BoardSize::FourByFour)),
+            None
+        );
+        assert_fresh(&board);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn solve_async() {
+        let mut board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let solution = board.clone().into_solved().unwrap();
+        assert_eq!(board.solve_async().await, Ok(()));
+        assert_eq!(board, solution);
+
+        let mut unsolvable: Board = "123. ...4 .... ....".parse().unwrap();
+        let before = unsolvable.clone();
+        assert_eq!(unsolvable.solve_async().await, Err(UnsolvableError));
+        assert_eq!(unsolvable, before);
+    }
+
+    #[test]
+    fn live_solver_matches_solving_from_scratch() {
+        use rand::seq::IteratorRandom;
+        use rand::Rng;
+
+        let puzzle: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let solution = puzzle.clone().into_solved().unwrap();
+        let mut solver = LiveSolver::new(puzzle.clone());
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for _ in 0..20 {
+            let board = solver.board().clone();
+            let empty = board.iter_cells().filter(|cell| board.get(cell).is_none());
+            let filled = board.iter_cells().filter(|cell| board.get(cell).is_some());
+
+            // fill in the solution, make a likely mistake or undo one, or remove a value
+            let mistakes: Vec<CellLoc> = board
+                .iter_cells()
+                .filter(|cell| {
+                    board
+                        .get(cell)
+                        .map_or(false, |value| Some(value) != solution.get(cell))
+                })
+                .collect();
+            match rng.gen_range(0..4) {
+                0 => {
+                    let cell = empty.choose(&mut rng).unwrap();
+                    solver.place(&cell, solution.get(&cell).unwrap()).unwrap();
+                }
+                1 => {
+                    let cell = empty.choose(&mut rng).unwrap();
+                    solver.place(&cell, rng.gen_range(1..=9)).unwrap();
+                }
+                2 if !mistakes.is_empty() => {
+                    let cell = *mistakes.iter().choose(&mut rng).unwrap();
+                    assert_eq!(solver.remove(&cell), Ok(board.get(&cell)));
+                }
+                _ => {
+                    let cell = filled.choose(&mut rng).unwrap();
+                    assert_eq!(solver.remove(&cell), Ok(board.get(&cell)));
+                }
+            }
+
+            let mut board = solver.board().clone();
+            let expected = if conflicting_pairs(&board, board.variant()).is_empty() {
+                SudokuSolver::new(&mut board).count_solutions(2)
+            } else {
+                Err(UnsolvableError)
+            };
+            assert_eq!(solver.solution_status(2), expected);
+            if solver.is_obviously_unsolvable() {
+                assert_eq!(expected, Err(UnsolvableError));
+            }
+
+            if solver.conflicts.is_empty() {
+                let fresh = CandidateCache::from_board(solver.board());
+                assert_eq!(
+                    solver.candidate_cache.possible_values(),
+                    fresh.possible_values()
+                );
+                assert_eq!(
+                    solver.candidate_cache.verify_consistency(solver.board()),
+                    Ok(())
+                );
+            }
+        }
+
+        let cell = puzzle.cell_at(0, 0);
+        assert!(solver.place(&cell, 10).is_err());
+        assert!(solver
+            .remove(&CellLoc::at(0, 0, BoardSize::FourByFour))
+            .is_err());
+    }
 }
@@ -1,31 +1,92 @@
+use super::candidates9::Candidates9;
 use super::indexed_map::{Indexed, IndexedMap, Map};
-use crate::board::{Board, CellLoc};
+use super::InvalidValueError;
+use crate::board::{Board, BoardSize, CellLoc, Variant, MAX_WIDTH};
+use std::collections::BTreeSet;
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+use std::error;
 use std::fmt;
-use std::{
-    collections::{BTreeSet, HashMap},
-    hash::Hash,
-};
+use std::mem;
 
-#[derive(Hash, Debug, PartialEq, Eq, Copy, Clone)]
+/// A group of cells in the board that must hold every value exactly once,
+/// identified by its 0 based line, column, square or extra region number.
+#[derive(Hash, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub enum Block {
+    /// A line of the board
     Line(usize),
+    /// A column of the board
     Col(usize),
+    /// A square of the board, numbered line first and then columns
     Square(usize),
+    /// An extra region of the [`Variant`] of the board, numbered line first and then
+    /// columns
+    Extra(usize),
 }
 
 impl Block {
-    fn with_value(&self, value: u8) -> (Self, u8) {
-        (*self, value)
+    /// Lines come first, then columns, squares and extra regions.
+    pub(crate) fn index(&self, width: usize) -> usize {
+        match self {
+            Block::Line(l) => *l,
+            Block::Col(c) => width + c,
+            Block::Square(s) => 2 * width + s,
+            Block::Extra(e) => 3 * width + e,
+        }
+    }
+
+    fn from_index(idx: usize, width: usize) -> Self {
+        match idx / width {
+            0 => Block::Line(idx),
+            1 => Block::Col(idx - width),
+            2 => Block::Square(idx - 2 * width),
+            _ => Block::Extra(idx - 3 * width),
+        }
+    }
+
+    /// Index in [`CandidateCache`]'s `candidate_cells` of the cells that can hold
+    /// `value` in this block.
+    fn slot(&self, value: u8, width: usize) -> usize {
+        self.index(width) * width + value as usize - 1
     }
-}
 
-impl CellLoc {
-    fn get_blocks_(&self) -> [Block; 3] {
-        [
-            Block::Line(self.line()),
-            Block::Col(self.col()),
-            Block::Square(self.square()),
-        ]
+    /// Bit of `cell` in a mask of cells of this block, the cell must belong to
+    /// the block.
+    fn bit(&self, cell: &CellLoc, board_size: BoardSize) -> u16 {
+        let position = match self {
+            Block::Line(_) => cell.col(),
+            Block::Col(_) => cell.line(),
+            // extra regions are as big as a square, so no two of their cells have the
+            // same position inside their square either
+            Block::Square(_) | Block::Extra(_) => {
+                (cell.line() % board_size.box_rows()) * board_size.box_cols()
+                    + cell.col() % board_size.box_cols()
+            }
+        };
+        1 << position
+    }
+
+    /// The opposite of [`Block::bit`].
+    fn cell_at(&self, position: usize, board_size: BoardSize, variant: Variant) -> CellLoc {
+        let (box_rows, box_cols) = (board_size.box_rows(), board_size.box_cols());
+        match self {
+            Block::Line(l) => CellLoc::at(*l, position, board_size),
+            Block::Col(c) => CellLoc::at(position, *c, board_size),
+            // each band is as many squares wide as each square has lines
+            Block::Square(s) => CellLoc::at(
+                (s / box_rows) * box_rows + position / box_cols,
+                (s % box_rows) * box_cols + position % box_cols,
+                board_size,
+            ),
+            Block::Extra(e) => {
+                let (line, col) = variant.extra_block_corner(*e);
+                CellLoc::at(
+                    line + (position / box_cols + box_rows - line % box_rows) % box_rows,
+                    col + (position % box_cols + box_cols - col % box_cols) % box_cols,
+                    board_size,
+                )
+            }
+        }
     }
 }
 
@@ -44,69 +105,247 @@ impl fmt::Display for NoCandidatesLeftError {
     }
 }
 
+/// The first difference found by [`SudokuSolver::verify_consistency`] or
+/// [`CachedBoard::verify_consistency`] between the candidates kept for each cell and
+/// block, and the board they belong to.
+///
+/// [`SudokuSolver::verify_consistency`]: super::SudokuSolver::verify_consistency
+/// [`CachedBoard::verify_consistency`]: super::CachedBoard::verify_consistency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsistencyError {
+    /// The cell is empty in the board but the solver has no candidates for it.
+    MissingCell(CellLoc),
+    /// The cell has a value in the board but the solver still has candidates for it.
+    FilledCell(CellLoc),
+    /// The value is a candidate for the cell but it's already in the cell's line,
+    /// column or square.
+    StaleCandidate {
+        /// The cell with the candidate
+        cell: CellLoc,
+        /// The value already placed in the board
+        value: u8,
+    },
+    /// The value can be placed in the cell according to the board but it's not one of
+    /// the cell's candidates.
+    MissingCandidate {
+        /// The cell without the candidate
+        cell: CellLoc,
+        /// The value missing from the candidates
+        value: u8,
+    },
+    /// The candidates of the cell and the candidate cells of the block disagree on
+    /// whether the cell can hold the value.
+    BlockMismatch {
+        /// The block of the cell
+        block: Block,
+        /// The value the block and the cell disagree about
+        value: u8,
+        /// The cell the block and its candidates disagree about
+        cell: CellLoc,
+    },
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingCell(cell) => write!(f, "Cell {} is empty but has no candidates", cell),
+            Self::FilledCell(cell) => write!(f, "Cell {} has a value but also candidates", cell),
+            Self::StaleCandidate { cell, value } => write!(
+                f,
+                "Cell {} has {} as a candidate but it's already placed in one of its blocks",
+                cell, value
+            ),
+            Self::MissingCandidate { cell, value } => write!(
+                f,
+                "Cell {} can hold {} but doesn't have it as a candidate",
+                cell, value
+            ),
+            Self::BlockMismatch { block, value, cell } => write!(
+                f,
+                "{:?} and cell {} disagree on whether the cell can hold {}",
+                block, cell, value
+            ),
+        }
+    }
+}
+
+impl error::Error for ConsistencyError {}
+
+/// The changes needed to undo a call to [`CandidateCache::set_value`].
+///
+/// Most of the changes are kept in the [`UndoLog`] of the cache, this only records where
+/// they start, so values must be undone in the reverse order they were set.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UndoSetValue {
-    moves: Vec<(u8, CellLoc, Block)>,
-    options: (CellLoc, Option<BTreeSet<u8>>),
-    affected_cell_options: Vec<(CellLoc, u8)>,
+    moves_start: usize,
+    affected_cell_options_start: usize,
+    options: (CellLoc, Option<Candidates9>),
 }
 
 impl UndoSetValue {
-    pub fn alternative_options(&self) -> &Option<BTreeSet<u8>> {
+    pub fn alternative_options(&self) -> &Option<Candidates9> {
         &self.options.1
     }
 }
 
-pub struct Candidates<'a> {
-    pub value: &'a u8,
-    pub block: &'a Block,
-    pub cells: &'a BTreeSet<CellLoc>,
+/// The changes needed to undo a call to [`CandidateCache::eliminate`], kept in the
+/// [`UndoLog`] of the cache the same way as [`UndoSetValue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoEliminate {
+    moves_start: usize,
+    affected_cell_options_start: usize,
+}
+
+/// The cells of a block that can still hold a value.
+pub struct Candidates {
+    pub value: u8,
+    pub block: Block,
+    cells: u16,
+    board_size: BoardSize,
+    variant: Variant,
+}
+
+impl Candidates {
+    pub fn len(&self) -> usize {
+        self.cells.count_ones() as usize
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = CellLoc> {
+        let Candidates {
+            block,
+            cells,
+            board_size,
+            variant,
+            ..
+        } = *self;
+
+        (0..board_size.width())
+            .filter(move |position| cells & (1 << position) != 0)
+            .map(move |position| block.cell_at(position, board_size, variant))
+    }
+}
+
+/// Slots of [`CandidateCache`]'s `candidate_cells` needed by the widest board, with three
+/// blocks per line. Variants only add extra regions to narrower boards, see
+/// [`Variant::supports`].
+const MAX_SLOTS: usize = 3 * MAX_WIDTH * MAX_WIDTH;
+
+/// Changes made by every call to [`CandidateCache::set_value`] that wasn't undone yet,
+/// used as a stack so the memory is reused instead of allocated for every value set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct UndoLog {
+    /// Cells removed from `candidate_cells`, by index.
+    moves: Vec<(usize, u16)>,
+    affected_cell_options: Vec<(CellLoc, u8)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CandidateCache {
-    possible_values: IndexedMap<CellLoc, BTreeSet<u8>>,
-    candidate_cells: HashMap<(Block, u8), BTreeSet<CellLoc>>,
+    board_size: BoardSize,
+    variant: Variant,
+    possible_values: IndexedMap<CellLoc, Candidates9>,
+    /// For every block and value the mask of cells of that block that can hold the
+    /// value, indexed by [`Block::slot`]. Only the first [`slot_count`](Self::slot_count)
+    /// slots are used.
+    candidate_cells: [u16; MAX_SLOTS],
+    /// Empty cells left out by [`CandidateCache::fix_cells_outside`], treated as if
+    /// they were filled.
+    fixed_cells: BTreeSet<CellLoc>,
+    undo_log: UndoLog,
+}
+
+impl Default for CandidateCache {
+    fn default() -> Self {
+        CandidateCache {
+            board_size: BoardSize::NineByNine,
+            variant: Variant::Standard,
+            possible_values: IndexedMap::new(0),
+            candidate_cells: [0; MAX_SLOTS],
+            fixed_cells: BTreeSet::new(),
+            undo_log: UndoLog::default(),
+        }
+    }
 }
 
 impl CandidateCache {
     pub fn from_board(board: &Board) -> Self {
-        let possible_values = Self::calculate_possible_values(board);
+        let mut candidate_cache = Self::default();
+        candidate_cache.reset(board);
+        candidate_cache
+    }
 
-        let mut candidate_cache = CandidateCache {
-            possible_values,
-            candidate_cells: HashMap::with_capacity(board.board_size().get_base_size().pow(4) * 3),
-        };
+    /// Same as [`reset`](Self::reset), but also checks that every value of the board is
+    /// in range. The cache is reset either way, ignoring the values out of range, so the
+    /// solver can still answer questions about the board before refusing to solve it.
+    pub fn try_reset(&mut self, board: &Board) -> Result<(), InvalidValueError> {
+        self.reset(board);
+        check_values(board)
+    }
 
-        for cell in candidate_cache.possible_values.keys() {
-            let possible_values = candidate_cache.possible_values.get(cell);
-
-            for value in 1..=(board.board_size().get_base_size() as u8).pow(2) {
-                if let Some(possible_values) = possible_values {
-                    if possible_values.contains(&value) {
-                        for block in &cell.get_blocks_() {
-                            candidate_cache
-                                .candidate_cells
-                                .entry(block.with_value(value))
-                                .or_default()
-                                .insert(*cell);
-                        }
-                    }
-                }
+    /// Recalculates the cache for a new board, reusing the memory already allocated
+    /// whenever possible.
+    pub fn reset(&mut self, board: &Board) {
+        let board_size = board.board_size();
+        self.board_size = board_size;
+        self.variant = board.variant();
+
+        if self.possible_values.capacity() == board_size.cell_count() {
+            self.possible_values.clear();
+        } else {
+            self.possible_values = IndexedMap::new(board_size.cell_count());
+        }
+
+        for cell in board.iter_cells() {
+            if let Some(values) = candidates_for(board, &cell) {
+                self.possible_values.insert(cell, values);
             }
         }
 
-        candidate_cache
+        self.fixed_cells.clear();
+        assert!(
+            self.slot_count() <= MAX_SLOTS,
+            "{:?} is too big for {:?}",
+            self.variant,
+            board_size
+        );
+        self.candidate_cells = [0; MAX_SLOTS];
+        self.clear_undo_log();
+
+        let width = board_size.width();
+        for (cell, possible_values) in self.possible_values.iter() {
+            for value in possible_values.iter() {
+                for block in cell.iter_blocks(self.variant) {
+                    self.candidate_cells[block.slot(value, width)] |= block.bit(cell, board_size);
+                }
+            }
+        }
     }
 
-    fn calculate_possible_values(board: &Board) -> IndexedMap<CellLoc, BTreeSet<u8>> {
-        let mut possible_values = IndexedMap::new(board.board_size().get_base_size().pow(4));
-        for cell in board.iter_cells() {
-            if let Some(values) = cell.get_possible_values(board) {
-                possible_values.insert(cell, values);
+    /// Treats the empty cells outside of `region` as if they were filled, they lose their
+    /// candidates and stop being candidate cells of their blocks, so only the cells of
+    /// `region` are left to solve. Blocks only partly in the region don't need to hold
+    /// every value anymore, so hidden singles can't be trusted after this.
+    pub fn fix_cells_outside(&mut self, region: &BTreeSet<CellLoc>) {
+        let width = self.board_size.width();
+        let board_size = self.board_size;
+        let outside: Vec<CellLoc> = self
+            .possible_values
+            .keys_copied()
+            .filter(|cell| !region.contains(cell))
+            .collect();
+
+        for cell in outside {
+            let values = self
+                .possible_values
+                .remove(&cell)
+                .expect("Only cells with candidates are fixed");
+            for value in values.iter() {
+                for block in cell.iter_blocks(self.variant) {
+                    self.candidate_cells[block.slot(value, width)] &= !block.bit(&cell, board_size);
+                }
             }
+            self.fixed_cells.insert(cell);
         }
-        possible_values
     }
 
     pub fn set_value(
@@ -114,70 +353,74 @@ impl CandidateCache {
         value: u8,
         cell: CellLoc,
     ) -> Result<UndoSetValue, NoCandidatesLeftError> {
+        // deductions found together can disagree, e.g. two hidden singles for the same
+        // value in the same line, so the value may no longer be a candidate
+        if let Some(options) = self.possible_values.get(&cell) {
+            if !options.contains(value) {
+                return Err(NoCandidatesLeftError(cell));
+            }
+        }
+
         // remove all possible values for this cell
         let maybe_options = self.possible_values.remove(&cell);
-        let mut moves = Vec::new();
+        let moves_start = self.undo_log.moves.len();
+        let affected_cell_options_start = self.undo_log.affected_cell_options.len();
+        let moves = &mut self.undo_log.moves;
+        let width = self.board_size.width();
+        let board_size = self.board_size;
 
         // in this line, column and square this value is no longer relevant so it's removed from cache
-        for block in &cell.get_blocks_() {
-            let candidates = self.candidate_cells.remove(&block.with_value(value));
-
-            if let Some(candidates) = candidates {
-                moves.extend(
-                    &mut candidates
-                        .iter()
-                        .map(|candidate| (value, *candidate, *block)),
-                );
+        for block in cell.iter_blocks(self.variant) {
+            let slot = block.slot(value, width);
+            let candidates = mem::take(&mut self.candidate_cells[slot]);
+
+            if candidates != 0 {
+                moves.push((slot, candidates));
             }
 
             // remove the cell as candidate for all other values in this line, col and square
             if let Some(other_values) = &maybe_options {
-                for other_value in other_values {
-                    if *other_value != value {
-                        if let Some(candidates) = self
-                            .candidate_cells
-                            .get_mut(&block.with_value(*other_value))
-                        {
-                            if candidates.remove(&cell) {
-                                moves.push((*other_value, cell, *block));
-                            }
+                let bit = block.bit(&cell, board_size);
+                for other_value in other_values.iter() {
+                    if other_value != value {
+                        let slot = block.slot(other_value, width);
+                        if self.candidate_cells[slot] & bit != 0 {
+                            self.candidate_cells[slot] &= !bit;
+                            moves.push((slot, bit));
                         }
                     }
                 }
             }
         }
 
-        let mut affected_cell_options = Vec::new();
+        let affected_cell_options = &mut self.undo_log.affected_cell_options;
 
-        let affected_cells = cell
-            .iter_line()
-            .chain(cell.iter_col())
-            .chain(cell.iter_square());
+        let affected_cells = cell.iter_peers(self.variant);
 
         for affected_cell in affected_cells {
             if let Some(values) = self.possible_values.get_mut(&affected_cell) {
                 assert!(!values.is_empty());
 
-                if values.remove(&value) {
+                if values.remove(value) {
                     affected_cell_options.push((affected_cell, value));
 
                     // for every cell affected by this one (same line, col and square)
                     // that cell is no longer a candidate for this value in all it's blocks
-                    for block in &affected_cell.get_blocks_() {
-                        if let Some(cells) = self.candidate_cells.get_mut(&block.with_value(value))
-                        {
-                            if cells.remove(&affected_cell) {
-                                moves.push((value, affected_cell, *block));
-                            }
+                    for block in affected_cell.iter_blocks(self.variant) {
+                        let slot = block.slot(value, width);
+                        let bit = block.bit(&affected_cell, board_size);
+                        if self.candidate_cells[slot] & bit != 0 {
+                            self.candidate_cells[slot] &= !bit;
+                            moves.push((slot, bit));
                         }
                     }
                 }
 
                 if values.is_empty() {
                     self.undo(UndoSetValue {
-                        moves,
+                        moves_start,
+                        affected_cell_options_start,
                         options: (cell, maybe_options),
-                        affected_cell_options,
                     });
                     return Err(NoCandidatesLeftError(cell));
                 }
@@ -185,43 +428,115 @@ impl CandidateCache {
         }
 
         Ok(UndoSetValue {
-            moves,
+            moves_start,
+            affected_cell_options_start,
             options: (cell, maybe_options),
-            affected_cell_options,
         })
     }
 
+    /// Removes each value from the candidates of its cell, the values that aren't
+    /// candidates anymore are ignored. If a cell is left without candidates the cache
+    /// is left untouched and an error is returned.
+    pub fn eliminate(
+        &mut self,
+        eliminations: &BTreeSet<(CellLoc, u8)>,
+    ) -> Result<UndoEliminate, NoCandidatesLeftError> {
+        let undo = UndoEliminate {
+            moves_start: self.undo_log.moves.len(),
+            affected_cell_options_start: self.undo_log.affected_cell_options.len(),
+        };
+        let width = self.board_size.width();
+        let board_size = self.board_size;
+
+        for (cell, value) in eliminations {
+            let values = match self.possible_values.get_mut(cell) {
+                Some(values) => values,
+                None => continue,
+            };
+
+            if !values.remove(*value) {
+                continue;
+            }
+            let is_empty = values.is_empty();
+            self.undo_log.affected_cell_options.push((*cell, *value));
+
+            for block in cell.iter_blocks(self.variant) {
+                let slot = block.slot(*value, width);
+                let bit = block.bit(cell, board_size);
+                if self.candidate_cells[slot] & bit != 0 {
+                    self.candidate_cells[slot] &= !bit;
+                    self.undo_log.moves.push((slot, bit));
+                }
+            }
+
+            if is_empty {
+                self.undo_eliminate(undo);
+                return Err(NoCandidatesLeftError(*cell));
+            }
+        }
+
+        Ok(undo)
+    }
+
     pub fn reset_candidates(
         &mut self,
         cell: &CellLoc,
-        options: BTreeSet<u8>,
-    ) -> Option<BTreeSet<u8>> {
-        for value in &options {
-            self.add_candidate(value, cell);
+        options: Candidates9,
+    ) -> Option<Candidates9> {
+        for value in options.iter() {
+            self.add_candidate(&value, cell);
         }
 
         self.possible_values.insert(*cell, options)
     }
 
+    /// Updates the cache after the value of `cell` was removed from `board`, the opposite
+    /// of [`set_value`]. The cell must already be empty in `board`.
+    ///
+    /// [`set_value`]: CandidateCache::set_value
+    pub fn remove_cell_value(&mut self, board: &Board, cell: CellLoc, value: u8) {
+        let options = candidates_for(board, &cell).expect("The cell must be empty in the board");
+        self.reset_candidates(&cell, options);
+
+        let affected_cells = cell
+            .iter_peers(self.variant)
+            .filter(|affected_cell| *affected_cell != cell && board.get(affected_cell).is_none());
+
+        for affected_cell in affected_cells {
+            // the value is possible again unless it's still set somewhere else in the
+            // line, column or square of the affected cell
+            let is_blocked = affected_cell
+                .iter_peers(self.variant)
+                .any(|other| board.get(&other) == Some(value));
+
+            if !is_blocked {
+                self.possible_values
+                    .entry(affected_cell)
+                    .or_default()
+                    .insert(value);
+                self.add_candidate(&value, &affected_cell);
+            }
+        }
+    }
+
     fn add_candidate(&mut self, value: &u8, cell: &CellLoc) {
-        for block in &cell.get_blocks_() {
-            self.candidate_cells
-                .entry(block.with_value(*value))
-                .or_default()
-                .insert(*cell);
+        let width = self.board_size.width();
+        let board_size = self.board_size;
+        for block in cell.iter_blocks(self.variant) {
+            self.candidate_cells[block.slot(*value, width)] |= block.bit(cell, board_size);
         }
     }
 
     pub fn remove_candidate(&mut self, value: &u8, cell: &CellLoc) {
         // first remove the value as an option for that cell
         if let Some(options) = self.possible_values.get_mut(cell) {
-            if options.remove(value) {
+            if options.remove(*value) {
                 // if value was an option for that cell then also remove the cell as
                 // a candidate for that value in all blocks
-                for block in &cell.get_blocks_() {
-                    if let Some(cells) = self.candidate_cells.get_mut(&block.with_value(*value)) {
-                        cells.remove(cell);
-                    }
+                let width = self.board_size.width();
+                let board_size = self.board_size;
+                for block in cell.iter_blocks(self.variant) {
+                    self.candidate_cells[block.slot(*value, width)] &= !block.bit(cell, board_size);
                 }
             }
         }
@@ -233,57 +548,248 @@ impl CandidateCache {
             self.possible_values.insert(cell, options);
         }
 
-        for (cell, value) in undo.affected_cell_options {
+        self.undo_since(undo.moves_start, undo.affected_cell_options_start);
+    }
+
+    pub fn undo_eliminate(&mut self, undo: UndoEliminate) {
+        self.undo_since(undo.moves_start, undo.affected_cell_options_start);
+    }
+
+    /// Restores every change in the undo log after the given positions.
+    fn undo_since(&mut self, moves_start: usize, affected_cell_options_start: usize) {
+        for (cell, value) in self
+            .undo_log
+            .affected_cell_options
+            .drain(affected_cell_options_start..)
+        {
             self.possible_values.entry(cell).or_default().insert(value);
         }
 
-        for (value, cell, block) in undo.moves {
-            self.candidate_cells
-                .entry(block.with_value(value))
-                .or_default()
-                .insert(cell);
+        for (slot, cells) in self.undo_log.moves.drain(moves_start..) {
+            self.candidate_cells[slot] |= cells;
         }
     }
 
-    pub fn iter_candidates(&self) -> impl Iterator<Item = Candidates> {
-        self.candidate_cells
+    /// Forgets how to undo every value set so far, for values that will never be
+    /// undone. None of the [`UndoSetValue`] returned until now can be used afterwards.
+    pub fn clear_undo_log(&mut self) {
+        self.undo_log.moves.clear();
+        self.undo_log.affected_cell_options.clear();
+    }
+
+    /// Iterates over every block and value that still has candidate cells.
+    pub fn iter_candidates(&self) -> impl Iterator<Item = Candidates> + '_ {
+        let board_size = self.board_size;
+        let width = board_size.width();
+
+        self.candidate_cells[..self.slot_count()]
             .iter()
-            .map(|((block, value), cells)| Candidates {
-                value,
-                block,
-                cells,
+            .enumerate()
+            .filter(|(_, cells)| **cells != 0)
+            .map(move |(slot, cells)| Candidates {
+                value: (slot % width) as u8 + 1,
+                block: Block::from_index(slot / width, width),
+                cells: *cells,
+                board_size,
+                variant: self.variant,
             })
     }
 
-    pub fn possible_values(&self) -> &IndexedMap<CellLoc, BTreeSet<u8>> {
+    /// Number of slots of `candidate_cells` used by the board, one per block and value.
+    fn slot_count(&self) -> usize {
+        let width = self.board_size.width();
+        (3 * width + self.variant.extra_block_count()) * width
+    }
+
+    pub fn possible_values(&self) -> &IndexedMap<CellLoc, Candidates9> {
         &self.possible_values
     }
 
-    #[cfg(test)]
-    fn candidates_at(&self, block: &Block, value: &u8) -> Option<&BTreeSet<CellLoc>> {
-        self.candidate_cells.get(&block.with_value(*value))
+    /// Returns true if some value can't be placed anywhere in a block, it's not in any
+    /// of the block's cells in `board` and none of its empty cells can hold it.
+    pub fn has_block_without_candidates(&self, board: &Board) -> bool {
+        let board_size = self.board_size;
+        let width = board_size.width();
+
+        (0..3 * width + self.variant.extra_block_count())
+            .map(|idx| Block::from_index(idx, width))
+            .any(|block| {
+                (1..=board_size.max_value()).any(|value| {
+                    self.candidate_cells[block.slot(value, width)] == 0
+                        && (0..width).all(|position| {
+                            let cell = block.cell_at(position, board_size, self.variant);
+                            board.get(&cell) != Some(value)
+                        })
+                })
+            })
     }
 
-    #[cfg(debug)]
+    /// Checks that the cache is the one [`from_board`](Self::from_board) would build for
+    /// `board`: on top of the checks of [`verify_candidates`](Self::verify_candidates),
+    /// every empty cell has exactly the candidates of a fresh cache. Cells fixed with
+    /// [`fix_cells_outside`](Self::fix_cells_outside) are left out.
+    pub fn verify_consistency(&self, board: &Board) -> Result<(), ConsistencyError> {
+        self.verify_candidates(board)?;
+
+        let fresh = CandidateCache::from_board(board);
+        for (cell, expected) in fresh.possible_values.iter() {
+            if self.fixed_cells.contains(cell) {
+                continue;
+            }
+            let found = self.possible_values.get(cell).copied().unwrap_or_default();
+            if let Some(value) = expected.iter().find(|value| !found.contains(*value)) {
+                return Err(ConsistencyError::MissingCandidate { cell: *cell, value });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the cache describes `board`: every empty cell and only those has
+    /// candidates, none of them already placed in one of the cell's blocks, and the
+    /// candidate cells of each block agree with the candidates of each cell. Cells may
+    /// have fewer candidates than a fresh cache, since eliminations and guesses ruled out
+    /// while backtracking remove candidates that can't be deduced from the board alone.
+    pub fn verify_candidates(&self, board: &Board) -> Result<(), ConsistencyError> {
+        let values: Vec<u8> = (1..=self.board_size.max_value()).collect();
+        board
+            .iter_cells()
+            .try_for_each(|cell| self.verify_cell(board, cell, &values))
+    }
+
+    /// Same as [`verify_candidates`](Self::verify_candidates) but only for `value` in
+    /// `cell` and the cells that see it, enough to check each value set by the solver
+    /// without slowing it down too much.
+    pub fn verify_placement(
+        &self,
+        board: &Board,
+        cell: CellLoc,
+        value: u8,
+    ) -> Result<(), ConsistencyError> {
+        std::iter::once(cell)
+            .chain(cell.iter_peers(self.variant))
+            .try_for_each(|other| self.verify_cell(board, other, &[value]))
+    }
+
+    fn verify_cell(
+        &self,
+        board: &Board,
+        cell: CellLoc,
+        values: &[u8],
+    ) -> Result<(), ConsistencyError> {
+        let width = self.board_size.width();
+        let board_size = self.board_size;
+        let candidates = match (self.possible_values.get(&cell), board.get(&cell)) {
+            (Some(_), Some(_)) => return Err(ConsistencyError::FilledCell(cell)),
+            (None, None) if self.fixed_cells.contains(&cell) => Candidates9::default(),
+            (None, None) => return Err(ConsistencyError::MissingCell(cell)),
+            (None, Some(_)) => Candidates9::default(),
+            (Some(candidates), None) => *candidates,
+        };
+
+        for value in values.iter().copied() {
+            if candidates.contains(value)
+                && cell
+                    .iter_peers(self.variant)
+                    .any(|other| board.get(&other) == Some(value))
+            {
+                return Err(ConsistencyError::StaleCandidate { cell, value });
+            }
+
+            for block in cell.iter_blocks(self.variant) {
+                let in_block = self.candidate_cells[block.slot(value, width)]
+                    & block.bit(&cell, board_size)
+                    != 0;
+                if in_block != candidates.contains(value) {
+                    return Err(ConsistencyError::BlockMismatch { block, value, cell });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the candidates of each cell from the candidate cells of every block, to
+    /// check that both views of the cache agree. Cells without candidates are left out.
+    #[cfg(debug_assertions)]
     pub fn possible_values_from_candidates(&self) -> HashMap<CellLoc, BTreeSet<u8>> {
         let mut possible_values: HashMap<CellLoc, BTreeSet<u8>> = HashMap::new();
 
-        for (ValueBlock { value, .. }, cells) in &self.candidate_cells {
-            for cell in cells {
-                possible_values.entry(*cell).or_default().insert(*value);
+        for candidates in self.iter_candidates() {
+            for cell in candidates.cells() {
+                possible_values
+                    .entry(cell)
+                    .or_default()
+                    .insert(candidates.value);
             }
         }
 
         possible_values
     }
+
+    #[cfg(test)]
+    fn candidates_at(&self, block: &Block, value: &u8) -> Option<BTreeSet<CellLoc>> {
+        let cells = self.candidate_cells[block.slot(*value, self.board_size.width())];
+        let candidates = Candidates {
+            value: *value,
+            block: *block,
+            cells,
+            board_size: self.board_size,
+            variant: self.variant,
+        };
+
+        match cells {
+            0 => None,
+            _ => Some(candidates.cells().collect()),
+        }
+    }
+}
+
+/// Same as [`CellLoc::get_possible_values`] but without allocating a set.
+pub fn candidates_for(board: &Board, cell: &CellLoc) -> Option<Candidates9> {
+    if board.get(cell).is_some() {
+        return None;
+    }
+
+    let max_value = board.board_size().max_value();
+    let mut candidates = Candidates9::all(max_value);
+    for value in cell
+        .iter_peers(board.variant())
+        .filter_map(|other| board.get(&other))
+        .filter(|value| (1..=max_value).contains(value))
+    {
+        candidates.remove(value);
+    }
+
+    Some(candidates)
+}
+
+/// Returns [`InvalidValueError`] for the first cell of the board holding 0 or a value
+/// larger than the largest value of the board.
+pub fn check_values(board: &Board) -> Result<(), InvalidValueError> {
+    let board_size = board.board_size();
+    let max_value = board_size.max_value();
+    let invalid = board
+        .iter_cells()
+        .filter_map(|cell| board.get(&cell).map(|value| (cell, value)))
+        .find(|(_, value)| !(1..=max_value).contains(value));
+
+    match invalid {
+        Some((cell, value)) => Err(InvalidValueError {
+            cell,
+            value,
+            board_size,
+        }),
+        None => Ok(()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Block::{Col, Line, Square};
-    use super::CandidateCache;
+    use super::Block::{self, Col, Line, Square};
+    use super::{CandidateCache, ConsistencyError};
     use crate::{
-        board::{Board, BoardSize, CellLoc},
+        board::{Board, BoardSize, CellLoc, Variant},
         solver::indexed_map::Map,
     };
     use std::collections::BTreeSet;
@@ -303,12 +809,56 @@ mod tests {
         assert_eq!(cc.iter_candidates().count(), 81 * 3);
         assert_eq!(
             cc.iter_candidates()
-                .map(|candidate| *candidate.value)
+                .map(|candidate| candidate.value)
                 .collect::<BTreeSet<u8>>(),
             (1..=9).collect()
         );
     }
 
+    #[test]
+    fn block_cell_positions() {
+        for (board_size, variant) in [
+            (BoardSize::FourByFour, Variant::Standard),
+            (BoardSize::NineByNine, Variant::Standard),
+            (BoardSize::NineByNine, Variant::Hyper),
+            (BoardSize::custom(2, 3).unwrap(), Variant::Standard),
+            (BoardSize::custom(3, 4).unwrap(), Variant::Standard),
+        ] {
+            let board = Board::new(board_size);
+            let width = board_size.width();
+
+            for cell in board.iter_cells() {
+                for block in cell.iter_blocks(variant) {
+                    assert_eq!(Block::from_index(block.index(width), width), block);
+
+                    let position = block.bit(&cell, board_size).trailing_zeros() as usize;
+                    assert_eq!(block.cell_at(position, board_size, variant), cell);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reset_with_invalid_values() {
+        let mut board: Board = "1... .... .... ...2".parse().unwrap();
+        let mut cc = CandidateCache::default();
+        assert_eq!(cc.try_reset(&board), Ok(()));
+
+        board.set_unchecked(0, 0, Some(9));
+        let err = cc.try_reset(&board).unwrap_err();
+        assert_eq!(err.cell(), board.cell_at(0, 0));
+        assert_eq!(err.value(), 9);
+
+        // the invalid value is ignored, so the cache matches a board without it
+        board.set_unchecked(0, 0, None);
+        let mut expected = CandidateCache::default();
+        expected.reset(&board);
+        assert_eq!(
+            cc.possible_values().get(&board.cell_at(0, 1)),
+            expected.possible_values().get(&board.cell_at(0, 1))
+        );
+    }
+
     #[test]
     fn possible_values_after_parse() {
         let board =
@@ -342,7 +892,7 @@ mod tests {
         assert_eq!(
             cc.candidates_at(&Line(0), &9),
             Some(
-                &vec![
+                vec![
                     CellLoc::at(0, 7, BoardSize::NineByNine),
                     CellLoc::at(0, 8, BoardSize::NineByNine),
                 ]
@@ -354,7 +904,7 @@ mod tests {
         assert_eq!(
             cc.candidates_at(&Col(0), &9),
             Some(
-                &vec![
+                vec![
                     CellLoc::at(7, 0, BoardSize::NineByNine),
                     CellLoc::at(8, 0, BoardSize::NineByNine),
                 ]
@@ -366,7 +916,7 @@ mod tests {
         assert_eq!(
             cc.candidates_at(&Square(0), &9),
             Some(
-                &vec![CellLoc::at(2, 2, BoardSize::NineByNine)]
+                vec![CellLoc::at(2, 2, BoardSize::NineByNine)]
                     .drain(..)
                     .collect()
             )
@@ -422,7 +972,7 @@ mod tests {
         // setting the 3 above removes the other possible location for a 3 in square 0
         assert_eq!(
             cc.candidates_at(&Square(0), &3),
-            Some(&vec![board.cell_at(1, 1)].into_iter().collect()),
+            Some(vec![board.cell_at(1, 1)].into_iter().collect()),
         );
 
         // setting a cell removes it as a possibility to all other values in its blocks
@@ -458,6 +1008,22 @@ mod tests {
             .contains(&board.cell_at(0, 0)));
     }
 
+    #[test]
+    fn remove_cell_value() {
+        let mut board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let mut cc = candidate_cache_from_board(&board);
+
+        for cell in board.iter_cells().collect::<Vec<_>>() {
+            if let Some(value) = board.unset(&cell) {
+                cc.remove_cell_value(&board, cell, value);
+                assert_eq!(cc, candidate_cache_from_board(&board));
+            }
+        }
+    }
+
     #[test]
     fn test_undo() {
         let board: Board = "
@@ -476,5 +1042,124 @@ mod tests {
         cc_clone.undo(undo);
 
         assert_eq!(cc, cc_clone);
+
+        // values are undone in the reverse order they were set
+        let first = cc_clone.set_value(1, board.cell_at(0, 0)).unwrap();
+        let after_first = cc_clone.clone();
+        let second = cc_clone.set_value(1, board.cell_at(1, 2)).unwrap();
+
+        cc_clone.undo(second);
+        assert_eq!(cc_clone, after_first);
+        cc_clone.undo(first);
+        assert_eq!(cc, cc_clone);
+
+        // eliminations are undone the same way
+        let undo = cc_clone
+            .eliminate(
+                &vec![(board.cell_at(0, 0), 1), (board.cell_at(3, 3), 2)]
+                    .into_iter()
+                    .collect(),
+            )
+            .unwrap();
+        assert!(!cc_clone
+            .candidates_at(&Square(0), &1)
+            .unwrap()
+            .contains(&board.cell_at(0, 0)));
+        cc_clone.undo_eliminate(undo);
+        assert_eq!(cc, cc_clone);
+
+        // eliminating the last candidate of a cell fails
+        let eliminations = (1..=4).map(|value| (board.cell_at(0, 0), value)).collect();
+        assert!(cc_clone.eliminate(&eliminations).is_err());
+        assert_eq!(cc, cc_clone);
+
+        // a failed set_value leaves the cache untouched
+        let mut cc = candidate_cache_from_board_str("12.. 3... .... ....");
+        let cc_clone = cc.clone();
+        assert!(cc
+            .set_value(4, CellLoc::at(2, 1, BoardSize::FourByFour))
+            .is_err());
+        assert_eq!(cc, cc_clone);
+    }
+
+    #[test]
+    fn verify_consistency() {
+        let mut board: Board =
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap();
+        let mut cc = candidate_cache_from_board(&board);
+        assert_eq!(cc.verify_consistency(&board), Ok(()));
+
+        let cell = CellLoc::at(0, 0, BoardSize::NineByNine);
+        cc.set_value(6, cell).unwrap();
+        board.set(&cell, 6);
+        assert_eq!(cc.verify_consistency(&board), Ok(()));
+        assert_eq!(cc.verify_placement(&board, cell, 6), Ok(()));
+
+        // a removed candidate is missing compared to a fresh cache, even if the cell
+        // and its blocks still agree with each other like after any elimination
+        let other = CellLoc::at(0, 1, BoardSize::NineByNine);
+        let mut corrupted = cc.clone();
+        corrupted.remove_candidate(&1, &other);
+        assert_eq!(
+            corrupted.verify_consistency(&board),
+            Err(ConsistencyError::MissingCandidate {
+                cell: other,
+                value: 1
+            })
+        );
+        assert_eq!(corrupted.verify_candidates(&board), Ok(()));
+
+        // but the cell can't be a candidate of its blocks for that value anymore
+        corrupted.add_candidate(&1, &other);
+        assert_eq!(
+            corrupted.verify_consistency(&board),
+            Err(ConsistencyError::BlockMismatch {
+                block: Block::Line(0),
+                value: 1,
+                cell: other
+            })
+        );
+
+        let mut corrupted = cc.clone();
+        corrupted.possible_values.get_mut(&other).unwrap().insert(6);
+        assert_eq!(
+            corrupted.verify_consistency(&board),
+            Err(ConsistencyError::StaleCandidate {
+                cell: other,
+                value: 6
+            })
+        );
+        assert_eq!(
+            corrupted.verify_placement(&board, cell, 6),
+            Err(ConsistencyError::StaleCandidate {
+                cell: other,
+                value: 6
+            })
+        );
+
+        // the board changes without the cache
+        let mut changed = board.clone();
+        changed.set(&other, 1);
+        assert_eq!(
+            cc.verify_consistency(&changed),
+            Err(ConsistencyError::FilledCell(other))
+        );
+        changed.unset(&cell);
+        assert_eq!(
+            cc.verify_consistency(&changed),
+            Err(ConsistencyError::MissingCell(cell))
+        );
+    }
+
+    #[test]
+    fn has_block_without_candidates() {
+        let board: Board = "12.. .... .... ....".parse().unwrap();
+        assert!(!CandidateCache::from_board(&board).has_block_without_candidates(&board));
+
+        // the 3 in the second line leaves no place for a 3 in the top right square
+        let board: Board = "..12 3... .... ....".parse().unwrap();
+        assert!(CandidateCache::from_board(&board).has_block_without_candidates(&board));
     }
 }
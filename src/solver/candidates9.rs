@@ -0,0 +1,137 @@
+use std::iter::FromIterator;
+
+/// A set of candidate values for a cell, stored as a bitmask where bit `i` set means
+/// value `i + 1` is a candidate.
+///
+/// Despite the name it holds values from 1 up to 16, enough for every board size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Candidates9(u16);
+
+impl Candidates9 {
+    /// Returns a set with every value from 1 to `max_value`.
+    pub fn all(max_value: u8) -> Self {
+        debug_assert!((1..=16).contains(&max_value));
+        Candidates9(u16::MAX >> (16 - max_value))
+    }
+
+    fn bit(value: u8) -> u16 {
+        debug_assert!((1..=16).contains(&value));
+        1 << (value - 1)
+    }
+
+    /// Adds a value to the set, returns true if it was not there before.
+    pub fn insert(&mut self, value: u8) -> bool {
+        let was_present = self.contains(value);
+        self.0 |= Self::bit(value);
+        !was_present
+    }
+
+    /// Removes a value from the set, returns true if it was there before.
+    pub fn remove(&mut self, value: u8) -> bool {
+        let was_present = self.contains(value);
+        self.0 &= !Self::bit(value);
+        was_present
+    }
+
+    pub fn contains(&self, value: u8) -> bool {
+        self.0 & Self::bit(value) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the values that are in both sets.
+    pub fn intersection(&self, other: &Candidates9) -> Candidates9 {
+        Candidates9(self.0 & other.0)
+    }
+
+    /// Iterates over the values in the set from the smallest to the biggest.
+    pub fn iter(&self) -> Iter {
+        Iter(self.0)
+    }
+}
+
+impl FromIterator<u8> for Candidates9 {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut candidates = Candidates9::default();
+        for value in iter {
+            candidates.insert(value);
+        }
+        candidates
+    }
+}
+
+impl IntoIterator for Candidates9 {
+    type Item = u8;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+pub struct Iter(u16);
+
+impl Iterator for Iter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let value = self.0.trailing_zeros() as u8 + 1;
+        // clear the lowest bit set
+        self.0 &= self.0 - 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+#[cfg(test)]
+mod tests {
+    use super::Candidates9;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut candidates = Candidates9::default();
+        assert!(candidates.is_empty());
+
+        assert!(candidates.insert(3));
+        assert!(!candidates.insert(3));
+        assert!(candidates.insert(9));
+        assert!(candidates.insert(16));
+        assert_eq!(candidates.len(), 3);
+        assert!(candidates.contains(3));
+        assert!(!candidates.contains(4));
+
+        assert!(candidates.remove(3));
+        assert!(!candidates.remove(3));
+        assert_eq!(candidates.iter().collect::<Vec<u8>>(), vec![9, 16]);
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!(
+            Candidates9::all(4).iter().collect::<Vec<u8>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(Candidates9::all(9).len(), 9);
+        assert_eq!(Candidates9::all(16).len(), 16);
+        assert_eq!(
+            vec![5, 1, 5].into_iter().collect::<Candidates9>(),
+            [1, 5].iter().copied().collect()
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Solves boards as an exact cover problem using Knuth's Algorithm X with dancing links.
+//!
+//! Every value in a cell is a row of the exact cover matrix, covering four constraints:
+//! the cell has a value, and its line, column and square have that value. A 9x9 board
+//! has 4 * 81 = 324 constraints, a solution picks rows that cover each of them exactly once.
+//! Cells in an extra region of the board's [`Variant`](crate::board::Variant) cover a
+//! fifth constraint, the region has that value.
+
+use super::UnsolvableError;
+use crate::board::{Board, CellLoc};
+
+/// A solver that uses the dancing links algorithm instead of the strategies and
+/// backtracking of [`SudokuSolver`](super::SudokuSolver), see [`Board::solve_dlx`].
+///
+/// ```
+/// use sudokugen::board::Board;
+/// use sudokugen::solver::dlx::SudokuSolverDlx;
+///
+/// let mut board: Board = "1... .... .... ....".parse().unwrap();
+/// SudokuSolverDlx::new(&mut board).solve().unwrap();
+///
+/// assert!(board.iter_cells().all(|cell| board.get(&cell).is_some()));
+/// ```
+#[derive(Debug)]
+pub struct SudokuSolverDlx<'a> {
+    board: &'a mut Board,
+    links: Links,
+}
+
+/// The exact cover matrix as circular doubly linked lists of nodes, stored as indexes
+/// into the vectors. Node 0 is the root, followed by a header node for each column.
+#[derive(Debug, Default)]
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    /// The cell and value of the row of each node after the headers.
+    rows: Vec<(CellLoc, u8)>,
+    /// Number of nodes in each column, by the index of its header node.
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new(columns: usize) -> Self {
+        let mut links = Links::default();
+        for idx in 0..=columns {
+            links.left.push(if idx == 0 { columns } else { idx - 1 });
+            links.right.push(if idx == columns { 0 } else { idx + 1 });
+            links.up.push(idx);
+            links.down.push(idx);
+            links.column.push(idx);
+            links.size.push(0);
+        }
+
+        links
+    }
+
+    /// Adds a row with a node in each of the columns, given by their header's index.
+    fn add_row(&mut self, row: (CellLoc, u8), columns: &[usize]) {
+        let first = self.left.len();
+
+        for (idx, &column) in columns.iter().enumerate() {
+            let node = first + idx;
+            self.left.push(if idx == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if idx == columns.len() - 1 {
+                first
+            } else {
+                node + 1
+            });
+
+            // insert at the bottom of the column
+            let last = self.up[column];
+            self.up.push(last);
+            self.down.push(column);
+            self.down[last] = node;
+            self.up[column] = node;
+
+            self.column.push(column);
+            self.rows.push(row);
+            self.size[column] += 1;
+        }
+    }
+
+    /// Returns the cell and value of the row a node is in.
+    fn row(&self, node: usize) -> (CellLoc, u8) {
+        self.rows[node - self.size.len()]
+    }
+
+    /// Removes a column from the header list and every row in it from the other columns.
+    fn cover(&mut self, column: usize) {
+        let (left, right) = (self.left[column], self.right[column]);
+        self.right[left] = right;
+        self.left[right] = left;
+
+        let mut row = self.down[column];
+        while row != column {
+            let mut node = self.right[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    /// The opposite of [`cover`](Self::cover), columns must be uncovered in the
+    /// reverse order they were covered.
+    fn uncover(&mut self, column: usize) {
+        let mut row = self.up[column];
+        while row != column {
+            let mut node = self.left[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                self.size[self.column[node]] += 1;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+
+        let (left, right) = (self.left[column], self.right[column]);
+        self.right[left] = column;
+        self.left[right] = column;
+    }
+
+    /// Searches for rows that cover every column left, pushing them to `solution`.
+    /// Returns false, leaving the matrix and `solution` unchanged, if there are none.
+    fn search(&mut self, solution: &mut Vec<usize>) -> bool {
+        // pick the column with the fewest rows to keep the search tree small
+        let mut column = self.right[0];
+        if column == 0 {
+            return true;
+        }
+
+        let mut header = self.right[column];
+        while header != 0 {
+            if self.size[header] < self.size[column] {
+                column = header;
+            }
+            header = self.right[header];
+        }
+
+        if self.size[column] == 0 {
+            return false;
+        }
+
+        self.cover(column);
+        let mut row = self.down[column];
+        while row != column {
+            solution.push(row);
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+
+            if self.search(solution) {
+                return true;
+            }
+
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+            solution.pop();
+            row = self.down[row];
+        }
+        self.uncover(column);
+
+        false
+    }
+}
+
+impl<'a> SudokuSolverDlx<'a> {
+    /// Creates a solver for `board`, the board is only changed by [`solve`](Self::solve).
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::dlx::SudokuSolverDlx;
+    ///
+    /// let mut board: Board = "1... .... .... ....".parse().unwrap();
+    /// let solver = SudokuSolverDlx::new(&mut board);
+    /// ```
+    pub fn new(board: &'a mut Board) -> Self {
+        SudokuSolverDlx {
+            board,
+            links: Links::default(),
+        }
+    }
+
+    /// Solves the board, same as [`Board::solve`]. If the board has no solution it's
+    /// left unchanged and [`UnsolvableError`] is returned.
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    /// use sudokugen::solver::dlx::SudokuSolverDlx;
+    ///
+    /// let mut board: Board = "123. ...4 .... ....".parse().unwrap();
+    /// assert!(SudokuSolverDlx::new(&mut board).solve().is_err());
+    /// ```
+    pub fn solve(&mut self) -> Result<(), UnsolvableError> {
+        self.build_links()?;
+
+        let mut solution = Vec::new();
+        if !self.links.search(&mut solution) {
+            return Err(UnsolvableError);
+        }
+
+        for node in solution {
+            let (cell, value) = self.links.row(node);
+            self.board.set(&cell, value);
+        }
+
+        Ok(())
+    }
+
+    /// Builds the exact cover matrix for the empty cells of the board, the constraints
+    /// already satisfied by the values in the board are left out.
+    fn build_links(&mut self) -> Result<(), UnsolvableError> {
+        let board_size = self.board.board_size();
+        let variant = self.board.variant();
+        let width = board_size.width();
+        let cells = board_size.cell_count();
+        let constraint_count = 4 * cells + variant.extra_block_count() * width;
+
+        // the constraints covered by a value in a cell, 0 based, the cell first and then
+        // each of its blocks, which are numbered in the same order as the cells
+        let constraints = |cell: &CellLoc, value: u8| -> Vec<usize> {
+            let value = usize::from(value) - 1;
+            std::iter::once(cell.get_index())
+                .chain(
+                    cell.iter_blocks(variant)
+                        .map(|block| cells + block.index(width) * width + value),
+                )
+                .collect()
+        };
+
+        let mut satisfied = vec![false; constraint_count];
+        for cell in self.board.iter_cells() {
+            if let Some(value) = self.board.get(&cell) {
+                for constraint in constraints(&cell, value) {
+                    if satisfied[constraint] {
+                        return Err(UnsolvableError);
+                    }
+                    satisfied[constraint] = true;
+                }
+            }
+        }
+
+        // header index of each constraint left, 0 for the satisfied ones
+        let mut headers = vec![0; constraint_count];
+        let mut columns = 0;
+        for (constraint, satisfied) in satisfied.iter().enumerate() {
+            if !satisfied {
+                columns += 1;
+                headers[constraint] = columns;
+            }
+        }
+
+        self.links = Links::new(columns);
+        for cell in self.board.iter_cells() {
+            if self.board.get(&cell).is_some() {
+                continue;
+            }
+
+            for value in 1..=board_size.max_value() {
+                let constraints = constraints(&cell, value);
+                if constraints.iter().all(|constraint| !satisfied[*constraint]) {
+                    let row_columns: Vec<usize> = constraints
+                        .iter()
+                        .map(|constraint| headers[*constraint])
+                        .collect();
+                    self.links.add_row((cell, value), &row_columns);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Board {
+    /// Solves the sudoku puzzle same as [`Board::solve`] using the dancing links algorithm,
+    /// see [`SudokuSolverDlx`].
+    ///
+    /// ```
+    /// use sudokugen::board::Board;
+    ///
+    /// let mut board: Board =
+    ///     ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21"
+    ///         .parse()
+    ///         .unwrap();
+    /// let solution = board.solved_copy().unwrap();
+    ///
+    /// board.solve_dlx().unwrap();
+    /// assert_eq!(board, solution);
+    /// ```
+    pub fn solve_dlx(&mut self) -> Result<(), UnsolvableError> {
+        SudokuSolverDlx::new(self).solve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardSize};
+    use crate::solver::UnsolvableError;
+
+    fn is_valid_solution(board: &Board, puzzle: &Board) -> bool {
+        board.iter_cells().all(|cell| {
+            let value = board.get(&cell);
+            value.is_some()
+                && (puzzle.get(&cell).is_none() || puzzle.get(&cell) == value)
+                && cell
+                    .iter_line()
+                    .chain(cell.iter_col())
+                    .chain(cell.iter_square())
+                    .filter(|other| *other != cell)
+                    .all(|other| board.get(&other) != value)
+        })
+    }
+
+    #[test]
+    fn solve_dlx() {
+        for puzzle in &[
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6..",
+            ".724..3........49.........2921...5.7..4.6...3......2...4..7.....3..196....5..4.21",
+            "1... .... .... ....",
+        ] {
+            let puzzle: Board = puzzle.parse().unwrap();
+            let mut board = puzzle.clone();
+            board.solve_dlx().unwrap();
+            assert!(is_valid_solution(&board, &puzzle));
+        }
+
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let puzzle = Board::new(board_size);
+            let mut board = puzzle.clone();
+            board.solve_dlx().unwrap();
+            assert!(is_valid_solution(&board, &puzzle));
+        }
+    }
+
+    #[test]
+    fn unsolvable() {
+        // conflicting givens
+        let mut board: Board = "11.. .... .... ....".parse().unwrap();
+        assert_eq!(board.solve_dlx(), Err(UnsolvableError));
+
+        // no conflicts, but the last cell of the first line can't hold any value
+        let mut board: Board = "123. ...4 .... ....".parse().unwrap();
+        let puzzle = board.clone();
+        assert_eq!(board.solve_dlx(), Err(UnsolvableError));
+        assert_eq!(board, puzzle);
+    }
+}
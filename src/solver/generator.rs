@@ -13,21 +13,228 @@
 //! [`board`]: struct.GenSudoku.html#method.board
 //! [`solution`]: struct.GenSudoku.html#method.solution
 
-use super::{MoveLog, Strategy, SudokuSolver};
-use crate::board::{Board, BoardSize, CellLoc};
+use super::candidate_cache::CandidateCache;
+use super::indexed_map::Map;
+use super::{
+    MoveLog, NoopObserver, SolveOptions, Solver, Strategy, StrategyConfig, SudokuSolver, TraceStep,
+    UnsolvableError, VerifyResult,
+};
+use crate::board::{Board, BoardSize, CellLoc, PermutationError, UnsupportedVariantError, Variant};
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error;
+use std::fmt;
 
 /// This structure represents a generated board and its solution
 ///
-/// This struct can only be created by calling the [`generate`] function, which will create
-/// a random board with a unique solution.
+/// This struct is usually created by calling the [`generate`](Puzzle::generate) function,
+/// which will create a random board with a unique solution. A puzzle can also be built
+/// from a known solution with [`from_solution`](Puzzle::from_solution), or read back with
+/// [`from_json_string`](Puzzle::from_json_string), which always produce a board with a
+/// unique solution as well.
 ///
-/// [`generate`]: ../fn.generate.html
+/// Puzzle doesn't implement [`Default`] since generating a puzzle is relatively expensive
+/// and should always be explicit.
+#[derive(Debug, Clone)]
 pub struct Puzzle {
     board: Board,
     solution: Board,
-    guesses: HashMap<CellLoc, BTreeSet<u8>>,
+    guesses: BTreeMap<CellLoc, BTreeSet<u8>>,
+}
+
+/// Options to customize the puzzles created by [`Puzzle::generate_with_options`].
+///
+/// ```
+/// use sudokugen::solver::generator::GeneratorOptions;
+///
+/// let options = GeneratorOptions {
+///     require_no_guessing: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    /// Only generate puzzles that can be solved without guessing, see
+    /// [`Board::solve_logical`]. Clues are added back to the minimal
+    /// puzzle until it can be solved by logic alone, so these puzzles tend to have
+    /// more clues than the default ones.
+    pub require_no_guessing: bool,
+    /// Check which clues can be removed from the board in parallel, several cells at a
+    /// time instead of one by one. Generates the same kind of minimal puzzles, but not the
+    /// same puzzle as the sequential removal for the same random number generator.
+    pub parallel_clue_removal: bool,
+    /// The order in which the generator tries to remove the clues of the full board, see
+    /// [`RemovalOrder`].
+    pub removal_order: RemovalOrder,
+}
+
+/// The order in which the generator tries to remove clues, set in
+/// [`GeneratorOptions::removal_order`].
+///
+/// Every clue that can be removed without making the solution ambiguous is removed, but
+/// removing one clue can make others necessary, so the cells tried first are the ones
+/// most likely to end up empty. All the orders generate minimal puzzles with a unique
+/// solution, they only change where their clues tend to be.
+///
+/// ```
+/// use sudokugen::{Puzzle, BoardSize};
+/// use sudokugen::solver::generator::{GeneratorOptions, RemovalOrder};
+///
+/// let options = GeneratorOptions {
+///     removal_order: RemovalOrder::CenterOut,
+///     ..Default::default()
+/// };
+/// let puzzle = Puzzle::generate_with_options(BoardSize::NineByNine, options);
+/// assert!(puzzle.is_solution_unique());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalOrder {
+    /// Cells are tried line by line, from the top left cell to the bottom right one. This
+    /// is the default, clues tend to be left towards the bottom of the board.
+    Sequential,
+    /// Cells are tried in a random order picked by the random number generator of the
+    /// generator, so seeded generators still generate the same puzzles.
+    Random,
+    /// Cells closer to the center of the board are tried first, clues tend to be left
+    /// near the borders.
+    CenterOut,
+    /// Cells closer to the borders of the board are tried first, clues tend to be left
+    /// near the center.
+    BorderIn,
+}
+
+impl Default for RemovalOrder {
+    fn default() -> Self {
+        Self::Sequential
+    }
+}
+
+impl RemovalOrder {
+    /// Sorts `cells` in this order, cells at the same distance of the center keep their
+    /// relative order.
+    fn arrange(self, cells: &mut [CellLoc], board_size: BoardSize, rng: &mut impl Rng) {
+        // distances are doubled to keep the center of boards of even width in a cell corner
+        let width = board_size.width();
+        let distance = |cell: &CellLoc| {
+            let l = 2 * cell.line() as isize - (width as isize - 1);
+            let c = 2 * cell.col() as isize - (width as isize - 1);
+            l * l + c * c
+        };
+
+        match self {
+            Self::Sequential => (),
+            Self::Random => cells.shuffle(rng),
+            Self::CenterOut => cells.sort_by_key(distance),
+            Self::BorderIn => cells.sort_by_key(|cell| std::cmp::Reverse(distance(cell))),
+        }
+    }
+}
+
+/// An endless iterator of new puzzles, see [`Puzzle::generator`].
+///
+/// The iterator can be sent to another thread, for instance to keep a queue of puzzles
+/// ready to be used.
+#[derive(Debug)]
+pub struct PuzzleGenerator {
+    board_size: BoardSize,
+    rng: ChaCha8Rng,
+    solver: Solver,
+}
+
+impl PuzzleGenerator {
+    fn new(board_size: BoardSize, rng: ChaCha8Rng) -> Self {
+        PuzzleGenerator {
+            board_size,
+            rng,
+            solver: Solver::with_capacity(board_size),
+        }
+    }
+}
+
+impl Iterator for PuzzleGenerator {
+    type Item = Puzzle;
+
+    fn next(&mut self) -> Option<Puzzle> {
+        let (puzzle, _) = Puzzle::generate_traced(
+            self.board_size,
+            Variant::Standard,
+            GeneratorOptions::default(),
+            &mut self.rng,
+            &mut self.solver,
+        );
+        Some(puzzle)
+    }
+}
+
+/// The clues removed from a solved board to generate a puzzle, see [`Puzzle::generate_with_trace`].
+///
+/// Only the removed clues are stored, the board at any step of the generation can be
+/// reconstructed with [`GenerationTrace::board_at`].
+#[derive(Debug, Clone)]
+pub struct GenerationTrace {
+    solution: Board,
+    removed: Vec<(CellLoc, u8)>,
+}
+
+impl GenerationTrace {
+    /// Returns the number of clues removed from the solution to generate the puzzle.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let (puzzle, trace) = Puzzle::generate_with_trace(BoardSize::FourByFour);
+    /// let clues = puzzle.board().iter_cells().filter(|cell| puzzle.board().get(cell).is_some()).count();
+    ///
+    /// assert_eq!(trace.steps(), 16 - clues);
+    /// ```
+    pub fn steps(&self) -> usize {
+        self.removed.len()
+    }
+
+    /// Returns the clues removed from the solution, in the order they were removed.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let (puzzle, trace) = Puzzle::generate_with_trace(BoardSize::FourByFour);
+    ///
+    /// for (cell, value) in trace.removed_clues() {
+    ///     assert_eq!(puzzle.board().get(cell), None);
+    ///     assert_eq!(puzzle.solution().get(cell), Some(*value));
+    /// }
+    /// ```
+    pub fn removed_clues(&self) -> &[(CellLoc, u8)] {
+        &self.removed
+    }
+
+    /// Returns the board after `step` clues were removed from the solution, or `None` if
+    /// `step` is bigger than the number of [`steps`]. Step 0 is the solution itself.
+    ///
+    /// [`steps`]: GenerationTrace::steps
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let (puzzle, trace) = Puzzle::generate_with_trace(BoardSize::NineByNine);
+    ///
+    /// assert_eq!(&trace.board_at(trace.steps()).unwrap(), puzzle.board());
+    /// assert_eq!(trace.board_at(trace.steps() + 1), None);
+    /// ```
+    pub fn board_at(&self, step: usize) -> Option<Board> {
+        if step > self.removed.len() {
+            return None;
+        }
+
+        let mut board = self.solution.clone();
+        for (cell, _) in &self.removed[..step] {
+            board.unset(cell);
+        }
+
+        Some(board)
+    }
 }
 
 impl Board {
@@ -47,6 +254,176 @@ impl Board {
     pub fn generate(board_size: BoardSize) -> Self {
         Puzzle::generate(board_size).board
     }
+
+    /// Generate a new 4x4 sudoku board with a unique solution, same as
+    /// `Board::generate(BoardSize::FourByFour)`.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board = Board::generate_4x4();
+    /// assert_eq!(board.board_size(), BoardSize::FourByFour);
+    /// ```
+    pub fn generate_4x4() -> Self {
+        Self::generate(BoardSize::FourByFour)
+    }
+
+    /// Generate a new 9x9 sudoku board with a unique solution, same as
+    /// `Board::generate(BoardSize::NineByNine)`.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board = Board::generate_9x9();
+    /// assert_eq!(board.board_size(), BoardSize::NineByNine);
+    /// ```
+    pub fn generate_9x9() -> Self {
+        Self::generate(BoardSize::NineByNine)
+    }
+
+    /// Generate a new 16x16 sudoku board with a unique solution, same as
+    /// `Board::generate(BoardSize::SixteenBySixteen)`.
+    ///
+    /// ```no_run
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let board = Board::generate_16x16();
+    /// assert_eq!(board.board_size(), BoardSize::SixteenBySixteen);
+    /// ```
+    pub fn generate_16x16() -> Self {
+        Self::generate(BoardSize::SixteenBySixteen)
+    }
+
+    /// Fills the squares on the diagonal of the board, squares 0, 4 and 8 in a 9x9 board,
+    /// with random permutations of all the values.
+    ///
+    /// These squares don't share any line or column, so they can be filled independently
+    /// without breaking any constraint as long as the rest of the board is empty. The
+    /// generator uses this as a starting point since it leaves the solver fewer
+    /// cells to guess. Keep in mind that on 4x4 boards the result doesn't always
+    /// have a solution.
+    ///
+    /// ```
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let mut board = Board::new(BoardSize::NineByNine);
+    /// board.fill_diagonal_boxes_randomly();
+    ///
+    /// assert_eq!(board.iter_cells().filter(|cell| board.get(cell).is_some()).count(), 27);
+    /// assert!(board.solve().is_ok());
+    /// ```
+    pub fn fill_diagonal_boxes_randomly(&mut self) {
+        self.fill_diagonal_boxes_with_rng(&mut rand::thread_rng());
+    }
+
+    /// Generates `count` distinct complete and valid boards, useful as a sample of
+    /// solutions for statistical experiments. The same `rng` state always returns the
+    /// same boards in the same order.
+    ///
+    /// Every new board is compared against the ones found so far and generated again if
+    /// it's a repeat. To avoid looping forever when `count` is larger than the number of
+    /// boards that exist, there are only 288 different 4x4 boards, this gives up and
+    /// returns [`PoolExhaustedError`] after [`MAX_POOL_COLLISIONS`] repeats in a row.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(3);
+    /// let pool = Board::random_full_pool(BoardSize::NineByNine, 5, &mut rng).unwrap();
+    ///
+    /// assert_eq!(pool.len(), 5);
+    /// assert!(pool.iter().all(|board| board.is_complete()));
+    ///
+    /// assert!(Board::random_full_pool(BoardSize::FourByFour, 300, &mut rng).is_err());
+    /// ```
+    pub fn random_full_pool(
+        board_size: BoardSize,
+        count: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<Board>, PoolExhaustedError> {
+        let mut solver = Solver::new();
+        let mut seen = BTreeSet::new();
+        let mut pool = Vec::with_capacity(count);
+        let mut collisions = 0;
+
+        while pool.len() < count {
+            let board = Board::random_full_grid(board_size, Variant::Standard, rng, &mut solver);
+            if seen.insert(board.to_line_string()) {
+                pool.push(board);
+                collisions = 0;
+            } else if collisions == MAX_POOL_COLLISIONS {
+                return Err(PoolExhaustedError {
+                    requested: count,
+                    found: pool.len(),
+                });
+            } else {
+                collisions += 1;
+            }
+        }
+
+        Ok(pool)
+    }
+
+    /// Generates a random complete board reusing the memory of `solver`, the moves used
+    /// to fill it are left in the solver's move log.
+    /// `variant` must be supported by `board_size`.
+    fn random_full_grid(
+        board_size: BoardSize,
+        variant: Variant,
+        rng: &mut impl Rng,
+        solver: &mut Solver,
+    ) -> Board {
+        // on 4x4 boards some fillings of the diagonal squares have no solution,
+        // in that case just try a different one
+        loop {
+            let mut board = Board::new(board_size);
+            board
+                .set_variant(variant)
+                .expect("The variant must be supported by the board size");
+            // the extra regions overlap the diagonal squares, values filled in them
+            // independently could repeat in a region
+            if variant == Variant::Standard {
+                board.fill_diagonal_boxes_with_rng(rng);
+            }
+
+            let mut filler = SudokuSolver::from_parts(
+                &mut board,
+                std::mem::take(&mut solver.candidate_cache),
+                std::mem::take(&mut solver.move_log),
+                NoopObserver,
+            );
+            filler.options = SolveOptions {
+                deterministic: false,
+                ..Default::default()
+            };
+            filler.rng = Some(ChaCha8Rng::from_seed(rng.gen()));
+
+            let solved = filler.solve().is_ok();
+            solver.candidate_cache = filler.candidate_cache;
+            solver.move_log = filler.move_log;
+            if solved {
+                return board;
+            }
+        }
+    }
+
+    fn fill_diagonal_boxes_with_rng(&mut self, rng: &mut impl Rng) {
+        let board_size = self.board_size();
+        let (box_rows, box_cols) = (board_size.box_rows(), board_size.box_cols());
+
+        // with rectangular boxes there are fewer bands or stacks than squares per band
+        for n in 0..box_rows.min(box_cols) {
+            let corner = self.cell_at(n * box_rows, n * box_cols);
+            let mut values: Vec<u8> = (1..=board_size.max_value()).collect();
+            values.shuffle(rng);
+
+            for (cell, value) in corner.iter_square().zip(values) {
+                self.set(&cell, value);
+            }
+        }
+    }
 }
 
 impl Puzzle {
@@ -67,145 +444,2290 @@ impl Puzzle {
     /// println!("{}", puzzle.solution());
     /// ```
     pub fn generate(board_size: BoardSize) -> Puzzle {
-        let mut board = Board::new(board_size);
-        let mut solver = SudokuSolver::new_random(&mut board);
-        solver
-            .solve()
-            .expect("Should always be possible to solve an empty board");
-
-        // dbg!(&solver.board.to_string());
-        let non_guesses = solver.move_log.iter().filter_map(|mov| match mov {
-            MoveLog::SetValue {
-                strategy: Strategy::Guess,
-                ..
-            } => None,
-            MoveLog::SetValue { cell, .. } => Some(cell),
-        });
+        Self::generate_with_options(board_size, GeneratorOptions::default())
+    }
 
-        // let mut board = solver.board;
+    /// Generate a new 4x4 sudoku puzzle with a unique solution, same as
+    /// `Puzzle::generate(BoardSize::FourByFour)`.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate_4x4();
+    /// assert_eq!(puzzle.board().board_size(), BoardSize::FourByFour);
+    /// ```
+    pub fn generate_4x4() -> Puzzle {
+        Self::generate(BoardSize::FourByFour)
+    }
 
-        // remove every cell generated without guessing
-        for cell in non_guesses {
-            board.unset(cell);
-        }
+    /// Generate a new 9x9 sudoku puzzle with a unique solution, same as
+    /// `Puzzle::generate(BoardSize::NineByNine)`.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate_9x9();
+    /// assert_eq!(puzzle.board().board_size(), BoardSize::NineByNine);
+    /// ```
+    pub fn generate_9x9() -> Puzzle {
+        Self::generate(BoardSize::NineByNine)
+    }
 
-        // let minimal_board = remove_false_guesses(board);
-        remove_false_guesses(&mut board);
-        let minimal_board = board;
+    /// Generate a new 16x16 sudoku puzzle with a unique solution, same as
+    /// `Puzzle::generate(BoardSize::SixteenBySixteen)`.
+    ///
+    /// ```no_run
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate_16x16();
+    /// assert_eq!(puzzle.board().board_size(), BoardSize::SixteenBySixteen);
+    /// ```
+    pub fn generate_16x16() -> Puzzle {
+        Self::generate(BoardSize::SixteenBySixteen)
+    }
 
-        let mut solved_board = minimal_board.clone();
-        let mut solver = SudokuSolver::new(&mut solved_board);
-        solver.solve().expect("A generated board must be solvable");
-        let givens: BTreeSet<CellLoc> = minimal_board
-            .iter_cells()
-            .filter(|cell| minimal_board.get(cell).is_some())
-            .collect();
-        let mut guesses = HashMap::new();
-        for mov in solver.move_log {
-            if let MoveLog::SetValue {
-                cell,
-                value,
-                strategy: Strategy::Guess,
-                undo_candidates,
-                ..
-            } = mov
-            {
-                if !givens.contains(&cell) {
-                    let mut options = undo_candidates
-                        .alternative_options()
-                        .as_ref()
-                        .unwrap()
-                        .to_owned();
-                    options.remove(&value);
+    /// Generate a new sudoku puzzle with a unique solution, customized by `options`.
+    ///
+    /// See [`Puzzle::generate`] for details on how puzzles are generated and
+    /// [`GeneratorOptions`] for the available options.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    /// use sudokugen::solver::generator::GeneratorOptions;
+    ///
+    /// let options = GeneratorOptions {
+    ///     require_no_guessing: true,
+    ///     ..Default::default()
+    /// };
+    /// let puzzle = Puzzle::generate_with_options(BoardSize::NineByNine, options);
+    ///
+    /// let mut board = puzzle.board().clone();
+    /// assert!(board.solve_logical().is_ok());
+    /// ```
+    pub fn generate_with_options(board_size: BoardSize, options: GeneratorOptions) -> Puzzle {
+        Self::generate_traced(
+            board_size,
+            Variant::Standard,
+            options,
+            &mut rand::thread_rng(),
+            &mut Solver::new(),
+        )
+        .0
+    }
 
-                    guesses.insert(cell, options);
-                }
-            }
-        }
+    /// Generate a new puzzle with a unique solution following the rules of `variant`, see
+    /// [`Variant`]. The puzzle and its solution have `variant` set.
+    ///
+    /// Returns [`UnsupportedVariantError`] if the variant can't be used with boards of
+    /// `board_size`.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    /// use sudokugen::board::Variant;
+    ///
+    /// let puzzle = Puzzle::generate_with_variant(BoardSize::NineByNine, Variant::Hyper).unwrap();
+    /// assert_eq!(puzzle.board().variant(), Variant::Hyper);
+    /// assert!(puzzle.is_solution_unique());
+    ///
+    /// assert!(Puzzle::generate_with_variant(BoardSize::FourByFour, Variant::Hyper).is_err());
+    /// ```
+    pub fn generate_with_variant(
+        board_size: BoardSize,
+        variant: Variant,
+    ) -> Result<Puzzle, UnsupportedVariantError> {
+        Board::new(board_size).set_variant(variant)?;
 
-        Self {
-            board: minimal_board,
-            solution: solved_board,
-            guesses,
-        }
+        Ok(Self::generate_traced(
+            board_size,
+            variant,
+            GeneratorOptions::default(),
+            &mut rand::thread_rng(),
+            &mut Solver::new(),
+        )
+        .0)
     }
-    /// Returns the minimal board generated
+
+    /// Generate a new minimal puzzle, same as [`Puzzle::generate`], with at least
+    /// `min_clues` filled cells, for instance to get less sparse puzzles for beginners.
+    ///
+    /// Puzzles are generated until one has enough clues, typical targets for 9x9 boards
+    /// are between 22 and 30. Keep in mind that minimal puzzles with many clues are rare,
+    /// so high values of `min_clues` can take a very long time and values that no minimal
+    /// puzzle can reach loop forever.
     ///
     /// ```
     /// use sudokugen::{Puzzle, BoardSize};
     ///
-    /// let gen = Puzzle::generate(BoardSize::NineByNine);
-    /// println!("{}", gen.board());
+    /// let puzzle = Puzzle::generate_with_min_clues(BoardSize::NineByNine, 24);
+    ///
+    /// assert!(puzzle.board().clue_count() >= 24);
     /// ```
-    pub fn board(&self) -> &Board {
-        &self.board
+    pub fn generate_with_min_clues(board_size: BoardSize, min_clues: usize) -> Puzzle {
+        Self::generator(board_size)
+            .find(|puzzle| puzzle.board().clue_count() >= min_clues)
+            .expect("the puzzle generator never ends")
     }
 
-    /// Returns the solution for the generated board
+    /// Generate a new minimal puzzle, same as [`Puzzle::generate`], with at most
+    /// `max_clues` filled cells, puzzles with fewer clues tend to be harder.
+    ///
+    /// Puzzles are generated until one has few enough clues, giving up after
+    /// [`MAX_CLUE_ATTEMPTS`] puzzles with [`ClueCountError`]. No 9x9 puzzle with a unique
+    /// solution has fewer than 17 clues, or 4x4 puzzle fewer than 4, so targets below
+    /// that fail right away. Minimal 9x9 puzzles usually have between 22 and 26 clues,
+    /// targets below 21 rarely succeed.
     ///
     /// ```
     /// use sudokugen::{Puzzle, BoardSize};
     ///
-    /// let gen = Puzzle::generate(BoardSize::NineByNine);
-    /// println!("{}", gen.solution());
+    /// let puzzle = Puzzle::generate_with_max_clues(BoardSize::NineByNine, 24).unwrap();
+    /// assert!(puzzle.board().clue_count() <= 24);
+    ///
+    /// assert!(Puzzle::generate_with_max_clues(BoardSize::NineByNine, 16).is_err());
     /// ```
-    pub fn solution(&self) -> &Board {
-        &self.solution
+    pub fn generate_with_max_clues(
+        board_size: BoardSize,
+        max_clues: usize,
+    ) -> Result<Puzzle, ClueCountError> {
+        Self::generate_with_clues_between(board_size, 0, max_clues)
     }
 
-    /// Verify that the solution for the generated board is unique.
+    /// Generate a new minimal puzzle, same as [`Puzzle::generate`], with `target` filled
+    /// cells give or take `tolerance`.
+    ///
+    /// Like [`Puzzle::generate_with_max_clues`] this gives up with [`ClueCountError`]
+    /// after [`MAX_CLUE_ATTEMPTS`] puzzles.
     ///
     /// ```
     /// use sudokugen::{Puzzle, BoardSize};
     ///
-    /// let gen = Puzzle::generate(BoardSize::NineByNine);
-    /// assert!(gen.is_solution_unique());
+    /// let puzzle = Puzzle::generate_with_clue_count(BoardSize::NineByNine, 24, 1).unwrap();
+    ///
+    /// assert!((23..=25).contains(&puzzle.board().clue_count()));
     /// ```
-    pub fn is_solution_unique(&self) -> bool {
-        for (cell, options) in self.guesses.iter() {
-            let has_other_solutions = options.par_iter().any(|value| {
-                let mut board = self.board.clone();
-                board.set(cell, *value);
-                board.solve().is_ok()
-            });
+    pub fn generate_with_clue_count(
+        board_size: BoardSize,
+        target: usize,
+        tolerance: usize,
+    ) -> Result<Puzzle, ClueCountError> {
+        Self::generate_with_clues_between(
+            board_size,
+            target.saturating_sub(tolerance),
+            target.saturating_add(tolerance),
+        )
+    }
 
-            if has_other_solutions {
-                return false;
-            }
+    fn generate_with_clues_between(
+        board_size: BoardSize,
+        min_clues: usize,
+        max_clues: usize,
+    ) -> Result<Puzzle, ClueCountError> {
+        let err = ClueCountError {
+            min_clues,
+            max_clues,
+        };
+        let fewest_clues = match board_size {
+            BoardSize::FourByFour => 4,
+            BoardSize::NineByNine => 17,
+            BoardSize::SixteenBySixteen | BoardSize::Custom { .. } => 0,
+        };
+        if max_clues < fewest_clues.max(min_clues) || min_clues > board_size.cell_count() {
+            return Err(err);
         }
 
-        true
+        Self::generator(board_size)
+            .take(MAX_CLUE_ATTEMPTS)
+            .find(|puzzle| (min_clues..=max_clues).contains(&puzzle.board().clue_count()))
+            .ok_or(err)
     }
-}
 
-fn remove_false_guesses(board: &mut Board) {
-    // let mut cur_board = board.clone();
+    /// Generate a new sudoku puzzle same as [`Puzzle::generate`], using `rng` as the
+    /// only source of randomness.
+    ///
+    /// Generation is deterministic, using a seeded `rng` always produces the same puzzle
+    /// for the same version of this crate.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate_with_rng(BoardSize::NineByNine, &mut StdRng::seed_from_u64(1));
+    /// let other = Puzzle::generate_with_rng(BoardSize::NineByNine, &mut StdRng::seed_from_u64(1));
+    ///
+    /// assert_eq!(puzzle, other);
+    /// ```
+    pub fn generate_with_rng(board_size: BoardSize, rng: &mut impl Rng) -> Puzzle {
+        Self::generate_traced(
+            board_size,
+            Variant::Standard,
+            GeneratorOptions::default(),
+            rng,
+            &mut Solver::new(),
+        )
+        .0
+    }
 
-    let cells: Vec<_> = board
-        .iter_cells()
-        .filter(|cell| board.get(cell).is_some())
-        .collect();
+    /// Generate a puzzle meant to be hard for a backtracking solver, to stress test
+    /// solvers. `effort` puzzles are generated, the same ones as calling
+    /// [`Puzzle::generate_with_rng`] `effort` times with `rng`, and the one with the
+    /// highest [`hardness_score`](Puzzle::hardness_score) is returned, the first one in
+    /// case of a tie. The scores are computed in parallel.
+    ///
+    /// An `effort` of 0 is treated as 1.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(4);
+    /// let hard = Puzzle::generate_hard(BoardSize::NineByNine, 4, &mut rng);
+    ///
+    /// let mut rng = StdRng::seed_from_u64(4);
+    /// let first = Puzzle::generate_with_rng(BoardSize::NineByNine, &mut rng);
+    /// assert!(hard.hardness_score() >= first.hardness_score());
+    /// assert!(hard.is_solution_unique());
+    /// ```
+    pub fn generate_hard(board_size: BoardSize, effort: u32, rng: &mut impl Rng) -> Puzzle {
+        let candidates: Vec<Puzzle> = (0..effort.max(1))
+            .map(|_| Self::generate_with_rng(board_size, rng))
+            .collect();
+        let scores: Vec<usize> = candidates.par_iter().map(Puzzle::hardness_score).collect();
 
-    for cell in cells {
-        // let mut board = cur_board.clone();
+        // max_by_key keeps the last of equal elements, reversing keeps the first one
+        let (idx, _) = scores
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, score)| **score)
+            .expect("there is at least one candidate");
+        candidates
+            .into_iter()
+            .nth(idx)
+            .expect("the index comes from the candidates")
+    }
 
-        // this unidiomatic and slightly fragile rust is necessary to avoid cloning
-        // the board on every loop run
-        let value = board.unset(&cell).expect("Guaranteed by the loop above");
-        let mut possible_values = cell
-            .get_possible_values(board)
-            .expect("Guaranteed to be Some by the for loop");
-        possible_values.remove(&value);
+    /// Generate the puzzle identified by `seed`, for instance a date to create a "puzzle of
+    /// the day" that is the same for every user without storing it anywhere.
+    ///
+    /// The string is hashed with 64 bit [FNV-1a] and the hash seeds the random number
+    /// generator used by [`Puzzle::generate_with_rng`]. The same seed and board size are
+    /// guaranteed to produce the same puzzle across releases with the same minor version
+    /// of this crate, but the puzzle may change between minor versions.
+    ///
+    /// [FNV-1a]: http://www.isthe.com/chongo/tech/comp/fnv/index.html
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let today = Puzzle::for_seed_string("2024-05-01", BoardSize::NineByNine);
+    ///
+    /// assert_eq!(today, Puzzle::for_seed_string("2024-05-01", BoardSize::NineByNine));
+    /// ```
+    pub fn for_seed_string(seed: &str, board_size: BoardSize) -> Puzzle {
+        let mut rng = ChaCha8Rng::seed_from_u64(fnv1a(seed.as_bytes()));
+        Self::generate_with_rng(board_size, &mut rng)
+    }
 
-        let is_guess = possible_values.par_iter().any(|other_value| {
-            let mut new_board = board.clone();
-            new_board.set(&cell, *other_value);
-            new_board.solve().is_ok()
-        });
+    /// Returns an endless iterator of new puzzles, same as calling [`Puzzle::generate`]
+    /// in a loop but reusing the memory of the solver between puzzles.
+    ///
+    /// ```
+    /// use sudokugen::{BoardSize, Puzzle};
+    ///
+    /// let puzzles: Vec<Puzzle> = Puzzle::generator(BoardSize::NineByNine)
+    ///     .filter(|puzzle| puzzle.board().clue_count() <= 26)
+    ///     .take(3)
+    ///     .collect();
+    ///
+    /// assert_eq!(puzzles.len(), 3);
+    /// ```
+    pub fn generator(board_size: BoardSize) -> PuzzleGenerator {
+        PuzzleGenerator::new(board_size, ChaCha8Rng::from_seed(rand::thread_rng().gen()))
+    }
 
-        if is_guess {
-            // board was solvable with a different value, this is a legitimate guess, reset it
-            board.set(&cell, value);
-        }
+    /// Returns an endless iterator of new puzzles same as [`Puzzle::generator`], the same
+    /// `seed` always generates the same puzzles in the same order.
+    ///
+    /// ```
+    /// use sudokugen::{BoardSize, Puzzle};
+    ///
+    /// let puzzles: Vec<Puzzle> = Puzzle::generator_with_seed(BoardSize::FourByFour, 3)
+    ///     .take(2)
+    ///     .collect();
+    ///
+    /// assert!(Puzzle::generator_with_seed(BoardSize::FourByFour, 3)
+    ///     .take(2)
+    ///     .eq(puzzles));
+    /// ```
+    pub fn generator_with_seed(board_size: BoardSize, seed: u64) -> PuzzleGenerator {
+        PuzzleGenerator::new(board_size, ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Returns an endless parallel iterator of new puzzles, same as calling
+    /// [`Puzzle::generate`] from every thread of the rayon thread pool, to generate large
+    /// collections of puzzles. Each thread reuses the memory of its solver between puzzles.
+    ///
+    /// The iterator never ends, use [`ParallelIterator::take_any`] or a similar adapter to
+    /// stop it. Puzzles are not returned in any particular order.
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    /// use sudokugen::{BoardSize, Puzzle};
+    ///
+    /// let puzzles: Vec<Puzzle> = Puzzle::par_generate(BoardSize::NineByNine)
+    ///     .take_any(8)
+    ///     .collect();
+    ///
+    /// assert_eq!(puzzles.len(), 8);
+    /// ```
+    pub fn par_generate(board_size: BoardSize) -> impl ParallelIterator<Item = Puzzle> {
+        // generating a puzzle also runs parallel iterators, rayon runs those in the same
+        // pool and the threads waiting on them steal other work, so nesting doesn't
+        // deadlock or start new threads
+        rayon::iter::repeat(()).map_init(Solver::new, move |solver, ()| {
+            Self::generate_traced(
+                board_size,
+                Variant::Standard,
+                GeneratorOptions::default(),
+                &mut rand::thread_rng(),
+                solver,
+            )
+            .0
+        })
+    }
+
+    /// Generate a new sudoku puzzle same as [`Puzzle::generate`], also returning a
+    /// [`GenerationTrace`] with every clue removed from the solution to reach the puzzle.
+    ///
+    /// The boards in between the solution and the puzzle all have the same unique solution
+    /// and can be used as easier versions of the puzzle.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let (puzzle, trace) = Puzzle::generate_with_trace(BoardSize::NineByNine);
+    ///
+    /// assert_eq!(&trace.board_at(0).unwrap(), puzzle.solution());
+    /// assert_eq!(&trace.board_at(trace.steps()).unwrap(), puzzle.board());
+    ///
+    /// // a version of the puzzle with 10 more clues
+    /// let easier = trace.board_at(trace.steps() - 10).unwrap();
+    /// ```
+    pub fn generate_with_trace(board_size: BoardSize) -> (Puzzle, GenerationTrace) {
+        Self::generate_traced(
+            board_size,
+            Variant::Standard,
+            GeneratorOptions::default(),
+            &mut rand::thread_rng(),
+            &mut Solver::new(),
+        )
+    }
+
+    /// Generates a puzzle reusing the memory of `solver` for the solver's structures.
+    fn generate_traced(
+        board_size: BoardSize,
+        variant: Variant,
+        options: GeneratorOptions,
+        rng: &mut impl Rng,
+        solver: &mut Solver,
+    ) -> (Puzzle, GenerationTrace) {
+        let mut board = Board::random_full_grid(board_size, variant, rng, solver);
+        let move_log = std::mem::take(&mut solver.move_log);
+
+        let full_board = board.clone();
+        let mut removed: Vec<(CellLoc, u8)> = move_log
+            .iter()
+            .filter_map(|mov| match mov {
+                MoveLog::SetValue {
+                    strategy: Strategy::Guess,
+                    ..
+                } => None,
+                MoveLog::SetValue { cell, value, .. } => Some((*cell, *value)),
+            })
+            .collect();
+        solver.move_log = move_log;
+
+        // remove every cell generated without guessing
+        let non_guesses: BTreeSet<CellLoc> = removed.iter().map(|(cell, _)| *cell).collect();
+        let keep = board
+            .iter_cells()
+            .filter(|cell| !non_guesses.contains(cell))
+            .collect();
+        board.retain_cells(&keep);
+
+        // kept up to date with the board from here on so it's only built once
+        let candidate_cache = &mut solver.candidate_cache;
+        candidate_cache.reset(&board);
+        let mut cells: Vec<CellLoc> = board
+            .iter_cells()
+            .filter(|cell| board.get(cell).is_some())
+            .collect();
+        options.removal_order.arrange(&mut cells, board_size, rng);
+        if options.parallel_clue_removal {
+            removed.extend(remove_false_guesses_parallel(
+                &mut board,
+                candidate_cache,
+                cells,
+            ));
+        } else {
+            removed.extend(remove_false_guesses_in_order(
+                &mut board,
+                candidate_cache,
+                cells,
+            ));
+        }
+
+        Self::from_minimal_board(full_board, board, candidate_cache, removed, options, rng)
+    }
+
+    /// Generate a minimal puzzle with a unique solution whose solution is `solution`.
+    ///
+    /// This is useful when the solution is chosen up front, for instance in instructional
+    /// materials. Returns [`UnsolvableError`] if `solution` is not a complete and valid board.
+    ///
+    /// [`UnsolvableError`]: ../struct.UnsolvableError.html
+    ///
+    /// ```
+    /// use sudokugen::{Board, Puzzle};
+    ///
+    /// let solution: Board = "1234 3412 2143 4321".parse().unwrap();
+    /// let puzzle = Puzzle::generate_from_solution(solution.clone()).unwrap();
+    ///
+    /// assert_eq!(puzzle.solution(), &solution);
+    /// assert!(puzzle.is_solution_unique());
+    ///
+    /// let incomplete: Board = "1234 3412 2143 432.".parse().unwrap();
+    /// assert!(Puzzle::generate_from_solution(incomplete).is_err());
+    /// ```
+    pub fn generate_from_solution(solution: Board) -> Result<Puzzle, UnsolvableError> {
+        Self::from_solution(&solution, &mut rand::thread_rng()).map_err(|_| UnsolvableError)
+    }
+
+    /// Generate a minimal puzzle with a unique solution whose solution is `solution`,
+    /// removing clues in an order picked by `rng`.
+    ///
+    /// Using a seeded `rng` always produces the same puzzle for the same solution.
+    /// Returns [`PuzzleError`] if `solution` is not a complete and valid board.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use sudokugen::{Board, Puzzle};
+    /// use sudokugen::solver::generator::PuzzleError;
+    ///
+    /// let solution: Board = "1234 3412 2143 4321".parse().unwrap();
+    /// let puzzle = Puzzle::from_solution(&solution, &mut StdRng::seed_from_u64(7)).unwrap();
+    ///
+    /// assert_eq!(puzzle.solution(), &solution);
+    /// assert_eq!(
+    ///     Puzzle::from_solution(&solution, &mut StdRng::seed_from_u64(7)).unwrap(),
+    ///     puzzle
+    /// );
+    ///
+    /// let incomplete: Board = "1234 3412 2143 432.".parse().unwrap();
+    /// assert_eq!(
+    ///     Puzzle::from_solution(&incomplete, &mut StdRng::seed_from_u64(7)),
+    ///     Err(PuzzleError::Incomplete)
+    /// );
+    /// ```
+    pub fn from_solution(solution: &Board, rng: &mut impl Rng) -> Result<Puzzle, PuzzleError> {
+        check_solution(solution)?;
+
+        let mut board = solution.clone();
+        let mut cells: Vec<CellLoc> = board.iter_cells().collect();
+        cells.shuffle(rng);
+        let mut candidate_cache = CandidateCache::from_board(&board);
+        let removed = remove_false_guesses_in_order(&mut board, &mut candidate_cache, cells);
+
+        Ok(Self::from_minimal_board(
+            solution.clone(),
+            board,
+            &mut candidate_cache,
+            removed,
+            GeneratorOptions::default(),
+            rng,
+        )
+        .0)
+    }
+
+    /// Builds the puzzle for `minimal_board`, `candidate_cache` must be up to date with it
+    /// and is left in any state.
+    fn from_minimal_board(
+        full_board: Board,
+        mut minimal_board: Board,
+        candidate_cache: &mut CandidateCache,
+        removed: Vec<(CellLoc, u8)>,
+        options: GeneratorOptions,
+        rng: &mut impl Rng,
+    ) -> (Puzzle, GenerationTrace) {
+        if options.require_no_guessing {
+            add_clues_until_logic_solvable(&mut minimal_board, rng);
+            candidate_cache.reset(&minimal_board);
+        }
+
+        let mut solved_board = minimal_board.clone();
+        let mut solver =
+            SudokuSolver::from_cache(&mut solved_board, std::mem::take(candidate_cache));
+        solver.solve().expect("A generated board must be solvable");
+        let givens: BTreeSet<CellLoc> = minimal_board
+            .iter_cells()
+            .filter(|cell| minimal_board.get(cell).is_some())
+            .collect();
+        let mut guesses = BTreeMap::new();
+        for mov in solver.move_log {
+            if let MoveLog::SetValue {
+                cell,
+                value,
+                strategy: Strategy::Guess,
+                undo_candidates,
+                ..
+            } = mov
+            {
+                if !givens.contains(&cell) {
+                    let options: BTreeSet<u8> = undo_candidates
+                        .alternative_options()
+                        .expect("Guesses are only made on empty cells")
+                        .iter()
+                        .filter(|option| *option != value)
+                        .collect();
+
+                    guesses.insert(cell, options);
+                }
+            }
+        }
+        *candidate_cache = solver.candidate_cache;
+
+        let trace = GenerationTrace {
+            solution: full_board,
+            removed,
+        };
+        let puzzle = Self {
+            board: minimal_board,
+            solution: solved_board,
+            guesses,
+        };
+
+        (puzzle, trace)
+    }
+    /// Returns the minimal board generated
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let gen = Puzzle::generate(BoardSize::NineByNine);
+    /// println!("{}", gen.board());
+    /// ```
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the solution for the generated board
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let gen = Puzzle::generate(BoardSize::NineByNine);
+    /// println!("{}", gen.solution());
+    /// ```
+    pub fn solution(&self) -> &Board {
+        &self.solution
+    }
+
+    /// Consumes the puzzle returning the board and the solution, in this order.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::FourByFour);
+    /// let (board, solution) = puzzle.clone().into_parts();
+    ///
+    /// assert_eq!(&board, puzzle.board());
+    /// assert_eq!(&solution, puzzle.solution());
+    /// ```
+    pub fn into_parts(self) -> (Board, Board) {
+        (self.board, self.solution)
+    }
+
+    /// Returns the board and the solution side by side, each line of the board followed
+    /// by `||` and the same line of the solution, in the format of the [`Board`] display.
+    ///
+    /// ```
+    /// use sudokugen::Puzzle;
+    ///
+    /// let puzzle = Puzzle::from_json_string(
+    ///     r#"{"board":".....123321.....","solution":"2341412332141432"}"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     puzzle.display_side_by_side(),
+    ///     "\
+    /// . . . . || 2 3 4 1
+    /// . 1 2 3 || 4 1 2 3
+    /// 3 2 1 . || 3 2 1 4
+    /// . . . . || 1 4 3 2
+    /// "
+    /// );
+    /// ```
+    pub fn display_side_by_side(&self) -> String {
+        let board = self.board.to_string();
+        let solution = self.solution.to_string();
+
+        board
+            .lines()
+            .zip(solution.lines())
+            .map(|(board_line, solution_line)| {
+                format!(
+                    "{} || {}\n",
+                    board_line.trim_end(),
+                    solution_line.trim_end()
+                )
+            })
+            .collect()
+    }
+
+    /// Serializes the puzzle as a JSON object with the board and the solution in the
+    /// single line notation of [`Board::to_line_string`].
+    ///
+    /// ```
+    /// use sudokugen::{Board, Puzzle};
+    ///
+    /// let solution: Board = "1243 3412 2134 4321".parse().unwrap();
+    /// let puzzle = Puzzle::generate_from_solution(solution).unwrap();
+    /// let json = puzzle.to_json_string();
+    ///
+    /// assert!(json.starts_with(r#"{"board":""#));
+    /// assert!(json.ends_with(r#"","solution":"1243341221344321"}"#));
+    /// ```
+    pub fn to_json_string(&self) -> String {
+        format!(
+            r#"{{"board":"{}","solution":"{}"}}"#,
+            self.board.to_line_string(),
+            self.solution.to_line_string()
+        )
+    }
+
+    /// Parses a puzzle written by [`to_json_string`](Self::to_json_string).
+    ///
+    /// Whitespace between the tokens and the keys in any order are accepted, but no other
+    /// keys or escape sequences. Returns [`PuzzleError::Malformed`] if the text is not in
+    /// this format, an error from [`Puzzle::from_solution`] if the solution is not complete
+    /// and valid, and [`PuzzleError::Mismatch`] if the solution is not the only solution
+    /// of the board.
+    ///
+    /// ```
+    /// use sudokugen::solver::generator::PuzzleError;
+    /// use sudokugen::{BoardSize, Puzzle};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::NineByNine);
+    /// let parsed = Puzzle::from_json_string(&puzzle.to_json_string()).unwrap();
+    /// assert_eq!(parsed, puzzle);
+    ///
+    /// assert_eq!(
+    ///     Puzzle::from_json_string(r#"{"board":"1234"}"#),
+    ///     Err(PuzzleError::Malformed)
+    /// );
+    /// assert_eq!(
+    ///     Puzzle::from_json_string(r#"{"board":"2...","solution":"1243341221344321"}"#),
+    ///     Err(PuzzleError::Malformed)
+    /// );
+    /// assert_eq!(
+    ///     Puzzle::from_json_string(
+    ///         r#"{"board":"2...............","solution":"1243341221344321"}"#
+    ///     ),
+    ///     Err(PuzzleError::Mismatch)
+    /// );
+    /// ```
+    pub fn from_json_string(s: &str) -> Result<Puzzle, PuzzleError> {
+        let fields = json_string_fields(s).ok_or(PuzzleError::Malformed)?;
+        let (board, solution) = match fields.as_slice() {
+            [("board", board), ("solution", solution)]
+            | [("solution", solution), ("board", board)] => (board, solution),
+            _ => return Err(PuzzleError::Malformed),
+        };
+        let board: Board = board.parse().map_err(|_| PuzzleError::Malformed)?;
+        let solution: Board = solution.parse().map_err(|_| PuzzleError::Malformed)?;
+
+        check_solution(&solution)?;
+        if !board.is_subset_of(&solution) {
+            return Err(PuzzleError::Mismatch);
+        }
+
+        let mut candidate_cache = CandidateCache::from_board(&board);
+        let (puzzle, _) = Self::from_minimal_board(
+            solution.clone(),
+            board,
+            &mut candidate_cache,
+            Vec::new(),
+            GeneratorOptions::default(),
+            &mut rand::thread_rng(),
+        );
+
+        if puzzle.solution != solution || !puzzle.is_solution_unique() {
+            return Err(PuzzleError::Mismatch);
+        }
+
+        Ok(puzzle)
+    }
+
+    /// Returns the cells where the solver had to guess while solving the minimal puzzle,
+    /// together with the alternative values it rejected for each cell, ordered by cell.
+    ///
+    /// These are the cells where the hard part of the puzzle lives, puzzles that can be
+    /// solved without guessing have none. The set of alternatives can be empty when every
+    /// other value was already ruled out while backtracking.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::NineByNine);
+    ///
+    /// for (cell, alternatives) in puzzle.ambiguous_cells() {
+    ///     assert_eq!(puzzle.board().get(&cell), None);
+    ///     assert!(!alternatives.contains(&puzzle.solution().get(&cell).unwrap()));
+    /// }
+    /// ```
+    pub fn ambiguous_cells(&self) -> impl Iterator<Item = (CellLoc, &BTreeSet<u8>)> {
+        self.guesses
+            .iter()
+            .map(|(cell, alternatives)| (*cell, alternatives))
+    }
+
+    /// Returns the number of alternative branches checked by [`Puzzle::is_solution_unique`],
+    /// one for each alternative value of each of the [`ambiguous_cells`].
+    ///
+    /// [`ambiguous_cells`]: Puzzle::ambiguous_cells
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::NineByNine);
+    ///
+    /// assert_eq!(
+    ///     puzzle.alternative_branch_count(),
+    ///     puzzle.ambiguous_cells().map(|(_, alternatives)| alternatives.len()).sum::<usize>()
+    /// );
+    /// ```
+    pub fn alternative_branch_count(&self) -> usize {
+        self.guesses.values().map(BTreeSet::len).sum()
+    }
+
+    /// Checks whether `attempt` correctly solves this puzzle, for instance a board filled
+    /// in by a user, see [`verify`](super::verify).
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    /// use sudokugen::solver::VerifyResult;
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::NineByNine);
+    ///
+    /// assert_eq!(puzzle.verify(puzzle.solution()), VerifyResult::Correct);
+    /// assert!(matches!(
+    ///     puzzle.verify(puzzle.board()),
+    ///     VerifyResult::Incomplete(_)
+    /// ));
+    /// ```
+    pub fn verify(&self, attempt: &Board) -> VerifyResult {
+        super::verify(&self.board, attempt)
+    }
+
+    /// Measures how hard the puzzle is for a backtracking solver, as the number of
+    /// guesses made while solving it plus the number of values removed while
+    /// backtracking from the wrong ones. Puzzles solvable without guessing score 0.
+    ///
+    /// The score is counted on the steps returned by [`Board::solve_traced`], which
+    /// solves with the default, deterministic [`SolveOptions`], so
+    /// it's the same every time and comparable between puzzles.
+    ///
+    /// ```
+    /// use sudokugen::{Board, Puzzle};
+    ///
+    /// let solution: Board = "1234 3412 2143 4321".parse().unwrap();
+    /// let puzzle = Puzzle::generate_from_solution(solution).unwrap();
+    ///
+    /// assert_eq!(puzzle.hardness_score(), puzzle.hardness_score());
+    /// ```
+    pub fn hardness_score(&self) -> usize {
+        let trace = self
+            .board
+            .clone()
+            .solve_traced()
+            .expect("a puzzle always has a solution");
+
+        trace
+            .iter()
+            .filter(|step| match step {
+                TraceStep::Place(step) => step.strategy() == Strategy::Guess,
+                TraceStep::Backtrack(_) => true,
+            })
+            .count()
+    }
+
+    /// Verify that the solution for the generated board is unique.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let gen = Puzzle::generate(BoardSize::NineByNine);
+    /// assert!(gen.is_solution_unique());
+    /// ```
+    pub fn is_solution_unique(&self) -> bool {
+        // every check starts from a copy of this cache instead of building a new one
+        let candidate_cache = CandidateCache::from_board(&self.board);
+
+        self.guesses.iter().all(|(cell, options)| {
+            !has_solution_with_any(
+                &self.board,
+                &candidate_cache,
+                cell,
+                options.iter().copied().collect(),
+            )
+        })
+    }
+
+    /// Returns this puzzle under each of the 8 rotations and reflections of the board,
+    /// starting with the puzzle itself. The solution and the [`ambiguous_cells`] of each
+    /// puzzle are moved the same way as its board, so they still match without solving
+    /// it again. Transformations that give the same board, for puzzles that are
+    /// symmetric, are only returned once.
+    ///
+    /// [`ambiguous_cells`]: Puzzle::ambiguous_cells
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::NineByNine);
+    /// let symmetries = puzzle.symmetries();
+    ///
+    /// assert_eq!(symmetries[0], puzzle);
+    /// assert_eq!(symmetries[1].board(), &puzzle.board().rotated());
+    /// assert_eq!(symmetries[1].solution(), &puzzle.solution().rotated());
+    /// ```
+    pub fn symmetries(&self) -> Vec<Puzzle> {
+        let mut puzzles: Vec<Puzzle> = Vec::with_capacity(8);
+
+        for mirrored in [false, true] {
+            for quarter_turns in 0..4 {
+                let puzzle = self.transformed(mirrored, quarter_turns);
+                if !puzzles.iter().any(|other| other.board == puzzle.board) {
+                    puzzles.push(puzzle);
+                }
+            }
+        }
+
+        puzzles
+    }
+
+    /// Same as [`Puzzle::symmetries`] with the values of the puzzle relabeled first,
+    /// every value `v` is replaced by `digit_perm[v - 1]`.
+    ///
+    /// Returns [`PermutationError::Digits`] if `digit_perm` is not a permutation of the
+    /// values of the board.
+    ///
+    /// ```
+    /// use sudokugen::{Puzzle, BoardSize};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::FourByFour);
+    /// let symmetries = puzzle.symmetries_with_digits(&[2, 1, 4, 3]).unwrap();
+    ///
+    /// let cell = puzzle.solution().cell_at(0, 0);
+    /// let value = puzzle.solution().get(&cell).unwrap();
+    /// assert_eq!(symmetries[0].solution().get(&cell), Some([2, 1, 4, 3][value as usize - 1]));
+    /// assert!(puzzle.symmetries_with_digits(&[1, 1, 2, 3]).is_err());
+    /// ```
+    pub fn symmetries_with_digits(
+        &self,
+        digit_perm: &[u8],
+    ) -> Result<Vec<Puzzle>, PermutationError> {
+        let identity: Vec<usize> = (0..self.board.board_size().width()).collect();
+        let relabel = |value: &u8| digit_perm[usize::from(*value) - 1];
+
+        let puzzle = Puzzle {
+            board: self
+                .board
+                .apply_permutation(&identity, &identity, digit_perm)?,
+            solution: self
+                .solution
+                .apply_permutation(&identity, &identity, digit_perm)?,
+            guesses: self
+                .guesses
+                .iter()
+                .map(|(cell, options)| (*cell, options.iter().map(relabel).collect()))
+                .collect(),
+        };
+
+        Ok(puzzle.symmetries())
+    }
+
+    /// Returns this puzzle mirrored horizontally if `mirrored` is set and then rotated
+    /// clockwise by 90deg `quarter_turns` times, see [`Board::rotated_n`].
+    fn transformed(&self, mirrored: bool, quarter_turns: u8) -> Puzzle {
+        let width = self.board.board_size().width();
+        let transform = |board: &Board| {
+            let mut board = board.clone();
+            if mirrored {
+                board.mirror_horizontal_in_place();
+            }
+            board.rotated_n(quarter_turns)
+        };
+
+        let board = transform(&self.board);
+        let guesses = self
+            .guesses
+            .iter()
+            .map(|(cell, options)| {
+                let (mut l, mut c) = (cell.line(), cell.col());
+                if mirrored {
+                    c = width - 1 - c;
+                }
+                for _ in 0..quarter_turns {
+                    let line = l;
+                    l = c;
+                    c = width - 1 - line;
+                }
+                (CellLoc::at(l, c, board.board_size()), options.clone())
+            })
+            .collect();
+
+        Puzzle {
+            solution: transform(&self.solution),
+            board,
+            guesses,
+        }
+    }
+
+    /// Rates the puzzle by the strategies needed to solve it, see [`Difficulty`].
+    ///
+    /// The puzzle is solved first with singles only, then with the pair eliminations and
+    /// finally guessing, so rating a hard puzzle takes about three times as long as
+    /// solving it.
+    ///
+    /// ```
+    /// use sudokugen::solver::generator::Difficulty;
+    /// use sudokugen::{BoardSize, Puzzle};
+    ///
+    /// let puzzle = Puzzle::generate(BoardSize::NineByNine);
+    ///
+    /// match puzzle.difficulty() {
+    ///     Difficulty::Easy => println!("solvable with singles only"),
+    ///     Difficulty::Medium => println!("needs candidate eliminations"),
+    ///     Difficulty::Hard => println!("needs guessing"),
+    /// }
+    /// ```
+    pub fn difficulty(&self) -> Difficulty {
+        let singles = StrategyConfig {
+            allow_guessing: false,
+            ..Default::default()
+        };
+        let eliminations = StrategyConfig {
+            naked_pairs: true,
+            pointing_pairs: true,
+            ..singles
+        };
+
+        if self.board.clone().solve_with_config(singles).is_ok() {
+            Difficulty::Easy
+        } else if self.board.clone().solve_with_config(eliminations).is_ok() {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Puzzle {
+    /// Same as [`Puzzle::generate`] but runs the generation on tokio's blocking thread
+    /// pool, so it doesn't block the async executor. Requires the `async` feature.
+    ///
+    /// ```
+    /// use sudokugen::{BoardSize, Puzzle};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let puzzle = Puzzle::generate_async(BoardSize::NineByNine).await;
+    ///
+    /// println!("{}", puzzle.board());
+    /// # });
+    /// ```
+    pub async fn generate_async(board_size: BoardSize) -> Puzzle {
+        tokio::task::spawn_blocking(move || Puzzle::generate(board_size))
+            .await
+            .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+    }
+}
+
+/// A rough rating of a puzzle by the hardest strategy needed to solve it, see
+/// [`Puzzle::difficulty`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// The puzzle can be solved placing naked and hidden singles only.
+    Easy,
+    /// The puzzle also needs naked pairs or pointing pairs to remove candidates.
+    Medium,
+    /// The puzzle can only be solved by guessing and backtracking.
+    Hard,
+}
+
+/// Puzzles are equal when both the board and the solution are equal.
+impl PartialEq for Puzzle {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board && self.solution == other.solution
+    }
+}
+
+impl Eq for Puzzle {}
+
+/// Displays the board followed by the solution, separated by an empty line.
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.board, self.solution)
+    }
+}
+
+/// Generates puzzles with [`Puzzle::generate_with_rng`] from a random seed, of the
+/// given size or either 4x4 or 9x9 when the size is `None`.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use sudokugen::{BoardSize, Puzzle};
+///
+/// proptest!(ProptestConfig::with_cases(4), |(puzzle in any_with::<Puzzle>(Some(BoardSize::FourByFour)))| {
+///     prop_assert!(puzzle.is_solution_unique());
+/// });
+/// ```
+#[cfg(feature = "arbitrary")]
+impl proptest::arbitrary::Arbitrary for Puzzle {
+    type Parameters = Option<BoardSize>;
+    type Strategy = proptest::strategy::BoxedStrategy<Puzzle>;
+
+    fn arbitrary_with(board_size: Option<BoardSize>) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        let board_size = match board_size {
+            Some(board_size) => Just(board_size).boxed(),
+            None => {
+                proptest::sample::select(vec![BoardSize::FourByFour, BoardSize::NineByNine]).boxed()
+            }
+        };
+
+        (board_size, any::<u64>())
+            .prop_map(|(board_size, seed)| {
+                Puzzle::generate_with_rng(board_size, &mut ChaCha8Rng::seed_from_u64(seed))
+            })
+            .boxed()
+    }
+}
+
+/// Strategy for boards that are always solvable, generated as the board of a [`Puzzle`]
+/// of the given size, see [`Puzzle`]'s `Arbitrary` implementation.
+///
+/// The boards shrink by removing clues, so they stay solvable but their solution may
+/// no longer be unique.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use sudokugen::solver::generator::solvable_boards;
+/// use sudokugen::BoardSize;
+///
+/// proptest!(ProptestConfig::with_cases(4), |(mut board in solvable_boards(Some(BoardSize::FourByFour)))| {
+///     prop_assert!(board.solve().is_ok());
+/// });
+/// ```
+#[cfg(feature = "arbitrary")]
+pub fn solvable_boards(board_size: Option<BoardSize>) -> proptest::strategy::BoxedStrategy<Board> {
+    use proptest::prelude::*;
+
+    any_with::<Puzzle>(board_size)
+        .prop_flat_map(|puzzle| {
+            let board = puzzle.into_parts().0;
+            let clues = board
+                .iter_cells()
+                .filter(|cell| board.get(cell).is_some())
+                .count();
+
+            // every clue is kept at first, shrinking turns them into false removing the clue
+            let keep = proptest::collection::vec(proptest::bool::weighted(1.0), clues);
+            (Just(board), keep)
+        })
+        .prop_map(|(mut board, keep)| {
+            let clues: Vec<CellLoc> = board
+                .iter_cells()
+                .filter(|cell| board.get(cell).is_some())
+                .collect();
+            for (cell, keep) in clues.iter().zip(keep) {
+                if !keep {
+                    board.unset(cell);
+                }
+            }
+            board
+        })
+        .boxed()
+}
+
+/// Error returned by [`Puzzle::from_solution`] when the given board is not a valid solution,
+/// and by [`Puzzle::from_json_string`] when the text can't be parsed into a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// Some of the cells in the board are empty.
+    Incomplete,
+    /// Some value is repeated in a line, column or square of the board.
+    Invalid,
+    /// The text is not in the format written by [`Puzzle::to_json_string`].
+    Malformed,
+    /// The solution is not the only solution of the board.
+    Mismatch,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Incomplete => write!(f, "The solution has empty cells"),
+            Self::Invalid => write!(f, "The solution repeats values in a line, column or square"),
+            Self::Malformed => write!(f, "The puzzle is not in the expected JSON format"),
+            Self::Mismatch => write!(f, "The solution is not the only solution of the board"),
+        }
+    }
+}
+
+impl error::Error for PuzzleError {}
+
+/// How many puzzles [`Puzzle::generate_with_max_clues`] and
+/// [`Puzzle::generate_with_clue_count`] generate before giving up.
+pub const MAX_CLUE_ATTEMPTS: usize = 10_000;
+
+/// Error returned when no puzzle with the requested number of clues was found, see
+/// [`Puzzle::generate_with_max_clues`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClueCountError {
+    min_clues: usize,
+    max_clues: usize,
+}
+
+impl fmt::Display for ClueCountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Could not generate a puzzle with between {} and {} clues",
+            self.min_clues, self.max_clues
+        )
+    }
+}
+
+impl error::Error for ClueCountError {}
+
+/// How many repeated boards in a row [`Board::random_full_pool`] generates before
+/// giving up on finding new ones.
+pub const MAX_POOL_COLLISIONS: usize = 1000;
+
+/// Error returned by [`Board::random_full_pool`] when it can't find as many distinct
+/// boards as requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolExhaustedError {
+    requested: usize,
+    found: usize,
+}
+
+impl PoolExhaustedError {
+    /// The number of boards requested.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let err = Board::random_full_pool(BoardSize::FourByFour, 1000, &mut rng).unwrap_err();
+    ///
+    /// assert_eq!(err.requested(), 1000);
+    /// ```
+    pub fn requested(&self) -> usize {
+        self.requested
+    }
+
+    /// The number of distinct boards found before giving up.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    /// use sudokugen::{Board, BoardSize};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let err = Board::random_full_pool(BoardSize::FourByFour, 1000, &mut rng).unwrap_err();
+    ///
+    /// assert!(err.found() <= 288);
+    /// ```
+    pub fn found(&self) -> usize {
+        self.found
+    }
+}
+
+impl fmt::Display for PoolExhaustedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Only found {} distinct boards out of the {} requested",
+            self.found, self.requested
+        )
+    }
+}
+
+impl error::Error for PoolExhaustedError {}
+
+/// 64 bit FNV-1a hash, used instead of the std hashers since their output is not
+/// guaranteed to be the same across Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Parses a flat JSON object whose values are all strings without escape sequences,
+/// returning its fields in order, or `None` if `s` is not such an object.
+fn json_string_fields(s: &str) -> Option<Vec<(&str, &str)>> {
+    fn string(s: &str) -> Option<(&str, &str)> {
+        let s = s.trim_start().strip_prefix('"')?;
+        let end = s.find(|c| c == '"' || c == '\\')?;
+        if s[end..].starts_with('\\') {
+            return None;
+        }
+
+        Some((&s[..end], &s[end + 1..]))
+    }
+
+    let mut rest = s.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut fields = Vec::new();
+    if rest.trim().is_empty() {
+        return Some(fields);
+    }
+
+    loop {
+        let (key, after_key) = string(rest)?;
+        let (value, after_value) = string(after_key.trim_start().strip_prefix(':')?)?;
+        fields.push((key, value));
+
+        let after_value = after_value.trim_start();
+        if after_value.is_empty() {
+            return Some(fields);
+        }
+        rest = after_value.strip_prefix(',')?;
+    }
+}
+
+/// Checks that every cell of the board is filled and no value is repeated in
+/// any line, column or square.
+fn check_solution(board: &Board) -> Result<(), PuzzleError> {
+    if board.iter_cells().any(|cell| board.get(&cell).is_none()) {
+        return Err(PuzzleError::Incomplete);
+    }
+
+    let mut board = board.clone();
+    for cell in board.iter_cells() {
+        let value = board.unset(&cell).expect("Guaranteed by the check above");
+        let is_valid = cell
+            .get_possible_values(&board)
+            .map_or(false, |values| values.contains(&value));
+        board.set(&cell, value);
+
+        if !is_valid {
+            return Err(PuzzleError::Invalid);
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes every clue that can be deduced from the remaining ones, trying the clues in
+/// the order of `cells`, and returns the removed clues in the order they were removed.
+/// `candidate_cache` must be up to date with `board` and is kept that way.
+fn remove_false_guesses_in_order(
+    board: &mut Board,
+    candidate_cache: &mut CandidateCache,
+    cells: Vec<CellLoc>,
+) -> Vec<(CellLoc, u8)> {
+    let mut removed = Vec::new();
+
+    for cell in cells {
+        let value = match board.unset(&cell) {
+            Some(value) => value,
+            None => continue,
+        };
+        candidate_cache.remove_cell_value(board, cell, value);
+
+        if has_other_solution(board, candidate_cache, &cell, value) {
+            // board was solvable with a different value, this is a legitimate guess, reset it
+            board.set(&cell, value);
+            candidate_cache
+                .set_value(value, cell)
+                .expect("Restoring a clue of a solvable board");
+            // the clue is never removed again, no need to keep its undo information
+            candidate_cache.clear_undo_log();
+        } else {
+            removed.push((cell, value));
+        }
+    }
+
+    removed
+}
+
+/// Same as [`remove_false_guesses_in_order`] but checks groups of cells in parallel.
+/// Cells in the same group don't share a line, column, square or extra region, the
+/// groups are filled in the order of `cells`.
+///
+/// Clues that can't be removed from the board stay that way when more clues are removed,
+/// so the whole group is checked against the same board and the clues that look
+/// removable are rechecked sequentially after the first one of the group is removed.
+fn remove_false_guesses_parallel(
+    board: &mut Board,
+    candidate_cache: &mut CandidateCache,
+    cells: Vec<CellLoc>,
+) -> Vec<(CellLoc, u8)> {
+    let mut removed = Vec::new();
+
+    for group in independent_groups(cells, board.variant()) {
+        let is_guess: Vec<bool> = group
+            .par_iter()
+            .map(|cell| {
+                let mut new_board = board.clone();
+                let value = new_board
+                    .unset(cell)
+                    .expect("Only filled cells are grouped");
+                let mut new_cache = candidate_cache.clone();
+                new_cache.remove_cell_value(&new_board, *cell, value);
+
+                has_other_solution(&new_board, &new_cache, cell, value)
+            })
+            .collect();
+
+        let mut board_changed = false;
+        for (cell, is_guess) in group.into_iter().zip(is_guess) {
+            if is_guess {
+                continue;
+            }
+
+            let value = board.unset(&cell).expect("Only filled cells are grouped");
+            candidate_cache.remove_cell_value(board, cell, value);
+
+            if board_changed && has_other_solution(board, candidate_cache, &cell, value) {
+                board.set(&cell, value);
+                candidate_cache
+                    .set_value(value, cell)
+                    .expect("Restoring a clue of a solvable board");
+                candidate_cache.clear_undo_log();
+            } else {
+                removed.push((cell, value));
+                board_changed = true;
+            }
+        }
+    }
+
+    removed
+}
+
+/// Greedily splits `cells` into groups where no two cells share a line, column, square
+/// or extra region of `variant`.
+fn independent_groups(cells: Vec<CellLoc>, variant: Variant) -> Vec<Vec<CellLoc>> {
+    let mut groups: Vec<Vec<CellLoc>> = Vec::new();
+
+    for cell in cells {
+        let conflicts = |other: &CellLoc| other.sees(&cell, variant);
+
+        match groups.iter_mut().find(|group| !group.iter().any(conflicts)) {
+            Some(group) => group.push(cell),
+            None => groups.push(vec![cell]),
+        }
+    }
+
+    groups
+}
+
+/// Returns true if the board, where `cell` was emptied, can still be solved with a
+/// value other than `value` in that cell. `candidate_cache` must be up to date with `board`.
+fn has_other_solution(
+    board: &Board,
+    candidate_cache: &CandidateCache,
+    cell: &CellLoc,
+    value: u8,
+) -> bool {
+    let other_values: Vec<u8> = candidate_cache
+        .possible_values()
+        .get(cell)
+        .expect("The cell must be empty")
+        .iter()
+        .filter(|other_value| *other_value != value)
+        .collect();
+
+    has_solution_with_any(board, candidate_cache, cell, other_values)
+}
+
+/// Returns true if the board can be solved with any of `values` in `cell`, which must
+/// be empty. `candidate_cache` must be up to date with `board`.
+fn has_solution_with_any(
+    board: &Board,
+    candidate_cache: &CandidateCache,
+    cell: &CellLoc,
+    values: Vec<u8>,
+) -> bool {
+    values.into_par_iter().any(|value| {
+        let mut new_cache = candidate_cache.clone();
+        if new_cache.set_value(value, *cell).is_err() {
+            return false;
+        }
+
+        let mut new_board = board.clone();
+        new_board.set(cell, value);
+        SudokuSolver::from_cache(&mut new_board, new_cache)
+            .solve()
+            .is_ok()
+    })
+}
+
+fn add_clues_until_logic_solvable(board: &mut Board, rng: &mut impl Rng) {
+    let mut solution = board.clone();
+    solution
+        .solve()
+        .expect("A generated board must be solvable");
+
+    loop {
+        let mut partial = board.clone();
+        if partial.solve_logical().is_ok() {
+            return;
+        }
+
+        // give away one of the cells the solver got stuck on, adding clues to a board
+        // with a unique solution keeps that solution unique
+        let cell = partial
+            .iter_cells()
+            .filter(|cell| partial.get(cell).is_none())
+            .choose(rng)
+            .expect("A stuck board must have empty cells");
+
+        board.set(
+            &cell,
+            solution
+                .get(&cell)
+                .expect("The solution must have all cells filled"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_solution, fnv1a, has_other_solution, independent_groups,
+        remove_false_guesses_parallel, CandidateCache, ClueCountError, Difficulty,
+        GeneratorOptions, PoolExhaustedError, Puzzle, PuzzleError, RemovalOrder, Solver,
+    };
+    use crate::board::Board;
+    use crate::board::BoardSize;
+    use crate::board::{PermutationError, Variant};
+    use crate::solver::{Block, Strategy, SudokuSolver};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use rayon::prelude::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn requires_guessing(puzzle: &Puzzle) -> bool {
+        let mut board = puzzle.board().clone();
+        let mut solver = SudokuSolver::new(&mut board);
+        solver.solve().unwrap();
+
+        solver
+            .move_log
+            .iter()
+            .any(|mov| mov.get_strategy() == Strategy::Guess)
+    }
+
+    #[test]
+    fn generate_with_min_clues() {
+        for (board_size, min_clues) in [
+            (BoardSize::FourByFour, 5),
+            (BoardSize::NineByNine, 22),
+            (BoardSize::NineByNine, 25),
+        ] {
+            let puzzle = Puzzle::generate_with_min_clues(board_size, min_clues);
+
+            assert!(puzzle.board().clue_count() >= min_clues);
+            assert!(puzzle.is_solution_unique());
+            assert!(puzzle.board().is_subset_of(puzzle.solution()));
+        }
+    }
+
+    #[test]
+    fn par_generate() {
+        let puzzles: Vec<Puzzle> = Puzzle::par_generate(BoardSize::NineByNine)
+            .take_any(16)
+            .collect();
+
+        assert_eq!(puzzles.len(), 16);
+        for puzzle in &puzzles {
+            assert!(puzzle.is_solution_unique());
+            assert!(puzzle.board().is_subset_of(puzzle.solution()));
+        }
+    }
+
+    #[test]
+    fn generate_sizes() {
+        for (board, board_size) in [
+            (Board::generate_4x4(), BoardSize::FourByFour),
+            (Board::generate_9x9(), BoardSize::NineByNine),
+        ] {
+            assert_eq!(board.board_size(), board_size);
+            assert_eq!(board.has_unique_solution(), Ok(true));
+        }
+
+        for (puzzle, board_size) in [
+            (Puzzle::generate_4x4(), BoardSize::FourByFour),
+            (Puzzle::generate_9x9(), BoardSize::NineByNine),
+        ] {
+            assert_eq!(puzzle.board().board_size(), board_size);
+            assert_eq!(puzzle.solution().board_size(), board_size);
+            assert!(puzzle.is_solution_unique());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        ignore = "generating 16x16 puzzles is slow without optimizations"
+    )]
+    fn generate_16x16() {
+        let board = Board::generate_16x16();
+        assert_eq!(board.board_size(), BoardSize::SixteenBySixteen);
+        assert_eq!(board.has_unique_solution(), Ok(true));
+
+        let puzzle = Puzzle::generate_16x16();
+        assert_eq!(puzzle.board().board_size(), BoardSize::SixteenBySixteen);
+        assert!(puzzle.is_solution_unique());
+    }
+
+    #[test]
+    fn symmetries() {
+        let puzzle = Puzzle::generate(BoardSize::NineByNine);
+        let symmetries = puzzle.symmetries();
+        assert_eq!(symmetries.len(), 8);
+        assert_eq!(symmetries[0], puzzle);
+
+        for symmetry in &symmetries {
+            assert_eq!(
+                symmetry.board().clone().into_solved(),
+                Ok(symmetry.solution().clone())
+            );
+            assert!(symmetry.is_solution_unique());
+            assert_eq!(
+                symmetry.alternative_branch_count(),
+                puzzle.alternative_branch_count()
+            );
+            for (cell, _) in symmetry.ambiguous_cells() {
+                assert_eq!(symmetry.board().get(&cell), None);
+            }
+        }
+        let boards: BTreeSet<String> = symmetries
+            .iter()
+            .map(|symmetry| symmetry.board().to_string())
+            .collect();
+        assert_eq!(boards.len(), 8);
+
+        // the board is the same when turned by 180deg
+        let puzzle = Puzzle::from_json_string(
+            r#"{"board":".....123321.....","solution":"2341412332141432"}"#,
+        )
+        .unwrap();
+        let symmetries = puzzle.symmetries();
+        assert_eq!(symmetries.len(), 4);
+        for symmetry in &symmetries {
+            assert_eq!(
+                symmetry.board().clone().into_solved(),
+                Ok(symmetry.solution().clone())
+            );
+        }
+
+        // boxes of custom sizes are turned as well
+        let puzzle = Puzzle::generate(BoardSize::custom(2, 3).unwrap());
+        for symmetry in puzzle.symmetries() {
+            assert_eq!(
+                symmetry.board().clone().into_solved(),
+                Ok(symmetry.solution().clone())
+            );
+            assert!(symmetry.is_solution_unique());
+        }
+
+        let symmetries = puzzle.symmetries_with_digits(&[6, 5, 4, 3, 2, 1]).unwrap();
+        for symmetry in &symmetries {
+            assert_eq!(
+                symmetry.board().clone().into_solved(),
+                Ok(symmetry.solution().clone())
+            );
+            assert!(symmetry.is_solution_unique());
+            for (cell, alternatives) in symmetry.ambiguous_cells() {
+                assert!(!alternatives.contains(&symmetry.solution().get(&cell).unwrap()));
+            }
+        }
+        assert_eq!(
+            puzzle.symmetries_with_digits(&[1, 2, 3]),
+            Err(PermutationError::Digits)
+        );
+    }
+
+    #[test]
+    fn generate_rectangular_boxes() {
+        for board_size in [
+            BoardSize::custom(2, 3).unwrap(),
+            BoardSize::custom(3, 2).unwrap(),
+            BoardSize::custom(3, 4).unwrap(),
+        ] {
+            let puzzle = Puzzle::generate(board_size);
+            assert_eq!(puzzle.board().board_size(), board_size);
+            assert!(puzzle.solution().is_complete());
+            assert!(puzzle.is_solution_unique());
+            assert!(puzzle.solution().validate_solution(puzzle.solution()));
+        }
+    }
+
+    #[test]
+    fn generate_with_variant() {
+        let puzzle = Puzzle::generate_with_variant(BoardSize::NineByNine, Variant::Hyper).unwrap();
+        assert_eq!(puzzle.board().variant(), Variant::Hyper);
+        assert_eq!(puzzle.solution().variant(), Variant::Hyper);
+        assert!(puzzle.is_solution_unique());
+        assert_eq!(puzzle.board().has_unique_solution(), Ok(true));
+
+        // every extra region of the solution has each value once
+        let solution = puzzle.solution();
+        let mut regions: BTreeMap<Block, BTreeSet<u8>> = BTreeMap::new();
+        for cell in solution.iter_cells() {
+            if let Some(block) = cell.extra_block(Variant::Hyper) {
+                regions
+                    .entry(block)
+                    .or_default()
+                    .insert(solution.get(&cell).unwrap());
+            }
+        }
+        assert_eq!(regions.len(), 4);
+        assert!(regions.values().all(|values| values.len() == 9));
+
+        // relabeled and turned puzzles are still hyper sudokus with the same solutions
+        let symmetries = puzzle
+            .symmetries_with_digits(&[9, 8, 7, 6, 5, 4, 3, 2, 1])
+            .unwrap();
+        for symmetry in &symmetries {
+            assert_eq!(symmetry.board().variant(), Variant::Hyper);
+            assert_eq!(symmetry.solution().variant(), Variant::Hyper);
+            assert_eq!(
+                symmetry.board().clone().into_solved(),
+                Ok(symmetry.solution().clone())
+            );
+        }
+
+        let err = Puzzle::generate_with_variant(BoardSize::FourByFour, Variant::Hyper).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Hyper sudoku is not supported on 4x4 boards"
+        );
+    }
+
+    #[test]
+    fn generate_with_clue_limits() {
+        for max_clues in [6, 8] {
+            let puzzle = Puzzle::generate_with_max_clues(BoardSize::FourByFour, max_clues).unwrap();
+            assert!(puzzle.board().clue_count() <= max_clues);
+            assert!(puzzle.is_solution_unique());
+        }
+        let puzzle = Puzzle::generate_with_max_clues(BoardSize::NineByNine, 23).unwrap();
+        assert!(puzzle.board().clue_count() <= 23);
+
+        for (target, tolerance) in [(24, 0), (25, 2), (1, 30)] {
+            let puzzle =
+                Puzzle::generate_with_clue_count(BoardSize::NineByNine, target, tolerance).unwrap();
+            let clues = puzzle.board().clue_count();
+            assert!(clues + tolerance >= target && clues <= target + tolerance);
+        }
+
+        // below the fewest clues a puzzle with a unique solution can have
+        let err = Puzzle::generate_with_max_clues(BoardSize::NineByNine, 16).unwrap_err();
+        assert_eq!(
+            err,
+            ClueCountError {
+                min_clues: 0,
+                max_clues: 16
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Could not generate a puzzle with between 0 and 16 clues"
+        );
+        assert!(Puzzle::generate_with_max_clues(BoardSize::FourByFour, 3).is_err());
+        assert!(Puzzle::generate_with_clue_count(BoardSize::FourByFour, 30, 10).is_err());
+    }
+
+    #[test]
+    fn generate_hard() {
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let hard = Puzzle::generate_hard(BoardSize::NineByNine, 8, &mut rng);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let batch: Vec<Puzzle> = (0..8)
+            .map(|_| Puzzle::generate_with_rng(BoardSize::NineByNine, &mut rng))
+            .collect();
+        let mut scores: Vec<usize> = batch.iter().map(Puzzle::hardness_score).collect();
+        scores.sort_unstable();
+
+        assert!(batch.contains(&hard));
+        assert!(hard.hardness_score() >= scores[scores.len() / 2]);
+        assert_eq!(hard.hardness_score(), scores[scores.len() - 1]);
+        assert!(hard.is_solution_unique());
+
+        // logic alone solves it, so the solver never guesses
+        assert_eq!(rated_puzzle("12.4 3412 2143 4.21").hardness_score(), 0);
+        assert!(
+            rated_puzzle(
+                "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+            )
+            .hardness_score()
+                > 100
+        );
+
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        assert_eq!(
+            Puzzle::generate_hard(BoardSize::FourByFour, 0, &mut rng),
+            Puzzle::generate_with_rng(BoardSize::FourByFour, &mut ChaCha8Rng::seed_from_u64(8))
+        );
+    }
+
+    #[test]
+    fn random_full_pool() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let pool = Board::random_full_pool(BoardSize::FourByFour, 20, &mut rng).unwrap();
+
+        assert_eq!(pool.len(), 20);
+        for (idx, board) in pool.iter().enumerate() {
+            assert!(board.is_complete());
+            assert_eq!(check_solution(board), Ok(()));
+            assert!(pool[..idx].iter().all(|other| other != board));
+        }
+
+        // the same seed gives the same pool
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        assert_eq!(
+            Board::random_full_pool(BoardSize::FourByFour, 20, &mut rng),
+            Ok(pool)
+        );
+
+        // there are only 288 4x4 boards
+        let err = Board::random_full_pool(BoardSize::FourByFour, 1000, &mut rng).unwrap_err();
+        assert_eq!(
+            err,
+            PoolExhaustedError {
+                requested: 1000,
+                found: err.found()
+            }
+        );
+        assert!(err.found() <= 288);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Only found {} distinct boards out of the 1000 requested",
+                err.found()
+            )
+        );
+
+        let pool = Board::random_full_pool(BoardSize::NineByNine, 3, &mut rng).unwrap();
+        assert!(pool.iter().all(|board| check_solution(board).is_ok()));
+        assert!(pool[0] != pool[1] && pool[1] != pool[2] && pool[0] != pool[2]);
+    }
+
+    #[test]
+    fn generate_without_guessing() {
+        let options = GeneratorOptions {
+            require_no_guessing: true,
+            ..Default::default()
+        };
+
+        for _ in 0..3 {
+            let puzzle = Puzzle::generate_with_options(BoardSize::NineByNine, options.clone());
+
+            assert!(!requires_guessing(&puzzle));
+            assert!(puzzle.is_solution_unique());
+        }
+    }
+
+    #[test]
+    fn generate_with_guessing() {
+        assert!((0..20).any(|_| requires_guessing(&Puzzle::generate(BoardSize::NineByNine))));
+    }
+
+    #[test]
+    fn ambiguous_cells() {
+        let puzzle = (0..20)
+            .map(|_| Puzzle::generate(BoardSize::NineByNine))
+            .find(requires_guessing)
+            .unwrap();
+        assert!(puzzle.ambiguous_cells().next().is_some());
+
+        let options = GeneratorOptions {
+            require_no_guessing: true,
+            ..Default::default()
+        };
+        let puzzle = Puzzle::generate_with_options(BoardSize::NineByNine, options);
+        assert_eq!(puzzle.ambiguous_cells().count(), 0);
+        assert_eq!(puzzle.alternative_branch_count(), 0);
+    }
+
+    #[test]
+    fn generation_trace() {
+        fn clues(board: &Board) -> usize {
+            board
+                .iter_cells()
+                .filter(|cell| board.get(cell).is_some())
+                .count()
+        }
+
+        let (puzzle, trace) = Puzzle::generate_with_trace(BoardSize::NineByNine);
+
+        let first = trace.board_at(0).unwrap();
+        assert_eq!(&first, puzzle.solution());
+        assert_eq!(clues(&first), 81);
+
+        let last = trace.board_at(trace.steps()).unwrap();
+        assert_eq!(&last, puzzle.board());
+        assert_eq!(clues(&last), 81 - trace.steps());
+
+        let clue_counts: Vec<usize> = (0..=trace.steps())
+            .map(|step| clues(&trace.board_at(step).unwrap()))
+            .collect();
+        assert!(clue_counts.windows(2).all(|pair| pair[0] > pair[1]));
+
+        let mut middle = trace.board_at(trace.steps() / 2).unwrap();
+        middle.solve().unwrap();
+        assert_eq!(&middle, puzzle.solution());
+    }
+
+    #[test]
+    fn clone_equality() {
+        let puzzle = Puzzle::generate(BoardSize::FourByFour);
+        let other = puzzle.clone();
+
+        assert_eq!(puzzle, other);
+        assert_ne!(puzzle, Puzzle::generate(BoardSize::NineByNine));
+    }
+
+    #[test]
+    fn into_parts() {
+        let puzzle = Puzzle::generate(BoardSize::NineByNine);
+        let (board, solution) = puzzle.clone().into_parts();
+
+        assert_eq!(&board, puzzle.board());
+        assert_eq!(&solution, puzzle.solution());
+    }
+
+    #[test]
+    fn display() {
+        let puzzle = Puzzle::generate(BoardSize::FourByFour);
+
+        assert_eq!(
+            puzzle.to_string(),
+            format!("{}\n{}", puzzle.board(), puzzle.solution())
+        );
+        assert_eq!(puzzle.to_string().lines().count(), 4 + 1 + 4);
+    }
+
+    #[test]
+    fn display_side_by_side() {
+        let puzzle = Puzzle::from_json_string(
+            r#"{
+                "board": "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..",
+                "solution": "483921657967345821251876493548132976729564138136798245372689514814253769695417382"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            puzzle.display_side_by_side(),
+            "\
+. . 3 . 2 . 6 . . || 4 8 3 9 2 1 6 5 7
+9 . . 3 . 5 . . 1 || 9 6 7 3 4 5 8 2 1
+. . 1 8 . 6 4 . . || 2 5 1 8 7 6 4 9 3
+. . 8 1 . 2 9 . . || 5 4 8 1 3 2 9 7 6
+7 . . . . . . . 8 || 7 2 9 5 6 4 1 3 8
+. . 6 7 . 8 2 . . || 1 3 6 7 9 8 2 4 5
+. . 2 6 . 9 5 . . || 3 7 2 6 8 9 5 1 4
+8 . . 2 . 3 . . 9 || 8 1 4 2 5 3 7 6 9
+. . 5 . 1 . 3 . . || 6 9 5 4 1 7 3 8 2
+"
+        );
+
+        // values of 16x16 boards are written as letters, so the lines still line up
+        let mut solution = Board::new(BoardSize::SixteenBySixteen);
+        solution.fill_diagonal_boxes_randomly();
+        let solution = solution.into_solved().unwrap();
+        let mut board = solution.clone();
+        board.unset(&board.cell_at(0, 0));
+        let json = format!(
+            r#"{{"board":"{}","solution":"{}"}}"#,
+            board.to_line_string(),
+            solution.to_line_string()
+        );
+        let puzzle = Puzzle::from_json_string(&json).unwrap();
+
+        let text = puzzle.display_side_by_side();
+        assert_eq!(text.lines().count(), 16);
+        assert!(text
+            .lines()
+            .all(|line| line.len() == 2 * 16 - 1 + 4 + 2 * 16 - 1));
+        for (line, solution_line) in text.lines().zip(solution.to_string().lines()) {
+            assert!(line.ends_with(&format!(" || {}", solution_line.trim_end())));
+        }
+        assert!(text.starts_with(". "));
+    }
+
+    #[test]
+    fn generate_from_solution() {
+        let solution: Board =
+            "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+                .parse()
+                .unwrap();
+        assert_eq!(check_solution(&solution), Ok(()));
+
+        let puzzle = Puzzle::generate_from_solution(solution.clone()).unwrap();
+        assert_eq!(puzzle.solution(), &solution);
+        assert!(puzzle.is_solution_unique());
+
+        let mut board = puzzle.board().clone();
+        board.solve().unwrap();
+        assert_eq!(board, solution);
+
+        let mut invalid = solution.clone();
+        invalid.set_at(0, 0, 4);
+        assert_eq!(check_solution(&invalid), Err(PuzzleError::Invalid));
+        assert!(Puzzle::generate_from_solution(invalid).is_err());
+
+        let mut incomplete = solution;
+        incomplete.set_at(4, 4, 1);
+        incomplete.unset(&incomplete.cell_at(8, 8));
+        assert_eq!(check_solution(&incomplete), Err(PuzzleError::Incomplete));
+    }
+
+    #[test]
+    fn seed_string() {
+        // reference values for 64 bit FNV-1a
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let puzzle = Puzzle::for_seed_string("2024-05-01", BoardSize::NineByNine);
+
+        assert_eq!(
+            puzzle,
+            Puzzle::for_seed_string("2024-05-01", BoardSize::NineByNine)
+        );
+        assert_ne!(
+            puzzle,
+            Puzzle::for_seed_string("2024-05-02", BoardSize::NineByNine)
+        );
+        assert!(puzzle.is_solution_unique());
+    }
+
+    #[test]
+    fn generator() {
+        for puzzle in Puzzle::generator(BoardSize::NineByNine).take(5) {
+            assert!(puzzle.is_solution_unique());
+            assert!(puzzle.board().has_unique_solution().unwrap());
+        }
+
+        // reusing the solver doesn't change the puzzles generated by the rng
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let expected: Vec<Puzzle> = (0..5)
+            .map(|_| Puzzle::generate_with_rng(BoardSize::NineByNine, &mut rng))
+            .collect();
+        let seeded: Vec<Puzzle> = Puzzle::generator_with_seed(BoardSize::NineByNine, 5)
+            .take(5)
+            .collect();
+        assert_eq!(seeded, expected);
+
+        // can be moved into a worker thread
+        let worker = std::thread::spawn({
+            let generator = Puzzle::generator_with_seed(BoardSize::NineByNine, 5);
+            move || generator.take(5).collect::<Vec<Puzzle>>()
+        });
+        assert_eq!(worker.join().unwrap(), expected);
+    }
+
+    #[test]
+    fn fill_diagonal_boxes() {
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let mut board = Board::new(board_size);
+            board.fill_diagonal_boxes_randomly();
+
+            for cell in board.iter_cells() {
+                let on_diagonal = cell.line() / board_size.get_base_size()
+                    == cell.col() / board_size.get_base_size();
+                assert_eq!(board.get(&cell).is_some(), on_diagonal);
+            }
+
+            if board_size != BoardSize::FourByFour {
+                assert_eq!(check_solution(&board.into_solved().unwrap()), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn removal_order() {
+        // mean distance of the clues to the center of the board, over 50 puzzles
+        let mean_distance = |removal_order: RemovalOrder| {
+            let distances: Vec<f64> = (0..50u64)
+                .into_par_iter()
+                .flat_map_iter(|seed| {
+                    let options = GeneratorOptions {
+                        removal_order,
+                        ..Default::default()
+                    };
+                    let (puzzle, _) = Puzzle::generate_traced(
+                        BoardSize::NineByNine,
+                        Variant::Standard,
+                        options,
+                        &mut ChaCha8Rng::seed_from_u64(seed),
+                        &mut Solver::new(),
+                    );
+                    assert!(puzzle.is_solution_unique());
+
+                    // every clue is still necessary for the solution to be unique
+                    let board = puzzle.board().clone();
+                    if seed == 0 {
+                        for cell in board.iter_cells() {
+                            let mut new_board = board.clone();
+                            if let Some(value) = new_board.unset(&cell) {
+                                let candidate_cache = CandidateCache::from_board(&new_board);
+                                assert!(has_other_solution(
+                                    &new_board,
+                                    &candidate_cache,
+                                    &cell,
+                                    value
+                                ));
+                            }
+                        }
+                    }
+
+                    board
+                        .iter_cells()
+                        .filter(|cell| board.get(cell).is_some())
+                        .map(|cell| {
+                            let l = cell.line() as f64 - 4.0;
+                            let c = cell.col() as f64 - 4.0;
+                            (l * l + c * c).sqrt()
+                        })
+                        .collect::<Vec<f64>>()
+                })
+                .collect();
+            distances.iter().sum::<f64>() / distances.len() as f64
+        };
+
+        // clues are left where the removal ends
+        let random = mean_distance(RemovalOrder::Random);
+        assert!(mean_distance(RemovalOrder::CenterOut) > random);
+        assert!(mean_distance(RemovalOrder::BorderIn) < random);
+    }
+
+    #[test]
+    fn parallel_clue_removal() {
+        let solution = Puzzle::generate(BoardSize::NineByNine).solution().clone();
+
+        let groups = independent_groups(solution.iter_cells().collect(), Variant::Standard);
+        assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), 81);
+        for group in &groups {
+            for (idx, cell) in group.iter().enumerate() {
+                assert!(group[idx + 1..].iter().all(|other| {
+                    other.line() != cell.line()
+                        && other.col() != cell.col()
+                        && other.square() != cell.square()
+                }));
+            }
+        }
+
+        let mut board = solution.clone();
+        let mut candidate_cache = CandidateCache::from_board(&board);
+        let cells = board.iter_cells().collect();
+        let removed = remove_false_guesses_parallel(&mut board, &mut candidate_cache, cells);
+        assert_eq!(candidate_cache, CandidateCache::from_board(&board));
+        assert_eq!(
+            board
+                .iter_cells()
+                .filter(|cell| board.get(cell).is_some())
+                .count(),
+            81 - removed.len()
+        );
+
+        // every remaining clue is necessary for the solution to be unique
+        for cell in board.iter_cells() {
+            let mut new_board = board.clone();
+            if let Some(value) = new_board.unset(&cell) {
+                let candidate_cache = CandidateCache::from_board(&new_board);
+                assert!(has_other_solution(
+                    &new_board,
+                    &candidate_cache,
+                    &cell,
+                    value
+                ));
+            }
+        }
+
+        let puzzle = Puzzle::generate_with_options(
+            BoardSize::NineByNine,
+            GeneratorOptions {
+                parallel_clue_removal: true,
+                ..Default::default()
+            },
+        );
+        assert!(puzzle.is_solution_unique());
+        let mut board = puzzle.board().clone();
+        board.solve().unwrap();
+        assert_eq!(&board, puzzle.solution());
+    }
+
+    fn rated_puzzle(board: &str) -> Puzzle {
+        let board: Board = board.parse().unwrap();
+        let solution = board.solved_copy().unwrap();
+
+        Puzzle {
+            board,
+            solution,
+            guesses: Default::default(),
+        }
+    }
+
+    fn rated(board: &str) -> Difficulty {
+        rated_puzzle(board).difficulty()
+    }
+
+    #[test]
+    fn difficulty() {
+        assert_eq!(rated("12.4 3412 2143 4.21"), Difficulty::Easy);
+        assert_eq!(
+            rated(
+                "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+            ),
+            Difficulty::Easy
+        );
+        assert_eq!(
+            rated(
+                ".......5.7.2...3.....8.7...96....2...1...4.......51....8...2.67.9......3.....3521"
+            ),
+            Difficulty::Medium
+        );
+        assert_eq!(
+            rated(
+                "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4.."
+            ),
+            Difficulty::Hard
+        );
+    }
+
+    #[test]
+    fn generated_difficulty() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut counts = BTreeMap::new();
+
+        for _ in 0..30 {
+            let puzzle = Puzzle::generate_with_rng(BoardSize::NineByNine, &mut rng);
+            let difficulty = puzzle.difficulty();
+
+            // the default solver only guesses when singles are not enough
+            assert_eq!(difficulty == Difficulty::Easy, !requires_guessing(&puzzle));
+            *counts.entry(difficulty).or_insert(0) += 1;
+        }
+
+        assert!(counts.len() > 1, "{:?}", counts);
+    }
+
+    #[test]
+    fn json() {
+        for board_size in [BoardSize::FourByFour, BoardSize::NineByNine] {
+            let puzzle = Puzzle::generate(board_size);
+            let json = puzzle.to_json_string();
+            let parsed = Puzzle::from_json_string(&json).unwrap();
+
+            assert_eq!(parsed.board(), puzzle.board());
+            assert_eq!(parsed.solution(), puzzle.solution());
+            assert_eq!(parsed.to_json_string(), json);
+        }
+
+        // a few empty cells are enough to check values written as letters
+        let mut solution = Board::new(BoardSize::SixteenBySixteen);
+        solution.fill_diagonal_boxes_randomly();
+        let solution = solution.into_solved().unwrap();
+        let mut board = solution.clone();
+        board.unset(&board.cell_at(0, 0));
+        board.unset(&board.cell_at(15, 15));
+        let json = format!(
+            " {{ \"solution\" : \"{}\",\n\"board\": \"{}\" }} ",
+            solution.to_line_string(),
+            board.to_line_string()
+        );
+        let puzzle = Puzzle::from_json_string(&json).unwrap();
+        assert_eq!(puzzle.board(), &board);
+        assert_eq!(puzzle.solution(), &solution);
+
+        let solution = "1243341221344321";
+        for malformed in [
+            "",
+            "{}",
+            r#"{"board":"................"}"#,
+            r#"{"board":"................","solution":"1243341221344321","extra":""}"#,
+            r#"{"board":"................" "solution":"1243341221344321"}"#,
+            r#"{"board":"...............\"","solution":"1243341221344321"}"#,
+            r#"{"board":"...............x","solution":"1243341221344321"}"#,
+            r#"{"board":"................","solution":"1243341221344321""#,
+        ] {
+            assert_eq!(
+                Puzzle::from_json_string(malformed),
+                Err(PuzzleError::Malformed),
+                "{}",
+                malformed
+            );
+        }
+
+        let json = |board: &str, solution: &str| {
+            Puzzle::from_json_string(&format!(
+                r#"{{"board":"{}","solution":"{}"}}"#,
+                board, solution
+            ))
+        };
+        assert_eq!(
+            json("1...............", "1243341221344.21"),
+            Err(PuzzleError::Incomplete)
+        );
+        assert_eq!(
+            json("1...............", "1243341221344312"),
+            Err(PuzzleError::Invalid)
+        );
+        // valid solution of a board with several solutions
+        assert_eq!(
+            json("1...............", solution),
+            Err(PuzzleError::Mismatch)
+        );
+        // boards of different sizes
+        assert_eq!(
+            json(
+                "1...............",
+                "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+            ),
+            Err(PuzzleError::Mismatch)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn generate_async() {
+        let puzzle = Puzzle::generate_async(BoardSize::NineByNine).await;
+
+        assert_eq!(puzzle.board().board_size(), BoardSize::NineByNine);
+        assert!(puzzle.is_solution_unique());
+        assert!(puzzle.board().validate_solution(puzzle.solution()));
     }
 }
@@ -1,6 +1,7 @@
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexedMap<K, V> {
     size: usize,
+    len: usize,
     values: Vec<V>,
     keys: Vec<Option<K>>,
 }
@@ -9,6 +10,7 @@ impl<K: Indexed + Clone, V: Default + Clone> IndexedMap<K, V> {
     pub fn new(size: usize) -> Self {
         IndexedMap {
             size,
+            len: 0,
             values: vec![V::default(); size],
             keys: vec![None; size],
         }
@@ -22,12 +24,30 @@ pub trait Indexed {
 pub trait Map<K: Indexed, V> {
     fn insert(&mut self, key: K, value: V) -> Option<V>;
     fn remove(&mut self, key: &K) -> Option<V>;
-    fn is_empty(&self) -> bool;
-    fn keys(&self) -> IterSome<K>;
+    fn len(&self) -> usize;
+    fn capacity(&self) -> usize;
+    fn keys(&self) -> IterSome<'_, K>;
+    fn values(&self) -> Values<'_, K, V>;
+    fn keys_copied(&self) -> std::iter::Copied<IterSome<'_, K>>
+    where
+        K: Copy;
     fn get(&self, key: &K) -> Option<&V>;
     fn get_mut(&mut self, key: &K) -> Option<&mut V>;
-    fn entry(&mut self, key: K) -> Entry<K, V>;
-    fn iter(&self) -> Iter<K, V>;
+    fn entry(&mut self, key: K) -> Entry<'_, K, V>;
+    fn iter(&self) -> Iter<'_, K, V>;
+    fn clear(&mut self);
+
+    // the solver doesn't need these, they complete the map API and have their own tests
+    #[allow(dead_code)]
+    fn is_empty(&self) -> bool;
+    #[allow(dead_code)]
+    fn contains_key(&self, key: &K) -> bool;
+    #[allow(dead_code)]
+    fn values_mut(&mut self) -> ValuesMut<'_, K, V>;
+    #[allow(dead_code)]
+    fn iter_mut(&mut self) -> IterMut<'_, K, V>;
+    #[allow(dead_code)]
+    fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F);
 }
 
 pub struct IterSome<'a, T> {
@@ -73,8 +93,7 @@ impl<'a, K, V> Iterator for Iter<'a, K, V> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = self.keys.len();
-        (size, Some(size))
+        (0, Some(self.keys.len().saturating_sub(self.idx)))
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
@@ -84,16 +103,98 @@ impl<'a, K, V> Iterator for Iter<'a, K, V> {
     }
 }
 
+pub struct Values<'a, K, V> {
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, value)| value)
+    }
+}
+
+// only returned by `Map::values_mut`, unused by the solver
+#[allow(dead_code)]
+pub struct ValuesMut<'a, K, V> {
+    iter: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, value)| value)
+    }
+}
+
+// only returned by `Map::iter_mut`, unused by the solver
+#[allow(dead_code)]
+pub struct IterMut<'a, K, V> {
+    inner: std::iter::Zip<std::slice::Iter<'a, Option<K>>, std::slice::IterMut<'a, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, value) in self.inner.by_ref() {
+            if let Some(key) = key {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+pub struct IntoIter<K, V> {
+    inner: std::iter::Zip<std::vec::IntoIter<Option<K>>, std::vec::IntoIter<V>>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        for (key, value) in self.inner.by_ref() {
+            if let Some(key) = key {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<K, V> IntoIterator for IndexedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.keys.into_iter().zip(self.values),
+        }
+    }
+}
+
+impl<'a, K: Indexed, V: Clone + Default> IntoIterator for &'a IndexedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 pub struct Entry<'a, K, V> {
     key: K,
     key_ref: &'a mut Option<K>,
     value: &'a mut V,
+    len: &'a mut usize,
 }
 
 impl<'a, K: Clone, V> Entry<'a, K, V> {
     pub fn or_default(&mut self) -> &mut V {
         if self.key_ref.is_none() {
             *self.key_ref = Some(self.key.clone());
+            *self.len += 1;
         }
 
         self.value
@@ -115,6 +216,7 @@ impl<K: Indexed, V: Clone + Default> Map<K, V> for IndexedMap<K, V> {
         } else {
             self.values[idx] = value;
             self.keys[idx] = Some(key);
+            self.len += 1;
             None
         }
     }
@@ -127,23 +229,53 @@ impl<K: Indexed, V: Clone + Default> Map<K, V> for IndexedMap<K, V> {
 
         if self.keys[idx].is_some() {
             self.keys[idx] = None;
+            self.len -= 1;
             return Some(std::mem::take(&mut self.values[idx]));
         }
 
         None
     }
 
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn capacity(&self) -> usize {
+        self.size
+    }
+
     fn is_empty(&self) -> bool {
-        self.keys.iter().all(|key| key.is_none())
+        self.len == 0
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        matches!(self.keys.get(key.idx()), Some(Some(_)))
     }
 
-    fn keys(&self) -> IterSome<K> {
+    fn keys(&self) -> IterSome<'_, K> {
         IterSome {
             values: &self.keys,
             idx: 0,
         }
     }
 
+    fn values(&self) -> Values<'_, K, V> {
+        Values { iter: self.iter() }
+    }
+
+    fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.iter_mut(),
+        }
+    }
+
+    fn keys_copied(&self) -> std::iter::Copied<IterSome<'_, K>>
+    where
+        K: Copy,
+    {
+        self.keys().copied()
+    }
+
     fn get(&self, key: &K) -> Option<&V> {
         if let Some(Some(_)) = self.keys.get(key.idx()) {
             return Some(&self.values[key.idx()]);
@@ -158,7 +290,7 @@ impl<K: Indexed, V: Clone + Default> Map<K, V> for IndexedMap<K, V> {
         None
     }
 
-    fn entry(&mut self, key: K) -> Entry<K, V> {
+    fn entry(&mut self, key: K) -> Entry<'_, K, V> {
         let idx = key.idx();
         if idx >= self.size {
             panic!("Index out of bounds, index value for key is bigger than the map capacity.");
@@ -167,16 +299,45 @@ impl<K: Indexed, V: Clone + Default> Map<K, V> for IndexedMap<K, V> {
             key,
             key_ref: &mut self.keys[idx],
             value: &mut self.values[idx],
+            len: &mut self.len,
         }
     }
 
-    fn iter(&self) -> Iter<K, V> {
+    fn iter(&self) -> Iter<'_, K, V> {
         Iter {
             idx: 0,
             keys: &self.keys,
             values: &self.values,
         }
     }
+
+    fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.keys.iter().zip(self.values.iter_mut()),
+        }
+    }
+
+    fn clear(&mut self) {
+        for key in self.keys.iter_mut() {
+            *key = None;
+        }
+        for value in self.values.iter_mut() {
+            *value = V::default();
+        }
+        self.len = 0;
+    }
+
+    fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        for idx in 0..self.size {
+            if let Some(key) = &self.keys[idx] {
+                if !f(key, &mut self.values[idx]) {
+                    self.keys[idx] = None;
+                    self.values[idx] = V::default();
+                    self.len -= 1;
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -268,4 +429,198 @@ mod test {
             vec![(&0, &0), (&1, &1)]
         );
     }
+
+    #[test]
+    fn len() {
+        let mut map = IndexedMap::new(4);
+        assert_eq!(map.len(), 0);
+
+        map.insert(0, 0_i32);
+        map.insert(3, 1_i32);
+        assert_eq!(map.len(), 2);
+
+        // replacing an existing key does not change the length
+        map.insert(3, 2_i32);
+        assert_eq!(map.len(), 2);
+
+        map.remove(&0);
+        map.remove(&0);
+        assert_eq!(map.len(), 1);
+
+        map.entry(1).or_default();
+        map.entry(1).or_default();
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn values() {
+        let mut map = IndexedMap::new(4);
+        map.insert(1, 10_i32);
+        map.insert(2, 20_i32);
+
+        assert_eq!(map.values().collect::<Vec<&i32>>(), vec![&10, &20]);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut map = IndexedMap::new(4);
+        map.insert(0, 0_i32);
+        map.insert(2, 2_i32);
+
+        for (key, value) in map.iter_mut() {
+            *value += *key as i32;
+        }
+
+        assert_eq!(
+            map.iter().collect::<Vec<(&usize, &i32)>>(),
+            vec![(&0, &0), (&2, &4)]
+        );
+    }
+
+    #[test]
+    fn contains_key() {
+        let mut map = IndexedMap::new(2);
+        map.insert(1, ());
+
+        assert!(!map.contains_key(&0));
+        assert!(map.contains_key(&1));
+        // keys beyond the capacity of the map are never present
+        assert!(!map.contains_key(&2));
+
+        map.remove(&1);
+        assert!(!map.contains_key(&1));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut map = IndexedMap::new(3);
+        assert_eq!(map.capacity(), 3);
+
+        map.insert(0, ());
+        assert_eq!(map.capacity(), 3);
+    }
+
+    #[test]
+    fn is_empty_after_remove() {
+        let mut map = IndexedMap::new(2);
+        map.insert(0, ());
+        map.insert(1, ());
+        map.remove(&0);
+        assert!(!map.is_empty());
+
+        map.remove(&1);
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut map = IndexedMap::new(4);
+        map.insert(0, String::from("a"));
+        map.insert(3, String::from("b"));
+        let len = map.len();
+
+        let items: Vec<(usize, String)> = map.into_iter().collect();
+
+        assert_eq!(items.len(), len);
+        assert_eq!(items, vec![(0, String::from("a")), (3, String::from("b"))]);
+    }
+
+    #[test]
+    fn into_iter_ref() {
+        let mut map = IndexedMap::new(3);
+        map.insert(1, 1_i32);
+        map.insert(2, 2_i32);
+
+        let mut count = 0;
+        for (key, value) in &map {
+            assert_eq!(*key as i32, *value);
+            count += 1;
+        }
+
+        assert_eq!(count, map.len());
+    }
+
+    #[test]
+    fn retain() {
+        let mut map = IndexedMap::new(4);
+        map.insert(0, vec![1]);
+        map.insert(1, vec![1, 2]);
+        map.insert(3, vec![3]);
+
+        map.retain(|_, value| value.len() > 1);
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&0), None);
+        assert_eq!(map.get(&1), Some(&vec![1, 2]));
+        assert_eq!(map.get(&3), None);
+    }
+
+    #[test]
+    fn retain_can_modify_values() {
+        let mut map = IndexedMap::new(3);
+        map.insert(0, 0_i32);
+        map.insert(1, 1_i32);
+        map.insert(2, 2_i32);
+
+        map.retain(|key, value| {
+            *value *= 10;
+            *key != 1
+        });
+
+        assert_eq!(
+            map.iter().collect::<Vec<(&usize, &i32)>>(),
+            vec![(&0, &0), (&2, &20)]
+        );
+    }
+
+    #[test]
+    fn clear() {
+        let mut map = IndexedMap::new(2);
+        map.insert(0, 0_i32);
+        map.insert(1, 1_i32);
+
+        map.clear();
+
+        assert!(map.is_empty());
+        assert_eq!(map.capacity(), 2);
+        assert_eq!(map.get(&0), None);
+    }
+
+    #[test]
+    fn values_count() {
+        let mut map = IndexedMap::new(5);
+        map.insert(0, 0_i32);
+        map.insert(2, 2_i32);
+        map.insert(4, 4_i32);
+        map.remove(&2);
+        map.insert(1, 1_i32);
+        map.remove(&3);
+
+        assert_eq!(map.values().count(), map.len());
+        assert_eq!(map.values().collect::<Vec<&i32>>(), vec![&0, &1, &4]);
+    }
+
+    #[test]
+    fn values_mut() {
+        let mut map = IndexedMap::new(3);
+        map.insert(0, 1_i32);
+        map.insert(2, 2_i32);
+
+        for value in map.values_mut() {
+            *value *= 10;
+        }
+
+        assert_eq!(map.values_mut().count(), map.len());
+        assert_eq!(map.values().collect::<Vec<&i32>>(), vec![&10, &20]);
+    }
+
+    #[test]
+    fn keys_copied() {
+        let mut map = IndexedMap::new(4);
+        map.insert(1, ());
+        map.insert(3, ());
+
+        assert_eq!(map.keys_copied().collect::<Vec<usize>>(), vec![1, 3]);
+    }
 }
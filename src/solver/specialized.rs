@@ -0,0 +1,356 @@
+//! A solver with fixed size structures for each board width, used by [`Board::solve`].
+//!
+//! It makes the same moves as [`SudokuSolver`](super::SudokuSolver) with the default
+//! options, placing naked and hidden singles until there are none left and then guessing
+//! the smallest value of the first cell with the fewest candidates, so both solvers find
+//! the same solution even for boards with more than one.
+
+use super::{SudokuSolver, UnsolvableError};
+use crate::board::{Board, BoardSize, Variant};
+
+/// Solves the board with the solver specialized for its size, leaving it unchanged
+/// if there is no solution. Boards [`try_solve`] can't handle are solved with the
+/// generic solver.
+pub(super) fn solve(board: &mut Board) -> Result<(), UnsolvableError> {
+    match try_solve(board) {
+        Some(result) => result,
+        None => SudokuSolver::new(board).solve(),
+    }
+}
+
+/// Solves the board with the solver specialized for its size, or returns `None`
+/// without changing it if this solver can't handle the board.
+///
+/// That's the case for boards of any variant other than [`Variant::Standard`], which
+/// this solver doesn't know about, and for boards of [`BoardSize::Custom`] sizes. It's
+/// also the case for boards with givens that conflict with each other or are out of
+/// range: the generic solver keeps conflicting givens and only uses them to rule out
+/// candidates of the empty cells, and reports values out of range.
+pub(super) fn try_solve(board: &mut Board) -> Option<Result<(), UnsolvableError>> {
+    if board.variant() != Variant::Standard {
+        return None;
+    }
+
+    match board.board_size() {
+        BoardSize::FourByFour => SolverImpl::<4>::new(board).map(|solver| solver.solve(board)),
+        BoardSize::NineByNine => SolverImpl::<9>::new(board).map(|solver| solver.solve(board)),
+        BoardSize::SixteenBySixteen => {
+            SolverImpl::<16>::new(board).map(|solver| solver.solve(board))
+        }
+        // the fixed size structures assume square boxes
+        BoardSize::Custom { .. } => None,
+    }
+}
+
+/// The state of a board of width `W` while solving it. It's small enough to be copied
+/// before every guess, so backtracking only needs to drop the copy.
+#[derive(Debug, Clone, Copy)]
+struct SolverImpl<const W: usize> {
+    /// The value of each cell by line and column, 0 for empty cells.
+    values: [[u8; W]; W],
+    /// Bitmasks of the values placed in each line, column and square, where bit `i`
+    /// set means value `i + 1` is placed.
+    lines: [u16; W],
+    cols: [u16; W],
+    squares: [u16; W],
+    empty_cells: usize,
+}
+
+impl<const W: usize> SolverImpl<W> {
+    const BASE: usize = match W {
+        4 => 2,
+        9 => 3,
+        _ => 4,
+    };
+    const ALL_VALUES: u16 = u16::MAX >> (16 - W);
+
+    /// Creates the solver with the board's givens, returns `None` if any of them conflict
+    /// or are out of range.
+    fn new(board: &Board) -> Option<Self> {
+        let mut solver = SolverImpl {
+            values: [[0; W]; W],
+            lines: [0; W],
+            cols: [0; W],
+            squares: [0; W],
+            empty_cells: W * W,
+        };
+
+        for cell in board.iter_cells() {
+            if let Some(value) = board.get(&cell) {
+                if !(1..=W as u8).contains(&value) {
+                    return None;
+                }
+                let mask = 1 << (value - 1);
+                if solver.candidates(cell.line(), cell.col()) & mask == 0 {
+                    return None;
+                }
+                solver.place(cell.line(), cell.col(), mask);
+            }
+        }
+
+        Some(solver)
+    }
+
+    fn solve(mut self, board: &mut Board) -> Result<(), UnsolvableError> {
+        if !self.search() {
+            return Err(UnsolvableError);
+        }
+
+        for (l, line) in self.values.iter().enumerate() {
+            for (c, value) in line.iter().enumerate() {
+                board.set_at(l, c, *value);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn square(l: usize, c: usize) -> usize {
+        l / Self::BASE * Self::BASE + c / Self::BASE
+    }
+
+    /// Returns the line and column of the cell at `position` of a line (`block` 0),
+    /// column (`block` 1) or square (`block` 2).
+    fn cell_in_block(block: usize, idx: usize, position: usize) -> (usize, usize) {
+        match block {
+            0 => (idx, position),
+            1 => (position, idx),
+            _ => (
+                idx / Self::BASE * Self::BASE + position / Self::BASE,
+                idx % Self::BASE * Self::BASE + position % Self::BASE,
+            ),
+        }
+    }
+
+    fn candidates(&self, l: usize, c: usize) -> u16 {
+        !(self.lines[l] | self.cols[c] | self.squares[Self::square(l, c)]) & Self::ALL_VALUES
+    }
+
+    /// Places the value with the single bit set in `mask`, which must be a candidate.
+    fn place(&mut self, l: usize, c: usize, mask: u16) {
+        debug_assert_eq!(mask.count_ones(), 1);
+        debug_assert_ne!(self.candidates(l, c) & mask, 0);
+
+        self.values[l][c] = mask.trailing_zeros() as u8 + 1;
+        self.lines[l] |= mask;
+        self.cols[c] |= mask;
+        self.squares[Self::square(l, c)] |= mask;
+        self.empty_cells -= 1;
+    }
+
+    /// Places naked and hidden singles until there are none left, returns false if a cell
+    /// is left without candidates or a value can't be placed anywhere in a block.
+    fn propagate(&mut self) -> bool {
+        loop {
+            let empty_cells = self.empty_cells;
+
+            for idx in 0..W * W {
+                let (l, c) = (idx / W, idx % W);
+                if self.values[l][c] != 0 {
+                    continue;
+                }
+
+                let candidates = self.candidates(l, c);
+                match candidates.count_ones() {
+                    0 => return false,
+                    1 => self.place(l, c, candidates),
+                    _ => (),
+                }
+            }
+
+            for block in 0..3 {
+                for idx in 0..W {
+                    // values that are candidates in at least one and in more than one cell
+                    let (mut once, mut twice) = (0, 0);
+                    for position in 0..W {
+                        let (l, c) = Self::cell_in_block(block, idx, position);
+                        if self.values[l][c] == 0 {
+                            let candidates = self.candidates(l, c);
+                            twice |= once & candidates;
+                            once |= candidates;
+                        }
+                    }
+
+                    let placed = match block {
+                        0 => self.lines[idx],
+                        1 => self.cols[idx],
+                        _ => self.squares[idx],
+                    };
+                    if once | placed != Self::ALL_VALUES {
+                        return false;
+                    }
+
+                    let singles = once & !twice;
+                    if singles == 0 {
+                        continue;
+                    }
+
+                    for position in 0..W {
+                        let (l, c) = Self::cell_in_block(block, idx, position);
+                        if self.values[l][c] != 0 {
+                            continue;
+                        }
+
+                        match self.candidates(l, c) & singles {
+                            0 => (),
+                            mask if mask.count_ones() == 1 => self.place(l, c, mask),
+                            // the cell is the only one left for two different values
+                            _ => return false,
+                        }
+                    }
+                }
+            }
+
+            if self.empty_cells == empty_cells {
+                return true;
+            }
+        }
+    }
+
+    /// Solves the rest of the board, returns false leaving it in any state if there
+    /// is no solution.
+    fn search(&mut self) -> bool {
+        if !self.propagate() {
+            return false;
+        }
+
+        // guess the first cell with the fewest candidates, singles were already placed
+        // so the search can stop at the first cell with two
+        let mut best: Option<(usize, usize, u32)> = None;
+        for idx in 0..W * W {
+            let (l, c) = (idx / W, idx % W);
+            if self.values[l][c] != 0 {
+                continue;
+            }
+
+            let count = self.candidates(l, c).count_ones();
+            if best.map_or(true, |(_, _, best_count)| count < best_count) {
+                best = Some((l, c, count));
+                if count <= 2 {
+                    break;
+                }
+            }
+        }
+
+        let (l, c) = match best {
+            Some((l, c, _)) => (l, c),
+            None => return true,
+        };
+
+        let mut candidates = self.candidates(l, c);
+        while candidates != 0 {
+            let mask = candidates & candidates.wrapping_neg();
+            candidates &= !mask;
+
+            let mut guess = *self;
+            guess.place(l, c, mask);
+            if guess.search() {
+                *self = guess;
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardSize};
+    use crate::solver::generator::Puzzle;
+    use crate::solver::{SudokuSolver, UnsolvableError};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Puzzle generated by `Puzzle::generate_with_rng` for a 16x16 board with seed 4.
+    const SIXTEEN_BY_SIXTEEN: &str = "\
+,,15,,,6,,10,,,,,,,,
+,,,3,16,,,,,,,6,14,,,
+,,7,6,,,,,,,4,,9,12,,
+9,,2,,4,,15,,,7,11,,,1,,
+,4,1,,,,,,11,,,13,,,,5
+,3,,2,,,8,,5,,,14,,11,1,
+,12,11,,,,13,1,16,,,,,,14,3
+,,,,12,5,,15,,10,,,,,,
+10,7,4,,,2,,,,,6,16,,,15,
+3,,,14,9,,,,,2,7,,4,,,
+,2,5,,,12,14,,,,3,15,13,6,16,8
+,,,,,,,8,12,13,5,1,7,,,
+,,,,3,7,,5,,,,,,,11,
+,,,,,9,,4,,,,,,15,,12
+,,,,,14,,11,,,,,6,4,5,9
+,,,,,,,,,,15,,1,16,13,2
+";
+
+    fn assert_same_solution(puzzle: &Board) {
+        let mut expected = puzzle.clone();
+        let expected_result = SudokuSolver::new(&mut expected).solve();
+
+        let mut board = puzzle.clone();
+        assert_eq!(super::solve(&mut board), expected_result);
+        assert_eq!(board, expected, "different solutions for\n{}", puzzle);
+    }
+
+    #[test]
+    fn same_solution_as_generic_solver() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut puzzles = vec![Board::from_csv(SIXTEEN_BY_SIXTEEN).unwrap()];
+
+        for board_size in [BoardSize::FourByFour, BoardSize::NineByNine] {
+            for _ in 0..10 {
+                puzzles.push(
+                    Puzzle::generate_with_rng(board_size, &mut rng)
+                        .board()
+                        .clone(),
+                );
+            }
+        }
+
+        // boards with many solutions, to check both solvers pick the same one
+        for board_size in [
+            BoardSize::FourByFour,
+            BoardSize::NineByNine,
+            BoardSize::SixteenBySixteen,
+        ] {
+            let mut solution = Board::new(board_size);
+            solution.solve().unwrap();
+            puzzles.push(Board::new(board_size));
+
+            for removed_ratio in [0.3, 0.6, 0.9] {
+                let mut board = solution.clone();
+                for cell in solution.iter_cells() {
+                    if rng.gen_bool(removed_ratio) {
+                        board.unset(&cell);
+                    }
+                }
+                puzzles.push(board);
+            }
+        }
+
+        for puzzle in &puzzles {
+            assert_same_solution(puzzle);
+        }
+    }
+
+    #[test]
+    fn unsolvable() {
+        for puzzle in ["11.. .... .... ....", "123. ...4 .... ...."] {
+            let puzzle: Board = puzzle.parse().unwrap();
+            let mut board = puzzle.clone();
+
+            assert_eq!(super::solve(&mut board), Err(UnsolvableError));
+            assert_eq!(board, puzzle);
+            assert_same_solution(&puzzle);
+        }
+    }
+
+    #[test]
+    fn conflicting_givens() {
+        let puzzle: Board = "3124 2413 4.33 1342".parse().unwrap();
+        let mut board = puzzle.clone();
+
+        assert_eq!(super::solve(&mut board), Ok(()));
+        assert_eq!(board, "3124 2413 4233 1342".parse().unwrap());
+        assert_same_solution(&puzzle);
+    }
+}
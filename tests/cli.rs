@@ -0,0 +1,87 @@
+#![cfg(feature = "cli")]
+
+use assert_cmd::Command;
+use serde_json::Value;
+use sudokugen::Board;
+
+#[test]
+fn solve_json() {
+    let input = "\
+...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6..
+this is not a board
+
+123. ...4 .... ....
+";
+
+    let output = Command::cargo_bin("sudokugen")
+        .unwrap()
+        .args(["solve", "--format", "json"])
+        .write_stdin(input)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let results: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+
+    assert_eq!(results[0]["status"], "solved");
+    assert_eq!(
+        results[0]["solution"],
+        "695412873413879526287653419146235987728946135359187264561398742872564391934721658"
+    );
+    assert!(results[0]["millis"].is_u64());
+
+    assert_eq!(results[1]["status"], "invalid");
+    assert_eq!(results[1]["input"], "this is not a board");
+    assert!(results[1]["solution"].is_null());
+
+    assert_eq!(results[2]["status"], "unsolvable");
+    assert!(results[2]["solution"].is_null());
+}
+
+#[test]
+fn solve_text() {
+    Command::cargo_bin("sudokugen")
+        .unwrap()
+        .arg("solve")
+        .write_stdin("1... .... .... ....\n123. ...4 .... ....\n")
+        .assert()
+        .success()
+        .stdout("1234341221434321\nunsolvable\n");
+}
+
+#[test]
+fn generate_json() {
+    let output = Command::cargo_bin("sudokugen")
+        .unwrap()
+        .args(["gen", "--count", "3", "--size", "4x4", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let results: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+
+    for result in results {
+        let puzzle: Board = result["puzzle"].as_str().unwrap().parse().unwrap();
+        let solution: Board = result["solution"].as_str().unwrap().parse().unwrap();
+
+        let clues = puzzle
+            .iter_cells()
+            .filter(|cell| puzzle.get(cell).is_some())
+            .count();
+        assert_eq!(result["clues"], clues);
+        assert_eq!(puzzle.into_solved().unwrap(), solution);
+    }
+}
+
+#[test]
+fn invalid_arguments() {
+    Command::cargo_bin("sudokugen")
+        .unwrap()
+        .args(["gen", "--format", "yaml"])
+        .assert()
+        .failure();
+}
@@ -1,4 +1,8 @@
-use sudokugen::{Board, Puzzle};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sudokugen::solver::generator::PuzzleError;
+use sudokugen::solver::{solve_all, solve_lines, SolveLineError, Solver};
+use sudokugen::{Board, BoardSize, Puzzle};
 
 #[test]
 fn solve_sudoku_simple() {
@@ -85,3 +89,174 @@ fn generate_test() {
 
     assert!(puzzle.is_solution_unique());
 }
+
+#[test]
+fn reused_solver_matches_board_solve() {
+    let puzzles: Vec<Board> = (0..20)
+        .map(|_| Puzzle::generate(BoardSize::NineByNine).board().clone())
+        .chain(vec![
+            "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+                .parse()
+                .unwrap(),
+            "123. ...4 .... ....".parse().unwrap(),
+            Board::new(BoardSize::FourByFour),
+        ])
+        .collect();
+
+    let mut solver = Solver::with_capacity(BoardSize::NineByNine);
+
+    for puzzle in puzzles {
+        let mut expected = puzzle.clone();
+        let expected_result = expected.solve();
+
+        let mut board = puzzle.clone();
+        assert_eq!(solver.solve(&mut board), expected_result);
+        if expected_result.is_ok() {
+            assert_eq!(board, expected);
+        }
+    }
+}
+
+#[test]
+fn solve_many_lines() {
+    let puzzle: Board =
+        "...4..87.4.3......2....3..9..62....7...9.6...3.9.8...........4.8725........72.6.."
+            .parse()
+            .unwrap();
+
+    let lines: Vec<String> = (0..100)
+        .map(|idx| match idx {
+            10 => String::from("this is not a board"),
+            50 => String::from("...4..87.4.3......2...."),
+            70 => String::from("123. ...4 .... ...."),
+            _ => puzzle.rotated_n(idx as u8).to_line_string(),
+        })
+        .collect();
+
+    let results = solve_lines(&lines.join("\n"));
+    assert_eq!(results.len(), 100);
+
+    for (idx, result) in results.into_iter().enumerate() {
+        let line = idx + 1;
+        match idx {
+            10 | 50 => assert!(matches!(
+                result,
+                Err(SolveLineError::Malformed { line: l, .. }) if l == line
+            )),
+            70 => assert_eq!(result, Err(SolveLineError::Unsolvable { line })),
+            _ => {
+                let mut solution = puzzle.rotated_n(idx as u8);
+                solution.solve().unwrap();
+                assert_eq!(result, Ok(solution));
+            }
+        }
+    }
+}
+
+#[test]
+fn solve_all_keeps_order() {
+    let boards: Vec<Board> = (0..20)
+        .map(|_| Puzzle::generate(BoardSize::FourByFour).board().clone())
+        .collect();
+
+    let solutions = solve_all(boards.clone());
+
+    for (board, solution) in boards.into_iter().zip(solutions) {
+        let mut expected = board.clone();
+        expected.solve().unwrap();
+        assert_eq!(solution, Ok(expected));
+    }
+}
+
+#[test]
+fn puzzle_from_solution() {
+    let solution: Board =
+        "572491386318726495469583172921348567754962813683157249146275938237819654895634721"
+            .parse()
+            .unwrap();
+
+    let puzzle = Puzzle::from_solution(&solution, &mut StdRng::seed_from_u64(42)).unwrap();
+    assert_eq!(puzzle.solution(), &solution);
+    assert!(puzzle.is_solution_unique());
+
+    let mut board = puzzle.board().clone();
+    board.solve().unwrap();
+    assert_eq!(board, solution);
+
+    let mut incomplete = solution.clone();
+    incomplete.unset(&incomplete.cell_at(3, 3));
+    assert_eq!(
+        Puzzle::from_solution(&incomplete, &mut StdRng::seed_from_u64(42)),
+        Err(PuzzleError::Incomplete)
+    );
+
+    let mut invalid = solution;
+    invalid.set_at(3, 3, 1);
+    assert_eq!(
+        Puzzle::from_solution(&invalid, &mut StdRng::seed_from_u64(42)),
+        Err(PuzzleError::Invalid)
+    );
+}
+
+/// Solves every puzzle of a file with one puzzle per line, returning how many of them
+/// could be solved without guessing.
+fn solve_dataset(input: &str) -> usize {
+    let mut logic_only = 0;
+
+    for line in input.lines() {
+        let puzzle: Board = line.parse().unwrap();
+
+        let mut solution = puzzle.clone();
+        solution.solve().unwrap();
+        assert!(puzzle.validate_solution(&solution), "{}", line);
+        assert_eq!(puzzle.has_unique_solution(), Ok(true), "{}", line);
+
+        if puzzle.clone().solve_logical().is_ok() {
+            logic_only += 1;
+        }
+    }
+
+    logic_only
+}
+
+#[test]
+fn solve_top95_puzzles() {
+    let input = include_str!("data/top95.txt");
+    assert_eq!(input.lines().count(), 95);
+
+    // the set is meant to be hard, none of its puzzles can be solved with the
+    // strategies of the solver alone, a change here means the strategies changed
+    let logic_only = solve_dataset(input);
+    println!(
+        "top95: {} solved by logic, {} needed guessing",
+        logic_only,
+        95 - logic_only
+    );
+    assert_eq!(logic_only, 0);
+}
+
+#[test]
+fn solve_hardest_puzzles() {
+    let input = include_str!("data/hardest.txt");
+    assert_eq!(input.lines().count(), 11);
+
+    let logic_only = solve_dataset(input);
+    println!(
+        "hardest: {} solved by logic, {} needed guessing",
+        logic_only,
+        11 - logic_only
+    );
+    assert_eq!(logic_only, 1);
+}
+
+#[test]
+fn solve_fixture_file() {
+    let input = include_str!("data/puzzles.txt");
+    let results = solve_lines(input);
+    assert_eq!(results.len(), 200);
+
+    for (line, result) in input.lines().zip(results) {
+        let board: Board = line.parse().unwrap();
+        assert!(board.validate_solution(&result.unwrap()), "{}", line);
+    }
+}
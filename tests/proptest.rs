@@ -0,0 +1,49 @@
+#![cfg(feature = "arbitrary")]
+
+use proptest::prelude::*;
+use sudokugen::board::{Board, BoardParams};
+use sudokugen::solver::generator::solvable_boards;
+use sudokugen::{BoardSize, Puzzle};
+
+proptest! {
+    // values above 9 in 16x16 boards can't be parsed back yet
+    #[test]
+    fn line_string_round_trip(
+        board in prop_oneof![Just(BoardSize::FourByFour), Just(BoardSize::NineByNine)]
+            .prop_flat_map(|board_size| any_with::<Board>(BoardParams {
+                board_size: Some(board_size),
+                ..BoardParams::default()
+            }))
+    ) {
+        prop_assert_eq!(board.to_line_string().parse::<Board>().unwrap(), board);
+    }
+
+    #[test]
+    fn empty_boards(board in any_with::<Board>(BoardParams { board_size: None, fill_ratio: 0.0 })) {
+        prop_assert!(board.is_empty());
+    }
+
+    #[test]
+    fn full_boards(board in any_with::<Board>(BoardParams { board_size: None, fill_ratio: 1.0 })) {
+        prop_assert!(board.iter_cells().all(|cell| board.get(&cell).is_some()));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn solve_generated_puzzle(puzzle in any_with::<Puzzle>(Some(BoardSize::NineByNine))) {
+        let mut board = puzzle.board().clone();
+        prop_assert!(board.solve().is_ok());
+        prop_assert_eq!(&board, puzzle.solution());
+    }
+
+    #[test]
+    fn solve_solvable_board(mut board in solvable_boards(None)) {
+        let clues = board.clone();
+        prop_assert!(board.solve().is_ok());
+
+        prop_assert!(board.is_completion_of(&clues));
+    }
+}